@@ -0,0 +1,124 @@
+//! Compares the peak heap use of chunked and unchunked hex aggregation.
+//!
+//! Run with `cargo bench --bench chunked_peak_memory`. The counting global
+//! allocator is installed for this binary only and the runs happen one after
+//! another, so no other work skews the readings.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use arrow_array::{RecordBatch, UInt32Array};
+use geojson::{Feature, Geometry, Value};
+use infra_hex_rs::{
+    CadentPipelineRecord, GeoPoint2d, HexSummaryOptions, to_hex_summary_with_options,
+};
+
+/// Tracks live and peak heap bytes across every thread.
+struct PeakAllocator;
+
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let live = LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK_BYTES.fetch_max(live, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAllocator = PeakAllocator;
+
+/// Peak heap bytes allocated while running `f`, above what was live when it
+/// started.
+fn peak_bytes_during<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let start = LIVE_BYTES.load(Ordering::Relaxed);
+    PEAK_BYTES.store(start, Ordering::Relaxed);
+    let result = f();
+    (
+        result,
+        PEAK_BYTES.load(Ordering::Relaxed).saturating_sub(start),
+    )
+}
+
+/// A dense grid of 2,000 long pipes around central Manchester.
+fn make_records() -> Vec<CadentPipelineRecord> {
+    (0..2_000)
+        .map(|i| {
+            let lat = 53.45 + (i % 100) as f64 * 0.0005;
+            let lon = -2.30 + (i / 100) as f64 * 0.005;
+            let coords = (0..200)
+                .map(|j| vec![lon + j as f64 * 0.0005, lat + (j % 2) as f64 * 0.0002])
+                .collect();
+            CadentPipelineRecord {
+                geo_point_2d: GeoPoint2d { lon, lat },
+                geo_shape: Feature {
+                    geometry: Some(Geometry::new(Value::LineString(coords))),
+                    ..Default::default()
+                },
+                pipe_type: None,
+                pressure: None,
+                material: None,
+                diameter: None,
+                diam_unit: None,
+                carr_mat: None,
+                carr_dia: None,
+                carr_di_un: None,
+                asset_id: Some(i.to_string()),
+                depth: None,
+                ag_ind: None,
+                inst_date: None,
+            }
+        })
+        .collect()
+}
+
+fn total_count(batch: &RecordBatch) -> u32 {
+    batch
+        .column_by_name("pipe_count")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<UInt32Array>()
+        .unwrap()
+        .iter()
+        .flatten()
+        .sum()
+}
+
+fn main() {
+    let records = make_records();
+    let options = HexSummaryOptions::new().with_geometry(false);
+
+    let (unchunked, unchunked_peak) =
+        peak_bytes_during(|| to_hex_summary_with_options(&records, 12, &(), &options).unwrap());
+    println!(
+        "unchunked:   {} rows, peak {} KiB",
+        unchunked.num_rows(),
+        unchunked_peak / 1024
+    );
+
+    let mut smallest_peak = usize::MAX;
+    for chunk_size in [1_000, 250, 50] {
+        let options = options.clone().with_chunk_size(chunk_size);
+        let (chunked, peak) =
+            peak_bytes_during(|| to_hex_summary_with_options(&records, 12, &(), &options).unwrap());
+        println!("chunk {:>5}: peak {} KiB", chunk_size, peak / 1024);
+
+        assert_eq!(chunked.num_rows(), unchunked.num_rows());
+        assert_eq!(total_count(&chunked), total_count(&unchunked));
+        smallest_peak = smallest_peak.min(peak);
+    }
+
+    assert!(
+        smallest_peak < unchunked_peak,
+        "chunking did not lower peak memory: {} KiB >= {} KiB",
+        smallest_peak / 1024,
+        unchunked_peak / 1024
+    );
+}
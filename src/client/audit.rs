@@ -0,0 +1,137 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::client::types::BBox;
+use crate::error::InfraHexError;
+
+/// One line of an audit log, describing a single completed fetch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the fetch finished, in milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// Dataset the records were pulled from.
+    pub dataset: String,
+    /// Requested bounding box as `[min_lon, min_lat, max_lon, max_lat]`.
+    pub bbox: [f64; 4],
+    /// Number of records returned.
+    pub record_count: usize,
+    /// Number of errors collected while fetching.
+    pub error_count: usize,
+    /// Wall-clock time the fetch took, in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl AuditEntry {
+    /// Builds an entry for a fetch that has just finished.
+    pub fn new(
+        dataset: impl Into<String>,
+        bbox: &BBox,
+        record_count: usize,
+        error_count: usize,
+        duration: Duration,
+    ) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+
+        Self {
+            timestamp_ms,
+            dataset: dataset.into(),
+            bbox: [bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat],
+            record_count,
+            error_count,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+/// An append-only JSONL file recording what data was fetched and when.
+///
+/// Each [`AuditLog::append`] writes exactly one JSON object followed by a
+/// newline. The file is opened in append mode for every write, so existing
+/// lines are never rewritten, and writes through the same `AuditLog` are
+/// serialised by a lock so concurrent fetches never interleave lines. Share
+/// one `AuditLog` (as an `Arc`) between clients writing to the same file.
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    /// Creates a log writing to `path`. The file is created on first write.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends `entry` as a single JSON line.
+    pub fn append(&self, entry: &AuditEntry) -> Result<(), InfraHexError> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+        // One write call per line, so other processes appending to the same
+        // file can't split it
+        file.write_all(&line)
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_log_appends_lines() {
+        let path = std::env::temp_dir().join(format!(
+            "infra-hex-rs-audit-{}-append.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let log = AuditLog::new(&path);
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        log.append(&AuditEntry::new(
+            "a",
+            &bbox,
+            10,
+            0,
+            Duration::from_millis(5),
+        ))
+        .unwrap();
+        log.append(&AuditEntry::new("b", &bbox, 0, 1, Duration::from_millis(7)))
+            .unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let entries: Vec<AuditEntry> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].dataset, "a");
+        assert_eq!(entries[0].bbox, [-2.26, 53.47, -2.22, 53.49]);
+        assert_eq!(entries[1].error_count, 1);
+        assert_eq!(entries[1].duration_ms, 7);
+    }
+}
@@ -1,13 +1,25 @@
 use geo_types::{MultiPolygon, Polygon};
 use geojson::{Feature, FeatureCollection, Geometry as GeoJsonGeometry};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::core::{FromGeoJson, ToGeoJson};
 use crate::error::InfraHexError;
 
-use super::types::HttpClient;
+use serde::Deserialize;
+
+use super::pagination::{PaginationConfig, fetch_all_pages};
+use super::rate_limit::RateLimiter;
+use super::retry::RetryConfig;
+use super::types::{BBox, HttpClient, InfraResult};
 
 const BASE_URL: &str = "https://services1.arcgis.com/ESMARspQHYMw9BZ9/arcgis/rest/services/main_ONS_BUA_2024_EW/FeatureServer/0/query";
 
+/// OBJECTIDs per `IN (...)` query issued by
+/// [`BuiltUpAreaClient::fetch_by_object_ids`], keeping URLs well under
+/// server length limits.
+const OBJECT_ID_CHUNK: usize = 100;
+
 /// Represents a Built-Up Area (BUA) boundary from the ONS 2024 dataset.
 ///
 /// # Fields
@@ -154,6 +166,16 @@ pub fn polygon_to_geojson(polygon: &Polygon<f64>) -> GeoJsonGeometry {
 /// ```
 pub struct BuiltUpAreaClient {
     http: HttpClient,
+    base_url: String,
+    geometry_precision: Option<u32>,
+    out_fields: Option<Vec<String>>,
+    pagination: PaginationConfig,
+}
+
+/// Response to a `returnCountOnly=true` query.
+#[derive(Deserialize)]
+struct CountResponse {
+    count: usize,
 }
 
 impl BuiltUpAreaClient {
@@ -163,9 +185,107 @@ impl BuiltUpAreaClient {
     pub fn new() -> Self {
         Self {
             http: HttpClient::new(),
+            base_url: BASE_URL.to_string(),
+            geometry_precision: None,
+            out_fields: None,
+            pagination: PaginationConfig::arcgis(),
         }
     }
 
+    /// Paces every request through a shared [`RateLimiter`].
+    ///
+    /// See [`HttpClient::with_rate_limiter`].
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.http = self.http.with_rate_limiter(limiter);
+        self
+    }
+
+    /// Sets the connect and overall request timeout, 30 seconds by default.
+    ///
+    /// See [`HttpClient::with_timeout`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.with_timeout(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    ///
+    /// See [`HttpClient::with_user_agent`]; returns `InfraHexError::Config`
+    /// for a value that isn't a valid header.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Result<Self, InfraHexError> {
+        self.http = self.http.with_user_agent(user_agent)?;
+        Ok(self)
+    }
+
+    /// Retries transient request failures with exponential backoff.
+    ///
+    /// See [`HttpClient::with_retry`].
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.http = self.http.with_retry(retry);
+        self
+    }
+
+    /// Limits returned coordinates to `digits` decimal places.
+    ///
+    /// By default the client asks the Feature Server for full-precision,
+    /// ungeneralised geometry (`maxAllowableOffset=0`), since a simplified
+    /// boundary can misassign hexes along its edge when used for clipping.
+    /// The trade-off is a bigger payload: large areas such as Manchester run
+    /// to tens of thousands of vertices. Setting a precision (e.g. 6 digits,
+    /// roughly 0.1 m in WGS84) trims the response size without generalising
+    /// the boundary.
+    pub fn with_geometry_precision(mut self, digits: u32) -> Self {
+        self.geometry_precision = Some(digits);
+        self
+    }
+
+    /// Requests only the given attribute fields (`outFields`) instead of all
+    /// of them.
+    ///
+    /// Geometry is always returned, as is `OBJECTID`, which every
+    /// [`BuiltUpArea`] needs; fields left out (e.g. `BUA24NMW` or
+    /// `areahectar`) come back empty. Useful when only boundaries are wanted,
+    /// e.g. `with_out_fields(&["BUA24NM"])`.
+    pub fn with_out_fields(mut self, fields: &[&str]) -> Self {
+        self.out_fields = Some(fields.iter().map(|f| f.to_string()).collect());
+        self
+    }
+
+    /// Sets how [`BuiltUpAreaClient::fetch_all`] pages through results.
+    ///
+    /// Defaults to [`PaginationConfig::arcgis`]. Keep the page size at or
+    /// below the server's `maxRecordCount`.
+    pub fn with_pagination(mut self, config: PaginationConfig) -> Self {
+        self.pagination = config;
+        self
+    }
+
+    /// Builds a query URL for an unencoded `where` clause, requesting the
+    /// configured fields (every field by default) and ungeneralised GeoJSON
+    /// geometry.
+    fn query_url(&self, where_clause: &str) -> String {
+        let out_fields = match &self.out_fields {
+            Some(fields) => {
+                let mut fields = fields.clone();
+                if !fields.iter().any(|f| f == "OBJECTID") {
+                    fields.insert(0, "OBJECTID".to_string());
+                }
+                urlencoding::encode(&fields.join(",")).into_owned()
+            }
+            None => "*".to_string(),
+        };
+        let mut url = format!(
+            "{}?where={}&outFields={}&maxAllowableOffset=0&f=geojson",
+            self.base_url,
+            urlencoding::encode(where_clause),
+            out_fields
+        );
+        if let Some(digits) = self.geometry_precision {
+            url.push_str(&format!("&geometryPrecision={}", digits));
+        }
+        url
+    }
+
     /// Fetches a built-up area by its ONS OBJECTID.
     ///
     /// The OBJECTID is a unique identifier assigned by the ONS Feature Server.
@@ -181,6 +301,8 @@ impl BuiltUpAreaClient {
     /// Returns an error if:
     /// - The HTTP request fails ([`InfraHexError::Http`])
     /// - No area exists with the given OBJECTID ([`InfraHexError::Api`])
+    /// - More than one feature is returned for the OBJECTID, which indicates
+    ///   a data error at source ([`InfraHexError::Api`])
     /// - The response geometry is invalid ([`InfraHexError::Geometry`])
     ///
     /// # Example
@@ -197,21 +319,284 @@ impl BuiltUpAreaClient {
     /// # }
     /// ```
     pub async fn fetch_by_object_id(&self, object_id: i64) -> Result<BuiltUpArea, InfraHexError> {
-        let url = format!(
-            "{}?where=OBJECTID%3D{}&outFields=*&f=geojson",
-            BASE_URL, object_id
-        );
+        let url = self.query_url(&format!("OBJECTID={}", object_id));
 
         let fc: FeatureCollection = self.http.fetch_json(&url).await?;
+        parse_single_feature(&fc, object_id)
+    }
 
-        if fc.features.is_empty() {
+    /// Fetches several built-up areas by OBJECTID in batched queries.
+    ///
+    /// Issues `OBJECTID IN (...)` queries of up to 100 ids each instead of one
+    /// request per id. Duplicate ids are fetched once, the result is sorted by
+    /// OBJECTID, and ids with no matching area are simply absent (no error).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use infra_hex_rs::BuiltUpAreaClient;
+    /// # async fn example() -> Result<(), infra_hex_rs::InfraHexError> {
+    /// let client = BuiltUpAreaClient::new();
+    /// let areas = client.fetch_by_object_ids(&[1310, 1311, 1312]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_by_object_ids(
+        &self,
+        object_ids: &[i64],
+    ) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        let mut areas = Vec::new();
+
+        for where_clause in object_id_predicates(object_ids, OBJECT_ID_CHUNK) {
+            areas.extend(self.fetch_where(&where_clause).await?);
+        }
+
+        areas.sort_by_key(|area| area.object_id);
+        Ok(areas)
+    }
+
+    /// Fetches every built-up area matching an ArcGIS SQL `where` clause.
+    ///
+    /// This is the building block for the multi-result lookups. Matches are
+    /// counted and paged through like [`BuiltUpAreaClient::fetch_all`], so
+    /// the [`BuiltUpAreaClient::with_pagination`] settings apply and large
+    /// result sets aren't truncated at the server's transfer limit. A
+    /// well-formed query that matches nothing returns `Ok(vec![])` rather
+    /// than an error, since "no results" is a normal outcome for a search.
+    /// Errors are reserved for HTTP failures and responses that can't be
+    /// parsed (which is also how the Feature Server reports a malformed
+    /// query); the first failed page fails the whole call.
+    ///
+    /// # Arguments
+    ///
+    /// * `where_clause` - An unencoded ArcGIS SQL expression, e.g. `BUA24CD = 'E63000001'`
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use infra_hex_rs::BuiltUpAreaClient;
+    /// # async fn example() -> Result<(), infra_hex_rs::InfraHexError> {
+    /// let client = BuiltUpAreaClient::new();
+    /// let areas = client.fetch_where("BUA24NM = 'Manchester'").await?;
+    /// for area in &areas {
+    ///     println!("{} ({})", area.name, area.object_id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_where(&self, where_clause: &str) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        let result = self.fetch_all_paged(where_clause, "").await;
+        match result.errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(result.records),
+        }
+    }
+
+    /// Fetches every built-up area with the given English name (`BUA24NM`).
+    ///
+    /// Names aren't unique, so all matches are returned, sorted by OBJECTID;
+    /// there are several Newports, for example. Matching uses SQL `LIKE` and
+    /// ignores case, so `%` and `_` act as wildcards: `"Newport%"` also finds
+    /// "Newport Pagnell". See [`BuiltUpAreaClient::fetch_by_welsh_name`] to
+    /// search the Welsh names instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InfraHexError::Api`] if no area matches `name`, besides the
+    /// errors of [`BuiltUpAreaClient::fetch_where`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use infra_hex_rs::BuiltUpAreaClient;
+    /// # async fn example() -> Result<(), infra_hex_rs::InfraHexError> {
+    /// let client = BuiltUpAreaClient::new();
+    /// let areas = client.fetch_by_name("Manchester").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_by_name(&self, name: &str) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        self.fetch_by_name_field("BUA24NM", name).await
+    }
+
+    /// Like [`BuiltUpAreaClient::fetch_by_name`], but searches the Welsh
+    /// names (`BUA24NMW`), e.g. `"Casnewydd"` for Newport. Only areas in
+    /// Wales have a Welsh name.
+    pub async fn fetch_by_welsh_name(&self, name: &str) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        self.fetch_by_name_field("BUA24NMW", name).await
+    }
+
+    async fn fetch_by_name_field(
+        &self,
+        field: &str,
+        name: &str,
+    ) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        let areas = self.fetch_where(&name_predicate(field, name)).await?;
+        if areas.is_empty() {
             return Err(InfraHexError::Api(format!(
-                "No built-up area found with OBJECTID: {}",
-                object_id
+                "No built-up area found with {} matching {:?}",
+                field, name
+            )));
+        }
+        Ok(areas)
+    }
+
+    /// Fetches every built-up area in England and Wales.
+    ///
+    /// Handy for building a local name to OBJECTID index. A
+    /// `returnCountOnly` query gets the total first, then pages are fetched
+    /// in parallel batches with `resultOffset`/`resultRecordCount`, as set by
+    /// [`BuiltUpAreaClient::with_pagination`]. Failed pages are collected in
+    /// `InfraResult::errors` alongside the areas that did arrive. Every area
+    /// comes with its full boundary, so consider
+    /// [`BuiltUpAreaClient::with_geometry_precision`] to trim the download.
+    pub async fn fetch_all(&self) -> InfraResult<BuiltUpArea> {
+        self.fetch_all_paged("1=1", "").await
+    }
+
+    /// Fetches every built-up area intersecting a WGS84 bounding box, e.g.
+    /// to overlay BUAs on a map viewport.
+    ///
+    /// The box is sent as an ArcGIS envelope with `spatialRel` set to
+    /// intersects, so areas only partly inside it are included with their
+    /// whole boundary. Matches are counted and paged through like
+    /// [`BuiltUpAreaClient::fetch_all`], so large boxes aren't truncated at
+    /// the server's transfer limit.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use infra_hex_rs::{BBox, BuiltUpAreaClient};
+    /// # async fn example() {
+    /// let client = BuiltUpAreaClient::new();
+    /// let bbox = BBox::new(53.40, -2.35, 53.55, -2.10);
+    /// let result = client.fetch_by_bbox(&bbox).await;
+    /// println!("{} built-up areas", result.records.len());
+    /// # }
+    /// ```
+    pub async fn fetch_by_bbox(&self, bbox: &BBox) -> InfraResult<BuiltUpArea> {
+        self.fetch_all_paged("1=1", &envelope_params(bbox)).await
+    }
+
+    /// Returns the number of built-up areas matching a `where` clause plus
+    /// any already-encoded `extra_params` (each starting with `&`).
+    async fn count_where(
+        &self,
+        where_clause: &str,
+        extra_params: &str,
+    ) -> Result<usize, InfraHexError> {
+        let url = format!(
+            "{}?where={}{}&returnCountOnly=true&f=json",
+            self.base_url,
+            urlencoding::encode(where_clause),
+            extra_params
+        );
+
+        let response: CountResponse = self.http.fetch_json(&url).await?;
+        Ok(response.count)
+    }
+
+    /// Fetches every match for a `where` clause and `extra_params` with
+    /// [`fetch_all_pages`], after counting the matches. Every multi-result
+    /// query goes through here.
+    async fn fetch_all_paged(
+        &self,
+        where_clause: &str,
+        extra_params: &str,
+    ) -> InfraResult<BuiltUpArea> {
+        let total = match self.count_where(where_clause, extra_params).await {
+            Ok(total) => total,
+            Err(e) => {
+                let mut result = InfraResult::new();
+                result.errors.push(e);
+                return result;
+            }
+        };
+
+        fetch_all_pages(total, self.pagination.clone(), |offset, limit| {
+            self.fetch_range(where_clause, extra_params, offset, limit)
+        })
+        .await
+    }
+
+    /// Fetches the `limit` matches starting at `offset`, in order of
+    /// OBJECTID.
+    ///
+    /// The server may return fewer features than asked for, flagging the
+    /// cut-off with `exceededTransferLimit`; the rest of the range is then
+    /// requested until it's complete or the matches run out.
+    async fn fetch_range(
+        &self,
+        where_clause: &str,
+        extra_params: &str,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        let mut areas = Vec::new();
+
+        while areas.len() < limit {
+            let url = format!(
+                "{}{}&orderByFields=OBJECTID&resultOffset={}&resultRecordCount={}",
+                self.query_url(where_clause),
+                extra_params,
+                offset + areas.len(),
+                limit - areas.len()
+            );
+            let fc: FeatureCollection = self.http.fetch_json(&url).await?;
+            let returned = fc.features.len();
+            areas.extend(parse_features(&fc)?);
+
+            if returned == 0 || !exceeded_transfer_limit(&fc) {
+                break;
+            }
+        }
+
+        Ok(areas)
+    }
+
+    /// Fetches every built-up area whose `region_field` equals `region_code`,
+    /// e.g. all BUAs in one local authority or region.
+    ///
+    /// The fields relating BUAs to higher geographies depend on the dataset
+    /// schema, so the field name is a parameter rather than hardcoded. Results
+    /// are paged through like [`BuiltUpAreaClient::fetch_where`], so large
+    /// regions are not truncated at the transfer limit. A region with no BUAs
+    /// returns `Ok(vec![])`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InfraHexError::Config`] if `region_field` is not a plain
+    /// field name, besides the errors of [`BuiltUpAreaClient::fetch_where`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use infra_hex_rs::BuiltUpAreaClient;
+    /// # async fn example() -> Result<(), infra_hex_rs::InfraHexError> {
+    /// let client = BuiltUpAreaClient::new();
+    /// let areas = client.fetch_by_region("LAD24CD", "E08000003").await?;
+    /// println!("{} built-up areas", areas.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_by_region(
+        &self,
+        region_field: &str,
+        region_code: &str,
+    ) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+        if region_field.is_empty()
+            || !region_field
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(InfraHexError::Config(format!(
+                "Invalid region field name: {:?}",
+                region_field
             )));
         }
 
-        parse_feature(&fc.features[0])
+        let where_clause = format!("{} = {}", region_field, arcgis_string(region_code));
+        self.fetch_where(&where_clause).await
     }
 }
 
@@ -221,6 +606,87 @@ impl Default for BuiltUpAreaClient {
     }
 }
 
+/// Builds `OBJECTID IN (...)` clauses covering the sorted, de-duplicated
+/// `ids`, at most `chunk_size` ids per clause.
+fn object_id_predicates(ids: &[i64], chunk_size: usize) -> Vec<String> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+
+    ids.chunks(chunk_size.max(1))
+        .map(|chunk| {
+            let list: Vec<String> = chunk.iter().map(|id| id.to_string()).collect();
+            format!("OBJECTID IN ({})", list.join(","))
+        })
+        .collect()
+}
+
+/// Builds the query parameters selecting features that intersect `bbox`,
+/// given in WGS84.
+fn envelope_params(bbox: &BBox) -> String {
+    let envelope = format!(
+        "{},{},{},{}",
+        bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat
+    );
+    format!(
+        "&geometry={}&geometryType=esriGeometryEnvelope&spatialRel=esriSpatialRelIntersects&inSR=4326",
+        urlencoding::encode(&envelope)
+    )
+}
+
+/// Builds a case-insensitive `LIKE` clause matching `name` in `field`.
+fn name_predicate(field: &str, name: &str) -> String {
+    format!("UPPER({}) LIKE UPPER({})", field, arcgis_string(name))
+}
+
+/// Escapes a value as an ArcGIS SQL single-quoted string literal.
+fn arcgis_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Whether a query response was truncated at the server's transfer limit.
+///
+/// ArcGIS reports this as `exceededTransferLimit`, either at the top level or
+/// inside a top-level `properties` object.
+fn exceeded_transfer_limit(fc: &FeatureCollection) -> bool {
+    let Some(members) = fc.foreign_members.as_ref() else {
+        return false;
+    };
+
+    let flag = |v: Option<&serde_json::Value>| v.and_then(|v| v.as_bool()).unwrap_or(false);
+    flag(members.get("exceededTransferLimit"))
+        || flag(
+            members
+                .get("properties")
+                .and_then(|p| p.get("exceededTransferLimit")),
+        )
+}
+
+/// Parses every feature in a collection; an empty collection yields an empty Vec.
+fn parse_features(fc: &FeatureCollection) -> Result<Vec<BuiltUpArea>, InfraHexError> {
+    fc.features.iter().map(parse_feature).collect()
+}
+
+/// Parses the response to a unique OBJECTID query, which must hold exactly
+/// one feature.
+fn parse_single_feature(
+    fc: &FeatureCollection,
+    object_id: i64,
+) -> Result<BuiltUpArea, InfraHexError> {
+    match fc.features.as_slice() {
+        [] => Err(InfraHexError::Api(format!(
+            "No built-up area found with OBJECTID: {}",
+            object_id
+        ))),
+        [feature] => parse_feature(feature),
+        features => Err(InfraHexError::Api(format!(
+            "{} features returned for OBJECTID {}",
+            features.len(),
+            object_id
+        ))),
+    }
+}
+
 fn parse_feature(feature: &Feature) -> Result<BuiltUpArea, InfraHexError> {
     let properties = feature
         .properties
@@ -273,6 +739,7 @@ fn parse_feature(feature: &Feature) -> Result<BuiltUpArea, InfraHexError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::mock_server::{self, MockRequest};
     use geo_types::{Coord, LineString};
     use geojson::Value as GeoJsonValue;
 
@@ -384,6 +851,34 @@ mod tests {
         assert!(result.is_err());
     }
 
+    /// Test BUA GeoJSON output follows the right-hand rule
+    #[test]
+    fn test_built_up_area_geojson_exterior_is_ccw() {
+        use geo::Winding;
+
+        let area = BuiltUpArea {
+            object_id: 1,
+            code: "E34000001".to_string(),
+            name: "Clockwise".to_string(),
+            name_welsh: None,
+            area_hectares: None,
+            geometry: MultiPolygon::new(vec![Polygon::new(
+                LineString::from(vec![
+                    (0.0, 0.0),
+                    (0.0, 1.0),
+                    (1.0, 1.0),
+                    (1.0, 0.0),
+                    (0.0, 0.0),
+                ]),
+                vec![],
+            )]),
+        };
+
+        let geometry = area.to_geojson_feature().geometry.unwrap();
+        let parsed = MultiPolygon::from_geojson(&geometry).unwrap();
+        assert!(parsed.0[0].exterior().is_ccw());
+    }
+
     /// Test BuiltUpArea::to_geojson_feature
     #[test]
     fn test_built_up_area_to_geojson_feature() {
@@ -468,6 +963,67 @@ mod tests {
         assert!(json_str.contains("\"name\":\"JSON Test\""));
     }
 
+    /// Test parse_features treats an empty FeatureCollection as no matches
+    #[test]
+    fn test_parse_features_empty_collection() {
+        let fc = FeatureCollection {
+            bbox: None,
+            features: vec![],
+            foreign_members: None,
+        };
+
+        let areas = parse_features(&fc).unwrap();
+        assert!(areas.is_empty());
+    }
+
+    /// Test parse_features propagates a bad feature as an error
+    #[test]
+    fn test_parse_features_invalid_feature() {
+        let fc = FeatureCollection {
+            bbox: None,
+            features: vec![Feature::default()],
+            foreign_members: None,
+        };
+
+        assert!(parse_features(&fc).is_err());
+    }
+
+    /// Test a unique-id response holding several features is rejected
+    #[test]
+    fn test_parse_single_feature_rejects_duplicates() {
+        let feature = serde_json::json!({
+            "type": "Feature",
+            "properties": { "OBJECTID": 1310, "BUA24CD": "E63000001" },
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 0.0]]]
+            }
+        });
+        let fc: FeatureCollection = serde_json::from_value(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [feature.clone(), feature]
+        }))
+        .unwrap();
+
+        let err = parse_single_feature(&fc, 1310).unwrap_err();
+        assert!(matches!(&err, InfraHexError::Api(msg) if msg.contains("OBJECTID 1310")));
+
+        let single = FeatureCollection {
+            features: fc.features[..1].to_vec(),
+            ..fc.clone()
+        };
+        assert_eq!(parse_single_feature(&single, 1310).unwrap().object_id, 1310);
+
+        let empty = FeatureCollection {
+            features: vec![],
+            ..fc
+        };
+        assert!(matches!(
+            parse_single_feature(&empty, 1310),
+            Err(InfraHexError::Api(_))
+        ));
+    }
+
     /// Test BuiltUpAreaClient::default
     #[test]
     fn test_client_default() {
@@ -478,6 +1034,237 @@ mod tests {
         assert!(std::mem::size_of_val(&client1) == std::mem::size_of_val(&client2));
     }
 
+    /// Serves `total` mock areas (OBJECTIDs 1 to `total`), answering count
+    /// queries and returning at most `cap` features per page with
+    /// `exceededTransferLimit` set when capped. Records every request.
+    fn spawn_mock_arcgis_server(
+        total: usize,
+        cap: usize,
+    ) -> (String, Arc<std::sync::Mutex<Vec<MockRequest>>>) {
+        let (base_url, requests) = mock_server::spawn(move |request| {
+            if request.target.contains("returnCountOnly=true") {
+                return format!(r#"{{"count":{}}}"#, total);
+            }
+            let offset = request.param("resultOffset");
+            let wanted = request.param("resultRecordCount");
+            let end = (offset + wanted.min(cap)).min(total);
+            let features: Vec<String> = (offset..end)
+                .map(|i| {
+                    format!(
+                        r#"{{"type":"Feature","properties":{{"OBJECTID":{},"BUA24CD":"E6300{:04}","BUA24NM":"Area {}"}},"geometry":{{"type":"Polygon","coordinates":[[[-2.2,53.4],[-2.1,53.4],[-2.1,53.5],[-2.2,53.4]]]}}}}"#,
+                        i + 1,
+                        i + 1,
+                        i + 1
+                    )
+                })
+                .collect();
+            format!(
+                r#"{{"type":"FeatureCollection","features":[{}],"exceededTransferLimit":{}}}"#,
+                features.join(","),
+                wanted > cap && end < total
+            )
+        });
+        (format!("{}/query", base_url), requests)
+    }
+
+    /// Test fetch_all counts first, then pages through every area, topping
+    /// up pages the server cut short
+    #[tokio::test]
+    async fn test_fetch_all_pages_through_every_area() {
+        let (base_url, requests) = spawn_mock_arcgis_server(12, 3);
+        let mut client = BuiltUpAreaClient::new().with_pagination(
+            PaginationConfig::arcgis()
+                .with_page_size(5)
+                .with_batch_delay(Duration::from_millis(1)),
+        );
+        client.base_url = base_url;
+
+        let result = client.fetch_all().await;
+
+        assert!(result.is_complete(), "{:?}", result.errors);
+        assert!(result.is_count_consistent());
+        let mut ids: Vec<i64> = result.records.iter().map(|a| a.object_id).collect();
+        ids.sort_unstable();
+        assert_eq!(ids, (1..=12).collect::<Vec<_>>());
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].target.contains("returnCountOnly=true"));
+        assert!(
+            requests
+                .iter()
+                .skip(1)
+                .all(|r| r.target.contains("where=1%3D1"))
+        );
+    }
+
+    /// Test lookups by name page through the pager with the configured page
+    /// size, topping up capped pages
+    #[tokio::test]
+    async fn test_fetch_by_name_uses_pagination() {
+        let (base_url, requests) = spawn_mock_arcgis_server(12, 3);
+        let mut client = BuiltUpAreaClient::new().with_pagination(
+            PaginationConfig::arcgis()
+                .with_page_size(5)
+                .with_batch_delay(Duration::from_millis(1)),
+        );
+        client.base_url = base_url;
+
+        let areas = client.fetch_by_name("Area%").await.unwrap();
+        let ids: Vec<i64> = areas.iter().map(|a| a.object_id).collect();
+        assert_eq!(ids, (1..=12).collect::<Vec<_>>());
+
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].target.contains("returnCountOnly=true"));
+        assert!(
+            requests
+                .iter()
+                .skip(1)
+                .all(|r| r.param("resultRecordCount") <= 5)
+        );
+    }
+
+    #[test]
+    fn test_envelope_params() {
+        let params = envelope_params(&BBox::new(53.4, -2.35, 53.55, -2.1));
+        assert_eq!(
+            params,
+            "&geometry=-2.35%2C53.4%2C-2.1%2C53.55&geometryType=esriGeometryEnvelope\
+             &spatialRel=esriSpatialRelIntersects&inSR=4326"
+        );
+    }
+
+    /// Test fetch_by_bbox sends the envelope with the count and every page
+    #[tokio::test]
+    async fn test_fetch_by_bbox_pages_with_envelope() {
+        let (base_url, requests) = spawn_mock_arcgis_server(7, 10);
+        let mut client = BuiltUpAreaClient::new().with_pagination(
+            PaginationConfig::arcgis()
+                .with_page_size(3)
+                .with_batch_delay(Duration::from_millis(1)),
+        );
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.4, -2.35, 53.55, -2.1);
+        let result = client.fetch_by_bbox(&bbox).await;
+
+        assert!(result.is_complete(), "{:?}", result.errors);
+        assert_eq!(result.records.len(), 7);
+
+        // The count probe plus three pages, all spatially filtered
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests.iter().all(|r| r.target.contains(
+            "geometryType=esriGeometryEnvelope&spatialRel=esriSpatialRelIntersects&inSR=4326"
+        )));
+    }
+
+    /// Test query URLs request ungeneralised geometry
+    #[test]
+    fn test_query_url_full_precision() {
+        let url = BuiltUpAreaClient::new().query_url("OBJECTID=1310");
+
+        assert!(url.contains("where=OBJECTID%3D1310"));
+        assert!(url.contains("maxAllowableOffset=0"));
+        assert!(!url.contains("geometryPrecision"));
+    }
+
+    /// Test with_geometry_precision adds the geometryPrecision parameter
+    #[test]
+    fn test_query_url_with_geometry_precision() {
+        let url = BuiltUpAreaClient::new()
+            .with_geometry_precision(6)
+            .query_url("OBJECTID=1310");
+
+        assert!(url.contains("maxAllowableOffset=0"));
+        assert!(url.contains("&geometryPrecision=6"));
+    }
+
+    /// Test with_out_fields restricts outFields, always keeping OBJECTID
+    #[test]
+    fn test_query_url_with_out_fields() {
+        let url = BuiltUpAreaClient::new().query_url("1=1");
+        assert!(url.contains("&outFields=*&"));
+
+        let url = BuiltUpAreaClient::new()
+            .with_out_fields(&["BUA24NM"])
+            .query_url("1=1");
+        assert!(url.contains("&outFields=OBJECTID%2CBUA24NM&"));
+
+        let url = BuiltUpAreaClient::new()
+            .with_out_fields(&["BUA24NM", "OBJECTID"])
+            .query_url("1=1");
+        assert!(url.contains("&outFields=BUA24NM%2COBJECTID&"));
+    }
+
+    /// Test object id lists are de-duplicated, sorted and chunked
+    #[test]
+    fn test_object_id_predicates() {
+        assert_eq!(
+            object_id_predicates(&[3, 1, 2, 3], 2),
+            vec!["OBJECTID IN (1,2)", "OBJECTID IN (3)"]
+        );
+        assert!(object_id_predicates(&[], 2).is_empty());
+    }
+
+    /// Test region codes are quoted and escaped
+    #[test]
+    fn test_arcgis_string_escapes_quotes() {
+        assert_eq!(arcgis_string("E08000003"), "'E08000003'");
+        assert_eq!(arcgis_string("O'Neil"), "'O''Neil'");
+    }
+
+    /// Test the transfer limit flag is read from either location
+    #[test]
+    fn test_exceeded_transfer_limit() {
+        let parse = |json: serde_json::Value| -> FeatureCollection {
+            serde_json::from_value(json).unwrap()
+        };
+
+        let top_level = parse(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [],
+            "exceededTransferLimit": true
+        }));
+        let nested = parse(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [],
+            "properties": { "exceededTransferLimit": true }
+        }));
+        let absent = parse(serde_json::json!({
+            "type": "FeatureCollection",
+            "features": []
+        }));
+
+        assert!(exceeded_transfer_limit(&top_level));
+        assert!(exceeded_transfer_limit(&nested));
+        assert!(!exceeded_transfer_limit(&absent));
+    }
+
+    #[test]
+    fn test_name_predicate() {
+        assert_eq!(
+            name_predicate("BUA24NM", "Manchester"),
+            "UPPER(BUA24NM) LIKE UPPER('Manchester')"
+        );
+        assert_eq!(
+            name_predicate("BUA24NMW", "Bryn'r Efail"),
+            "UPPER(BUA24NMW) LIKE UPPER('Bryn''r Efail')"
+        );
+
+        let client = BuiltUpAreaClient::new();
+        let url = client.query_url(&name_predicate("BUA24NM", "Newport%"));
+        assert!(url.contains("where=UPPER%28BUA24NM%29%20LIKE%20UPPER%28%27Newport%25%27%29"));
+    }
+
+    /// Test fetch_by_region rejects field names that could alter the query
+    #[tokio::test]
+    async fn test_fetch_by_region_rejects_invalid_field() {
+        let client = BuiltUpAreaClient::new();
+        let result = client.fetch_by_region("1=1 OR LAD24CD", "E08000003").await;
+
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
     // ==================== Integration Tests ====================
     // These tests require network access and are marked with #[ignore]
 
@@ -503,6 +1290,29 @@ mod tests {
         }
     }
 
+    /// Integration test: full-precision geometry keeps boundary detail
+    #[tokio::test]
+    #[ignore = "requires network access"]
+    async fn test_fetch_manchester_vertex_density() {
+        let client = BuiltUpAreaClient::new();
+        let area = client.fetch_by_object_id(1310).await.unwrap();
+
+        let vertices: usize = area
+            .geometry
+            .0
+            .iter()
+            .map(|p| p.exterior().0.len() + p.interiors().iter().map(|r| r.0.len()).sum::<usize>())
+            .sum();
+
+        println!("Vertices: {}", vertices);
+        // A generalised Manchester boundary collapses to a few hundred vertices
+        assert!(
+            vertices > 1000,
+            "Expected detailed boundary, got {} vertices",
+            vertices
+        );
+    }
+
     /// Integration test: non-existent OBJECTID returns error
     #[tokio::test]
     #[ignore = "requires network access"]
@@ -513,6 +1323,52 @@ mod tests {
         assert!(result.is_err(), "Should fail for non-existent OBJECTID");
     }
 
+    /// Integration test: a valid query matching nothing is not an error
+    #[tokio::test]
+    #[ignore = "requires network access"]
+    async fn test_fetch_where_no_matches() {
+        let client = BuiltUpAreaClient::new();
+        let result = client.fetch_where("BUA24NM = 'No Such Place Xyzzy'").await;
+
+        assert!(result.is_ok(), "No matches should not be an error");
+        assert!(result.unwrap().is_empty());
+    }
+
+    /// Integration test: a name shared by several areas returns them all
+    #[tokio::test]
+    #[ignore = "requires network access"]
+    async fn test_fetch_by_name_newport() {
+        let client = BuiltUpAreaClient::new();
+        let areas = client.fetch_by_name("newport").await.unwrap();
+
+        assert!(areas.len() > 1, "Expected several Newports");
+        assert!(areas.iter().all(|a| a.name.eq_ignore_ascii_case("Newport")));
+    }
+
+    /// Integration test: an unknown name is reported as an API error
+    #[tokio::test]
+    #[ignore = "requires network access"]
+    async fn test_fetch_by_name_no_matches() {
+        let client = BuiltUpAreaClient::new();
+        let result = client.fetch_by_welsh_name("No Such Place Xyzzy").await;
+
+        assert!(matches!(result, Err(InfraHexError::Api(_))));
+    }
+
+    /// Integration test: batched lookup skips missing ids and sorts results
+    #[tokio::test]
+    #[ignore = "requires network access"]
+    async fn test_fetch_by_object_ids() {
+        let client = BuiltUpAreaClient::new();
+        let areas = client
+            .fetch_by_object_ids(&[1311, 1310, 1310, -1])
+            .await
+            .unwrap();
+
+        let ids: Vec<i64> = areas.iter().map(|a| a.object_id).collect();
+        assert_eq!(ids, vec![1310, 1311]);
+    }
+
     /// Integration test: verify GeoJSON roundtrip
     #[tokio::test]
     #[ignore = "requires network access"]
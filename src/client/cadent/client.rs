@@ -1,108 +1,655 @@
-use crate::client::pagination::{fetch_all_pages, PaginationConfig};
-use crate::client::traits::InfraClient;
-use crate::client::types::{ApiResponse, BBox, HttpClient, InfraResult};
+use std::path::Path;
+
+use crate::client::opendatasoft::{OpenDataSoftClient, odsql_string};
+use crate::client::types::{ApiResponse, BBox, InfraResult, RegionStats};
 use crate::error::InfraHexError;
 
+use super::filter::PipeFilter;
 use super::record::CadentPipelineRecord;
 
-pub struct CadentClient {
-    http: HttpClient,
-    base_url: String,
-}
+/// Client for Cadent's gas pipe infrastructure dataset: an
+/// [`OpenDataSoftClient`] preset with Cadent's portal and dataset, plus the
+/// Cadent-specific queries below.
+pub type CadentClient = OpenDataSoftClient<CadentPipelineRecord>;
+
+impl OpenDataSoftClient<CadentPipelineRecord> {
+    const CATALOG_URL: &'static str = "https://cadentgas.opendatasoft.com/api/explore/v2.1/catalog";
+
+    const DATASET_ID: &'static str = "gas-pipe-infrastructure-gpi_open";
 
-impl CadentClient {
-    const DEFAULT_BASE_URL: &'static str = "https://cadentgas.opendatasoft.com/api/explore/v2.1/catalog/datasets/gas-pipe-infrastructure-gpi_open/records";
+    /// Max length of an encoded `where` clause before a query is split.
+    /// Keeps the full URL comfortably under the common 2,048 character limit.
+    const MAX_WHERE_LEN: usize = 1500;
+
+    /// Number of records sampled by [`CadentClient::region_stats`] to derive
+    /// the extent. OpenDataSoft caps a single page at 100 records.
+    const REGION_STATS_SAMPLE: usize = 100;
 
     pub fn new() -> Result<Self, InfraHexError> {
         let key = std::env::var("CADENT_API_KEY")
             .map_err(|_| InfraHexError::Config("CADENT_API_KEY not set".into()))?;
 
-        Ok(Self {
-            http: HttpClient::new().with_api_key(key),
-            base_url: Self::DEFAULT_BASE_URL.to_string(),
-        })
+        Ok(Self::from_key(key))
     }
 
-    fn bbox_query(&self, bbox: &BBox) -> String {
-        format!(
-            "in_bbox(geo_point_2d,{},{},{},{})",
-            bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon
-        )
-    }
+    /// Creates a client using an API key read from a file, such as a mounted
+    /// Docker or Kubernetes secret.
+    ///
+    /// Surrounding whitespace and newlines are trimmed. Returns
+    /// `InfraHexError::Config` if the file can't be read or holds no key.
+    pub fn from_key_file(path: impl AsRef<Path>) -> Result<Self, InfraHexError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            InfraHexError::Config(format!(
+                "Could not read API key file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
 
-    async fn fetch_page(
-        &self,
-        bbox: &BBox,
-        limit: usize,
-        offset: usize,
-    ) -> Result<Vec<CadentPipelineRecord>, InfraHexError> {
-        let url = format!(
-            "{}?where={}&limit={}&offset={}",
-            self.base_url,
-            urlencoding::encode(&self.bbox_query(bbox)),
-            limit,
-            offset
-        );
+        let key = contents.trim();
+        if key.is_empty() {
+            return Err(InfraHexError::Config(format!(
+                "API key file {} is empty",
+                path.display()
+            )));
+        }
 
-        let response: ApiResponse<CadentPipelineRecord> = self.http.fetch_json(&url).await?;
-        Ok(response.results)
+        Ok(Self::from_key(key))
     }
-}
 
-impl InfraClient for CadentClient {
-    type Record = CadentPipelineRecord;
+    fn from_key(key: impl Into<String>) -> Self {
+        let mut client = Self::from_catalog(Self::CATALOG_URL, Self::DATASET_ID).with_api_key(key);
+        client.required_fields = &["geo_point_2d", "geo_shape"];
+        client
+    }
+
+    /// Fetches all records matching the given asset ids.
+    ///
+    /// Builds an ODSQL `asset_id in (...)` predicate with each id escaped as a
+    /// string literal. Long id lists are split into several queries so no URL
+    /// exceeds the length limit; the results of every query are merged into a
+    /// single `InfraResult`. Ids with no matching record are simply absent.
+    pub async fn fetch_by_asset_ids(&self, ids: &[&str]) -> InfraResult<CadentPipelineRecord> {
+        let mut result = InfraResult::new();
+        result.expected_count = Some(0);
+        result.fetched_count = Some(0);
+
+        for where_clause in asset_id_predicates(ids, Self::MAX_WHERE_LEN) {
+            let chunk = self.fetch_all_where(&where_clause).await;
+            result.records.extend(chunk.records);
+            result.errors.extend(chunk.errors);
+            // Counts stay known only while every query reported them
+            result.expected_count = result
+                .expected_count
+                .zip(chunk.expected_count)
+                .map(|(a, b)| a + b);
+            result.fetched_count = result
+                .fetched_count
+                .zip(chunk.fetched_count)
+                .map(|(a, b)| a + b);
+        }
 
-    async fn fetch_by_bbox(
+        result
+    }
+
+    /// Fetches all records in a bounding box matching a [`PipeFilter`],
+    /// applied server-side alongside the bbox clause.
+    ///
+    /// An invalid filter is reported in the result's `errors` without any
+    /// request being sent.
+    pub async fn fetch_all_by_pipe_filter(
         &self,
         bbox: &BBox,
-        limit: Option<usize>,
-    ) -> Result<Vec<Self::Record>, InfraHexError> {
-        let limit = limit.unwrap_or(100);
+        filter: &PipeFilter,
+    ) -> InfraResult<CadentPipelineRecord> {
+        match filter.to_odsql() {
+            Ok(filter) => self.fetch_all_by_bbox_where(bbox, &filter).await,
+            Err(e) => {
+                let mut result = InfraResult::new();
+                result.errors.push(e);
+                result
+            }
+        }
+    }
+
+    /// Returns the record count and an approximate extent for a bounding box.
+    ///
+    /// Issues a single request: the count comes from `total_count` and the
+    /// extent from the geometries of the first page of results, so no
+    /// pagination takes place. When the region holds more records than the
+    /// sample, the extent may be smaller than the true extent. If nothing
+    /// matches, the extent is the queried `bbox`.
+    pub async fn region_stats(&self, bbox: &BBox) -> Result<RegionStats, InfraHexError> {
         let url = format!(
-            "{}?where={}&limit={}",
+            "{}?where={}{}&limit={}",
             self.base_url,
             urlencoding::encode(&self.bbox_query(bbox)),
-            limit
+            self.select_param(),
+            Self::REGION_STATS_SAMPLE
         );
 
         let response: ApiResponse<CadentPipelineRecord> = self.http.fetch_json(&url).await?;
-        Ok(response.results)
+
+        Ok(RegionStats {
+            total_count: response.total_count,
+            extent: records_extent(&response.results).unwrap_or(*bbox),
+        })
     }
 
-    async fn fetch_all_by_bbox(&self, bbox: &BBox) -> InfraResult<Self::Record> {
-        // Get total count first
+    /// Checks that the Cadent API is reachable and accepts the API key.
+    ///
+    /// Issues a single `limit=0` request over a tiny bounding box, so no
+    /// records are transferred. Returns `InfraHexError::Auth` if the key is
+    /// rejected (401/403) and `InfraHexError::Http` if the endpoint can't be
+    /// reached. Intended for liveness probes rather than data queries.
+    pub async fn health_check(&self) -> Result<(), InfraHexError> {
+        let probe = BBox::new(53.48, -2.25, 53.4801, -2.2499);
         let url = format!(
-            "{}?where={}&limit=1",
+            "{}?where={}&limit=0",
             self.base_url,
-            urlencoding::encode(&self.bbox_query(bbox)),
+            urlencoding::encode(&self.bbox_query(&probe))
         );
 
-        let first = match self
-            .http
-            .fetch_json::<ApiResponse<CadentPipelineRecord>>(&url)
-            .await
-        {
-            Ok(resp) => resp,
-            Err(e) => {
-                let mut result = InfraResult::new();
-                result.errors.push(e);
-                return result;
+        let _: ApiResponse<CadentPipelineRecord> = self.http.fetch_json(&url).await?;
+        Ok(())
+    }
+}
+
+/// Computes the bounding box of every geometry position in `records`.
+///
+/// Records without a geometry fall back to their `geo_point_2d`. Returns
+/// `None` when `records` is empty.
+fn records_extent(records: &[CadentPipelineRecord]) -> Option<BBox> {
+    let mut extent: Option<BBox> = None;
+    let mut include = |lon: f64, lat: f64| {
+        let b = extent.get_or_insert(BBox::new(lat, lon, lat, lon));
+        b.min_lat = b.min_lat.min(lat);
+        b.min_lon = b.min_lon.min(lon);
+        b.max_lat = b.max_lat.max(lat);
+        b.max_lon = b.max_lon.max(lon);
+    };
+
+    for record in records {
+        match &record.geo_shape.geometry {
+            Some(geometry) => visit_positions(&geometry.value, &mut include),
+            None => include(record.geo_point_2d.lon, record.geo_point_2d.lat),
+        }
+    }
+
+    extent
+}
+
+/// Calls `f(lon, lat)` for every position in a GeoJSON geometry.
+fn visit_positions(value: &geojson::Value, f: &mut impl FnMut(f64, f64)) {
+    use geojson::Value;
+
+    let mut each = |positions: &[Vec<f64>]| {
+        for p in positions.iter().filter(|p| p.len() >= 2) {
+            f(p[0], p[1]);
+        }
+    };
+
+    match value {
+        Value::Point(p) => each(std::slice::from_ref(p)),
+        Value::MultiPoint(ps) | Value::LineString(ps) => each(ps),
+        Value::MultiLineString(lines) | Value::Polygon(lines) => {
+            lines.iter().for_each(|l| each(l));
+        }
+        Value::MultiPolygon(polys) => polys.iter().flatten().for_each(|l| each(l)),
+        Value::GeometryCollection(geoms) => {
+            for g in geoms {
+                visit_positions(&g.value, f);
             }
-        };
+        }
+    }
+}
 
-        let total = first.total_count as usize;
+/// Builds one or more `asset_id in (...)` predicates covering all `ids`.
+///
+/// A new predicate is started whenever adding the next id would push the
+/// URL-encoded clause past `max_len`. A single id is never split, so an
+/// oversized id still gets a predicate of its own.
+fn asset_id_predicates(ids: &[&str], max_len: usize) -> Vec<String> {
+    let build = |chunk: &[String]| format!("asset_id in ({})", chunk.join(","));
 
-        // Use pagination helper with OpenDataSoft config
-        fetch_all_pages(total, PaginationConfig::opendatasoft(), |offset, limit| {
-            self.fetch_page(bbox, limit, offset)
-        })
-        .await
+    let mut predicates = Vec::new();
+    let mut chunk: Vec<String> = Vec::new();
+
+    for id in ids {
+        chunk.push(odsql_string(id));
+        if chunk.len() > 1 && urlencoding::encode(&build(&chunk)).len() > max_len {
+            let last = chunk.pop().unwrap();
+            predicates.push(build(&chunk));
+            chunk = vec![last];
+        }
+    }
+
+    if !chunk.is_empty() {
+        predicates.push(build(&chunk));
     }
+
+    predicates
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::audit::AuditEntry;
+    use crate::client::mock_server::{self, MockRequest};
+    use crate::client::opendatasoft::and_where;
+    use crate::client::pagination::{PaginationConfig, page_count};
+    use crate::client::traits::InfraClient;
+    use futures::TryStreamExt;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    type Requests = Arc<Mutex<Vec<MockRequest>>>;
+
+    fn temp_key_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("infra-hex-rs-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_from_key_file_trims_key() {
+        let path = temp_key_file("key", "  secret-key\n");
+        let client = CadentClient::from_key_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let (base_url, requests) = spawn_mock_records_server(0, 0);
+        let mut client = client.unwrap();
+        client.base_url = base_url;
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        client.count_by_bbox(&bbox).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            requests[0].header("authorization"),
+            Some("Apikey secret-key")
+        );
+    }
+
+    #[test]
+    fn test_from_key_file_empty() {
+        let path = temp_key_file("empty", " \n");
+        let result = CadentClient::from_key_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_export_url() {
+        let client = CadentClient::from_key("key");
+        assert_eq!(
+            client.export_url(),
+            "https://cadentgas.opendatasoft.com/api/explore/v2.1/catalog/datasets/gas-pipe-infrastructure-gpi_open/exports/geojson"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_timeout_fails_fast() {
+        // Accept connections but never respond
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let mut client =
+            CadentClient::from_key("key").with_probe_timeout(Duration::from_millis(50));
+        client.base_url = format!("http://{}/records", addr);
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.count_by_bbox(&bbox).await;
+        assert!(matches!(result, Err(InfraHexError::Http(e)) if e.is_timeout()));
+    }
+
+    /// Serves `total` mock records over HTTP, reporting `reported_count` as
+    /// `total_count`, and records every request.
+    fn spawn_mock_records_server(total: usize, reported_count: usize) -> (String, Requests) {
+        let (base_url, requests) = mock_server::spawn(move |request| {
+            let (offset, limit) = (request.param("offset"), request.param("limit"));
+            let results: Vec<String> = (offset..(offset + limit).min(total))
+                .map(|i| {
+                    format!(
+                        r#"{{"geo_point_2d":{{"lon":-2.24,"lat":53.48}},"geo_shape":{{"type":"Feature","properties":{{}},"geometry":{{"type":"LineString","coordinates":[[-2.24,53.48],[-2.2401,53.48]]}}}},"asset_id":"A{}"}}"#,
+                        i
+                    )
+                })
+                .collect();
+            format!(
+                r#"{{"total_count":{},"results":[{}]}}"#,
+                reported_count,
+                results.join(",")
+            )
+        });
+        (format!("{}/records", base_url), requests)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_bbox_unbounded_pages_until_exhausted() {
+        let (base_url, requests) = spawn_mock_records_server(250, 0);
+        let mut client = CadentClient::from_key("key");
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.fetch_all_by_bbox_unbounded(&bbox).await;
+
+        assert!(result.is_complete(), "{:?}", result.errors);
+        assert_eq!(result.records.len(), 250);
+        assert_eq!(result.records[249].asset_id.as_deref(), Some("A249"));
+
+        // Three pages (100, 100, 50) and no limit=1 count probe
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|r| r.target.contains("limit=100")));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_bbox_filtered_keeps_matching_records() {
+        let (base_url, _requests) = spawn_mock_records_server(250, 250);
+        let mut client = CadentClient::from_key("key");
+        client.base_url = base_url;
+
+        // Keep the even-numbered assets only
+        let is_even = |record: &CadentPipelineRecord| {
+            record
+                .asset_id
+                .as_deref()
+                .and_then(|id| id.strip_prefix('A')?.parse::<usize>().ok())
+                .is_some_and(|n| n % 2 == 0)
+        };
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.fetch_all_by_bbox_filtered(&bbox, is_even).await;
+
+        assert!(result.is_complete(), "{:?}", result.errors);
+        assert!(result.is_count_consistent());
+        assert_eq!(result.records.len(), 125);
+        assert!(result.records.iter().all(is_even));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_bbox_writes_audit_log() {
+        let path = temp_key_file("audit.jsonl", "");
+        let (base_url, _requests) = spawn_mock_records_server(150, 150);
+        let mut client = CadentClient::from_key("key").with_audit_log(&path);
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.fetch_all_by_bbox(&bbox).await;
+        assert!(result.is_complete(), "{:?}", result.errors);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let entry: AuditEntry = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(entry.record_count, 150);
+        assert_eq!(entry.error_count, 0);
+        assert_eq!(entry.bbox, [-2.26, 53.47, -2.22, 53.49]);
+    }
+
+    #[tokio::test]
+    async fn test_stream_by_bbox_yields_every_record_in_order() {
+        let (base_url, requests) = spawn_mock_records_server(250, 250);
+        let mut client = CadentClient::from_key("key");
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let records: Vec<CadentPipelineRecord> =
+            client.stream_by_bbox(&bbox).try_collect().await.unwrap();
+
+        assert_eq!(records.len(), 250);
+        for (i, record) in records.iter().enumerate() {
+            assert_eq!(record.asset_id, Some(format!("A{}", i)));
+        }
+
+        // The count probe plus three pages
+        assert_eq!(requests.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_pipe_filter_ands_predicates_with_bbox() {
+        let (base_url, requests) = spawn_mock_records_server(20, 20);
+        let mut client = CadentClient::from_key("key");
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let filter = PipeFilter::new().with_pipe_type("HP").with_material("ST");
+        let result = client.fetch_all_by_pipe_filter(&bbox, &filter).await;
+        assert!(result.is_complete(), "{:?}", result.errors);
+
+        let expected = urlencoding::encode(&and_where(
+            &client.bbox_query(&bbox),
+            "(type = \"HP\") AND (material = \"ST\")",
+        ))
+        .into_owned();
+        let requests = requests.lock().unwrap();
+        assert!(!requests.is_empty());
+        assert!(
+            requests
+                .iter()
+                .all(|r| r.target.contains(&format!("where={}&", expected)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_pipe_filter_rejects_invalid_filter() {
+        let client = CadentClient::from_key("key");
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let filter = PipeFilter::new().with_installed_after("last year");
+
+        let result = client.fetch_all_by_pipe_filter(&bbox, &filter).await;
+        assert!(result.records.is_empty());
+        assert!(matches!(result.errors[..], [InfraHexError::Config(_)]));
+    }
+
+    #[tokio::test]
+    async fn test_with_select_requests_only_chosen_fields() {
+        let (base_url, requests) = spawn_mock_records_server(150, 150);
+        let mut client = CadentClient::from_key("key").with_select(&["type"]);
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.fetch_all_by_bbox(&bbox).await;
+
+        // The mock records carry no `type`, which deserialises as None
+        assert!(result.is_complete(), "{:?}", result.errors);
+        assert_eq!(result.records.len(), 150);
+        assert!(result.records.iter().all(|r| r.pipe_type.is_none()));
+
+        let requests = requests.lock().unwrap();
+        assert!(requests.iter().all(|r| {
+            r.target
+                .contains("&select=type%2Cgeo_point_2d%2Cgeo_shape&")
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_plan_fetch_sends_only_the_count_probe() {
+        let (base_url, requests) = spawn_mock_records_server(0, 12_000);
+        let mut client = CadentClient::from_key("key");
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let plan = client.plan_fetch(&bbox).await.unwrap();
+
+        assert_eq!(plan.total_count, 12_000);
+        assert_eq!(
+            plan.pages,
+            page_count(12_000, &PaginationConfig::opendatasoft())
+        );
+        assert!(plan.truncated);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].target.contains("limit=1"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_bbox_collects_stream() {
+        let (base_url, _requests) = spawn_mock_records_server(150, 150);
+        let mut client = CadentClient::from_key("key");
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.fetch_all_by_bbox(&bbox).await;
+
+        assert!(result.is_complete(), "{:?}", result.errors);
+        assert!(result.is_count_consistent());
+        assert_eq!(result.expected_count, Some(150));
+        assert_eq!(result.records.len(), 150);
+    }
+
+    #[tokio::test]
+    async fn test_with_pagination_reports_fetch_all_progress() {
+        let (base_url, requests) = spawn_mock_records_server(150, 150);
+        let reports = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = reports.clone();
+        let mut client = CadentClient::from_key("key").with_pagination(
+            PaginationConfig::opendatasoft()
+                .with_page_size(50)
+                .with_batch_delay(Duration::from_millis(1))
+                .with_progress(std::sync::Arc::new(move |progress| {
+                    seen.lock().unwrap().push(progress)
+                })),
+        );
+        client.base_url = base_url;
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client.fetch_all_by_bbox(&bbox).await;
+        assert_eq!(result.records.len(), 150);
+
+        // One count probe, then three pages of 50
+        assert_eq!(requests.lock().unwrap().len(), 4);
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.last().unwrap().completed_pages, 3);
+        assert_eq!(reports.last().unwrap().records_so_far, 150);
+    }
+
+    #[test]
+    fn test_dataset_from_base_url() {
+        let client = CadentClient::from_key("key");
+        assert_eq!(client.dataset(), "gas-pipe-infrastructure-gpi_open");
+    }
+
+    #[test]
+    fn test_from_key_file_missing() {
+        let result = CadentClient::from_key_file("/nonexistent/infra-hex-rs/key");
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_asset_id_predicates_single_chunk() {
+        let predicates = asset_id_predicates(&["A1", "A2"], 1500);
+        assert_eq!(predicates, vec!["asset_id in (\"A1\",\"A2\")".to_string()]);
+    }
+
+    #[test]
+    fn test_asset_id_predicates_empty() {
+        assert!(asset_id_predicates(&[], 1500).is_empty());
+    }
+
+    #[test]
+    fn test_asset_id_predicates_splits_long_lists() {
+        let ids: Vec<String> = (0..500).map(|i| format!("ASSET-{:05}", i)).collect();
+        let refs: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
+
+        let predicates = asset_id_predicates(&refs, 1500);
+
+        assert!(predicates.len() > 1);
+        for p in &predicates {
+            assert!(urlencoding::encode(p).len() <= 1500);
+        }
+        let total: usize = predicates.iter().map(|p| p.matches("ASSET-").count()).sum();
+        assert_eq!(total, 500);
+    }
+
+    fn make_record(geo_shape: serde_json::Value) -> CadentPipelineRecord {
+        serde_json::from_value(serde_json::json!({
+            "geo_point_2d": { "lon": -2.0, "lat": 53.0 },
+            "geo_shape": geo_shape,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_records_extent_spans_all_positions() {
+        let records = vec![
+            make_record(serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": [[-2.25, 53.48], [-2.24, 53.49]] },
+                "properties": {}
+            })),
+            make_record(serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "MultiLineString", "coordinates": [[[-2.30, 53.47], [-2.28, 53.47]]] },
+                "properties": {}
+            })),
+        ];
+
+        let extent = records_extent(&records).unwrap();
+        assert_eq!(extent.min_lon, -2.30);
+        assert_eq!(extent.max_lon, -2.24);
+        assert_eq!(extent.min_lat, 53.47);
+        assert_eq!(extent.max_lat, 53.49);
+    }
+
+    #[test]
+    fn test_records_extent_falls_back_to_point() {
+        let records = vec![make_record(serde_json::json!({
+            "type": "Feature",
+            "geometry": null,
+            "properties": {}
+        }))];
+
+        let extent = records_extent(&records).unwrap();
+        assert_eq!((extent.min_lon, extent.min_lat), (-2.0, 53.0));
+        assert_eq!((extent.max_lon, extent.max_lat), (-2.0, 53.0));
+    }
+
+    #[test]
+    fn test_records_extent_empty() {
+        assert!(records_extent(&[]).is_none());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_health_check() -> Result<(), InfraHexError> {
+        let client = CadentClient::new()?;
+        client.health_check().await
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_export_geojson_by_bbox() -> Result<(), InfraHexError> {
+        let client = CadentClient::new()?;
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+
+        let collection = client.export_geojson_by_bbox(&bbox).await?;
+        println!("Exported {} features", collection.features.len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_region_stats() -> Result<(), InfraHexError> {
+        let client = CadentClient::new()?;
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+
+        let stats = client.region_stats(&bbox).await?;
+        println!("{} records, extent {:?}", stats.total_count, stats.extent);
+        Ok(())
+    }
 
     #[tokio::test]
     #[ignore]
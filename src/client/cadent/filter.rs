@@ -0,0 +1,152 @@
+use crate::client::opendatasoft::odsql_string;
+use crate::error::InfraHexError;
+
+/// Server-side attribute filters for Cadent pipe queries.
+///
+/// Each field that is set becomes an ODSQL predicate; predicates are ANDed,
+/// so `PipeFilter::new().with_pipe_type("HP").with_material("ST")` selects
+/// high-pressure steel mains only. Pass it to
+/// [`CadentClient::fetch_all_by_pipe_filter`](crate::client::CadentClient::fetch_all_by_pipe_filter).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PipeFilter {
+    pub pipe_type: Option<String>,
+    pub material: Option<String>,
+    /// Smallest `diameter` kept, compared with the value as recorded (the
+    /// dataset records most diameters in millimetres, see `diam_unit`).
+    pub min_diameter: Option<f64>,
+    /// Keeps pipes installed on or after this `YYYY-MM-DD` date.
+    pub installed_after: Option<String>,
+}
+
+impl PipeFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keeps pipes whose `type` is exactly `pipe_type`, e.g. `"HP"`.
+    pub fn with_pipe_type(mut self, pipe_type: impl Into<String>) -> Self {
+        self.pipe_type = Some(pipe_type.into());
+        self
+    }
+
+    /// Keeps pipes whose `material` code is exactly `material`, e.g. `"ST"`.
+    pub fn with_material(mut self, material: impl Into<String>) -> Self {
+        self.material = Some(material.into());
+        self
+    }
+
+    /// Keeps pipes with a `diameter` of at least `min_diameter`.
+    ///
+    /// The comparison is on the raw `diameter` value; `diam_unit` is ignored,
+    /// so a pipe recorded in inches is compared as if its figure were
+    /// millimetres. Use [`crate::filter_by_min_diameter`] after fetching to
+    /// compare unit-converted diameters.
+    pub fn with_min_diameter(mut self, min_diameter: f64) -> Self {
+        self.min_diameter = Some(min_diameter);
+        self
+    }
+
+    /// Keeps pipes installed on or after `date`, given as `YYYY-MM-DD`.
+    pub fn with_installed_after(mut self, date: impl Into<String>) -> Self {
+        self.installed_after = Some(date.into());
+        self
+    }
+
+    /// Builds the ODSQL `where` clause for the filter, each predicate
+    /// parenthesised and joined with `AND`. Returns an empty string when no
+    /// field is set.
+    ///
+    /// Returns `InfraHexError::Config` for a non-finite diameter or a date
+    /// not in `YYYY-MM-DD` form.
+    pub fn to_odsql(&self) -> Result<String, InfraHexError> {
+        let mut predicates = Vec::new();
+
+        if let Some(pipe_type) = &self.pipe_type {
+            predicates.push(format!("type = {}", odsql_string(pipe_type)));
+        }
+        if let Some(material) = &self.material {
+            predicates.push(format!("material = {}", odsql_string(material)));
+        }
+        if let Some(min_diameter) = self.min_diameter {
+            if !min_diameter.is_finite() {
+                return Err(InfraHexError::Config(format!(
+                    "Minimum diameter must be finite, got {}",
+                    min_diameter
+                )));
+            }
+            predicates.push(format!("diameter >= {}", min_diameter));
+        }
+        if let Some(date) = &self.installed_after {
+            if !is_iso_date(date) {
+                return Err(InfraHexError::Config(format!(
+                    "Installation date must be YYYY-MM-DD, got {:?}",
+                    date
+                )));
+            }
+            predicates.push(format!("inst_date >= date'{}'", date));
+        }
+
+        Ok(predicates
+            .iter()
+            .map(|p| format!("({})", p))
+            .collect::<Vec<_>>()
+            .join(" AND "))
+    }
+}
+
+/// Checks `date` has the `YYYY-MM-DD` shape, so it can't break out of the
+/// ODSQL date literal.
+fn is_iso_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_filter() {
+        assert_eq!(PipeFilter::new().to_odsql().unwrap(), "");
+    }
+
+    #[test]
+    fn test_predicates_are_parenthesised_and_anded() {
+        let filter = PipeFilter::new()
+            .with_pipe_type("HP")
+            .with_material("ST")
+            .with_min_diameter(150.0)
+            .with_installed_after("2000-01-01");
+
+        assert_eq!(
+            filter.to_odsql().unwrap(),
+            "(type = \"HP\") AND (material = \"ST\") AND (diameter >= 150) \
+             AND (inst_date >= date'2000-01-01')"
+        );
+    }
+
+    #[test]
+    fn test_values_are_escaped() {
+        let filter = PipeFilter::new().with_material("S\" OR 1=1 OR \"");
+        assert_eq!(
+            filter.to_odsql().unwrap(),
+            "(material = \"S\\\" OR 1=1 OR \\\"\")"
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_date_and_diameter() {
+        let bad_date = PipeFilter::new().with_installed_after("2000-01-01' OR '1");
+        assert!(matches!(bad_date.to_odsql(), Err(InfraHexError::Config(_))));
+
+        let bad_diameter = PipeFilter::new().with_min_diameter(f64::NAN);
+        assert!(matches!(
+            bad_diameter.to_odsql(),
+            Err(InfraHexError::Config(_))
+        ));
+    }
+}
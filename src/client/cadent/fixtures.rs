@@ -0,0 +1,45 @@
+//! Hand-built Cadent records for tests.
+//!
+//! Tests set the attributes they care about with struct update syntax, e.g.
+//! `CadentPipelineRecord { material: Some("PE".into()), ..line_record(coords) }`.
+
+use geojson::{Feature, Geometry, Value};
+
+use crate::client::types::GeoPoint2d;
+
+use super::CadentPipelineRecord;
+
+/// A record with no geometry and every attribute unset.
+pub(crate) fn empty_record() -> CadentPipelineRecord {
+    CadentPipelineRecord {
+        geo_point_2d: GeoPoint2d {
+            lon: -2.248,
+            lat: 53.480,
+        },
+        geo_shape: Feature::default(),
+        pipe_type: None,
+        pressure: None,
+        material: None,
+        diameter: None,
+        diam_unit: None,
+        carr_mat: None,
+        carr_dia: None,
+        carr_di_un: None,
+        asset_id: None,
+        depth: None,
+        ag_ind: None,
+        inst_date: None,
+    }
+}
+
+/// A record along a WGS84 line string of `[lon, lat]` positions, with every
+/// attribute unset.
+pub(crate) fn line_record(coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+    CadentPipelineRecord {
+        geo_shape: Feature {
+            geometry: Some(Geometry::new(Value::LineString(coords))),
+            ..Default::default()
+        },
+        ..empty_record()
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+
+/// Pipe material, decoded from Cadent's terse `material` codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Material {
+    Polyethylene,
+    CastIron,
+    DuctileIron,
+    SpunIron,
+    Steel,
+    Pvc,
+    Copper,
+}
+
+impl Material {
+    /// Parses a material code such as `"PE"` or `"CI"`.
+    ///
+    /// Codes are matched case-insensitively, ignoring surrounding whitespace.
+    /// Unrecognised codes return `None`.
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.trim().to_ascii_uppercase().as_str() {
+            "PE" => Some(Self::Polyethylene),
+            "CI" => Some(Self::CastIron),
+            "DI" => Some(Self::DuctileIron),
+            "SI" => Some(Self::SpunIron),
+            "ST" => Some(Self::Steel),
+            "PVC" => Some(Self::Pvc),
+            "CU" => Some(Self::Copper),
+            _ => None,
+        }
+    }
+
+    /// The human-readable material name, e.g. `"Polyethylene"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Polyethylene => "Polyethylene",
+            Self::CastIron => "Cast Iron",
+            Self::DuctileIron => "Ductile Iron",
+            Self::SpunIron => "Spun Iron",
+            Self::Steel => "Steel",
+            Self::Pvc => "PVC",
+            Self::Copper => "Copper",
+        }
+    }
+}
+
+impl fmt::Display for Material {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_code() {
+        assert_eq!(Material::from_code("PE"), Some(Material::Polyethylene));
+        assert_eq!(Material::from_code(" ci "), Some(Material::CastIron));
+        assert_eq!(Material::from_code("XX"), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Material::DuctileIron.to_string(), "Ductile Iron");
+        assert_eq!(Material::Steel.to_string(), "Steel");
+    }
+}
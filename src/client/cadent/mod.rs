@@ -1,5 +1,11 @@
 mod client;
+mod filter;
+#[cfg(test)]
+pub(crate) mod fixtures;
+mod material;
 mod record;
 
 pub use client::CadentClient;
-pub use record::CadentPipelineRecord;
+pub use filter::PipeFilter;
+pub use material::Material;
+pub use record::{CadentPipelineRecord, records_to_geojson};
@@ -1,9 +1,11 @@
-use geojson::Feature;
+use geojson::{Feature, FeatureCollection};
 use serde::Deserialize;
 
 use crate::client::traits::PipelineData;
 use crate::client::types::GeoPoint2d;
 
+use super::material::Material;
+
 #[derive(Debug, Deserialize)]
 pub struct CadentPipelineRecord {
     pub geo_point_2d: GeoPoint2d,
@@ -26,6 +28,119 @@ pub struct CadentPipelineRecord {
     pub inst_date: Option<String>,
 }
 
+impl CadentPipelineRecord {
+    /// Parses the `ag_ind` above-ground indicator.
+    ///
+    /// The dataset uses `Y`/`N` flags; `YES`/`NO`, `TRUE`/`FALSE`, `1`/`0` and
+    /// `AG`/`BG` are also accepted case-insensitively. Missing or unrecognised
+    /// codes return `None`.
+    pub fn is_above_ground(&self) -> Option<bool> {
+        match self.ag_ind.as_deref()?.trim().to_ascii_uppercase().as_str() {
+            "Y" | "YES" | "TRUE" | "1" | "AG" => Some(true),
+            "N" | "NO" | "FALSE" | "0" | "BG" => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns the pipe `diameter` in millimetres, converting from its
+    /// `diam_unit`.
+    ///
+    /// Units are recognised as for
+    /// [`CadentPipelineRecord::carrier_diameter_mm`]. Returns `None` if either
+    /// value is missing or the unit is unrecognised.
+    pub fn diameter_mm(&self) -> Option<f64> {
+        diameter_to_mm(self.diameter?, self.diam_unit.as_deref()?)
+    }
+
+    /// Returns the carrier pipe diameter (`carr_dia`) in millimetres,
+    /// converting from its `carr_di_un` unit.
+    ///
+    /// Millimetres, centimetres, metres and inches are recognised
+    /// case-insensitively. Returns `None` if either value is missing or the
+    /// unit is unrecognised.
+    pub fn carrier_diameter_mm(&self) -> Option<f64> {
+        diameter_to_mm(self.carr_dia?, self.carr_di_un.as_deref()?)
+    }
+
+    /// Decodes the `material` code, e.g. `"PE"` to [`Material::Polyethylene`].
+    pub fn material_kind(&self) -> Option<Material> {
+        Material::from_code(self.material.as_deref()?)
+    }
+
+    /// Returns the human-readable material name (e.g. `"Cast Iron"` for
+    /// `"CI"`), or `None` if the material is missing or its code unknown.
+    pub fn material_name(&self) -> Option<&str> {
+        self.material_kind().map(|m| m.name())
+    }
+}
+
+impl CadentPipelineRecord {
+    /// Converts the record to a GeoJSON [`Feature`] carrying its original
+    /// pipe geometry.
+    ///
+    /// Scalar attributes (`asset_id`, `type`, `pressure`, `material`,
+    /// `diameter`, `diam_unit`, `carr_mat`, `carr_dia`, `carr_di_un`, `depth`,
+    /// `ag_ind`, `inst_date`) become properties; missing values are omitted.
+    /// Returns `None` if the record has no geometry.
+    pub fn to_geojson_feature(&self) -> Option<Feature> {
+        let geometry = self.geo_shape.geometry.clone()?;
+
+        let mut properties = serde_json::Map::new();
+        let mut insert = |key: &str, value: serde_json::Value| {
+            if !value.is_null() {
+                properties.insert(key.to_string(), value);
+            }
+        };
+        insert("asset_id", serde_json::json!(self.asset_id));
+        insert("type", serde_json::json!(self.pipe_type));
+        insert("pressure", serde_json::json!(self.pressure));
+        insert("material", serde_json::json!(self.material));
+        insert("diameter", serde_json::json!(self.diameter));
+        insert("diam_unit", serde_json::json!(self.diam_unit));
+        insert("carr_mat", serde_json::json!(self.carr_mat));
+        insert("carr_dia", serde_json::json!(self.carr_dia));
+        insert("carr_di_un", serde_json::json!(self.carr_di_un));
+        insert("depth", serde_json::json!(self.depth));
+        insert("ag_ind", serde_json::json!(self.ag_ind));
+        insert("inst_date", serde_json::json!(self.inst_date));
+
+        Some(Feature {
+            bbox: None,
+            geometry: Some(geometry),
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        })
+    }
+}
+
+/// Converts a diameter in `unit` to millimetres.
+fn diameter_to_mm(value: f64, unit: &str) -> Option<f64> {
+    let factor = match unit.trim().to_ascii_lowercase().as_str() {
+        "mm" | "millimetre" | "millimetres" | "millimeter" | "millimeters" => 1.0,
+        "cm" | "centimetre" | "centimetres" => 10.0,
+        "m" | "metre" | "metres" | "meter" | "meters" => 1000.0,
+        "in" | "inch" | "inches" | "\"" => 25.4,
+        _ => return None,
+    };
+    Some(value * factor)
+}
+
+/// Converts raw pipe records into a GeoJSON [`FeatureCollection`] of their
+/// original line geometries, for eyeballing fetched data before aggregation.
+///
+/// Records with no geometry are skipped.
+pub fn records_to_geojson(records: &[CadentPipelineRecord]) -> FeatureCollection {
+    FeatureCollection {
+        bbox: None,
+        features: records
+            .iter()
+            .filter_map(CadentPipelineRecord::to_geojson_feature)
+            .collect(),
+        foreign_members: None,
+    }
+}
+
 impl PipelineData for CadentPipelineRecord {
     fn geo_shape(&self) -> &Feature {
         &self.geo_shape
@@ -46,4 +161,115 @@ impl PipelineData for CadentPipelineRecord {
     fn pressure(&self) -> Option<&str> {
         self.pressure.as_deref()
     }
+
+    fn diameter_mm(&self) -> Option<f64> {
+        CadentPipelineRecord::diameter_mm(self)
+    }
+
+    fn is_above_ground(&self) -> Option<bool> {
+        CadentPipelineRecord::is_above_ground(self)
+    }
+
+    fn carrier_material(&self) -> Option<&str> {
+        self.carr_mat.as_deref()
+    }
+
+    fn carrier_diameter_mm(&self) -> Option<f64> {
+        CadentPipelineRecord::carrier_diameter_mm(self)
+    }
+
+    fn carrier_diameter_unit(&self) -> Option<&str> {
+        self.carr_di_un.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_record(geometry: serde_json::Value) -> CadentPipelineRecord {
+        serde_json::from_value(serde_json::json!({
+            "geo_point_2d": { "lon": -2.248, "lat": 53.480 },
+            "geo_shape": { "type": "Feature", "geometry": geometry, "properties": {} },
+            "type": "MP",
+            "diameter": 125.0,
+            "asset_id": "TEST-001",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_records_to_geojson_skips_null_geometry() {
+        let records = vec![
+            make_record(serde_json::json!({
+                "type": "LineString",
+                "coordinates": [[-2.2484, 53.4804], [-2.2502, 53.4806]]
+            })),
+            make_record(serde_json::Value::Null),
+        ];
+
+        let collection = records_to_geojson(&records);
+        assert_eq!(collection.features.len(), 1);
+    }
+
+    #[test]
+    fn test_to_geojson_feature_properties() {
+        let record = make_record(serde_json::json!({
+            "type": "LineString",
+            "coordinates": [[-2.2484, 53.4804], [-2.2502, 53.4806]]
+        }));
+
+        let feature = record.to_geojson_feature().unwrap();
+        let props = feature.properties.unwrap();
+
+        assert_eq!(props["asset_id"], "TEST-001");
+        assert_eq!(props["type"], "MP");
+        assert_eq!(props["diameter"], 125.0);
+        assert!(!props.contains_key("material"));
+    }
+
+    #[test]
+    fn test_carrier_diameter_mm() {
+        let mut record = make_record(serde_json::Value::Null);
+        assert_eq!(record.carrier_diameter_mm(), None);
+
+        record.carr_dia = Some(6.0);
+        record.carr_di_un = Some("IN".to_string());
+        assert!((record.carrier_diameter_mm().unwrap() - 152.4).abs() < 1e-9);
+
+        record.carr_di_un = Some("mm".to_string());
+        assert_eq!(record.carrier_diameter_mm(), Some(6.0));
+
+        record.carr_di_un = Some("furlong".to_string());
+        assert_eq!(record.carrier_diameter_mm(), None);
+    }
+
+    #[test]
+    fn test_is_above_ground_parsing() {
+        // `ag_ind` as it appears in the dataset's JSON: `Y`, `N`, an empty
+        // string or null
+        let parse = |ag_ind: serde_json::Value| {
+            let mut record = make_record(serde_json::Value::Null);
+            record.ag_ind = serde_json::from_value(ag_ind).unwrap();
+            record.is_above_ground()
+        };
+
+        assert_eq!(parse(serde_json::json!("Y")), Some(true));
+        assert_eq!(parse(serde_json::json!("N")), Some(false));
+        assert_eq!(parse(serde_json::json!("n ")), Some(false));
+        assert_eq!(parse(serde_json::json!("")), None);
+        assert_eq!(parse(serde_json::Value::Null), None);
+    }
+
+    #[test]
+    fn test_material_name() {
+        let mut record = make_record(serde_json::Value::Null);
+        assert_eq!(record.material_name(), None);
+
+        record.material = Some("PE".to_string());
+        assert_eq!(record.material_name(), Some("Polyethylene"));
+
+        record.material = Some("??".to_string());
+        assert_eq!(record.material_name(), None);
+    }
 }
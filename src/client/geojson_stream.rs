@@ -0,0 +1,197 @@
+use geojson::Feature;
+
+use crate::error::InfraHexError;
+
+/// Incrementally extracts features from a GeoJSON `FeatureCollection` as its
+/// bytes arrive.
+///
+/// Only the feature currently being read is buffered: bytes outside the
+/// top-level `features` array are scanned and dropped, and each feature
+/// object is parsed and released as soon as its closing brace is seen. Peak
+/// memory is therefore bounded by the largest single feature rather than the
+/// whole document.
+#[derive(Debug, Default)]
+pub(crate) struct FeatureStreamParser {
+    /// Nesting depth of objects and arrays.
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// Last string seen directly inside the top-level object, i.e. the most
+    /// recent key.
+    key: Vec<u8>,
+    in_features: bool,
+    saw_features: bool,
+    /// Bytes of the feature currently being read.
+    feature: Vec<u8>,
+    capturing: bool,
+}
+
+impl FeatureStreamParser {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the next chunk of the document, returning every feature it
+    /// completed.
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Result<Vec<Feature>, InfraHexError> {
+        let mut features = Vec::new();
+
+        for &byte in chunk {
+            if self.capturing {
+                self.feature.push(byte);
+            }
+
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                } else if self.depth == 1 {
+                    self.key.push(byte);
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    if self.depth == 1 {
+                        self.key.clear();
+                    }
+                }
+                b'{' | b'[' => {
+                    if byte == b'[' && self.depth == 1 && self.key == b"features" {
+                        self.in_features = true;
+                        self.saw_features = true;
+                    } else if byte == b'{' && self.in_features && self.depth == 2 {
+                        self.capturing = true;
+                        self.feature.push(byte);
+                    }
+                    self.depth += 1;
+                }
+                b'}' | b']' => {
+                    self.depth = self.depth.checked_sub(1).ok_or_else(|| {
+                        InfraHexError::Api("Malformed GeoJSON: unbalanced brackets".to_string())
+                    })?;
+                    if self.capturing && self.depth == 2 {
+                        self.capturing = false;
+                        features.push(serde_json::from_slice(&self.feature)?);
+                        self.feature.clear();
+                    } else if self.in_features && self.depth == 1 {
+                        self.in_features = false;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(features)
+    }
+
+    /// Checks that the document ended cleanly.
+    ///
+    /// A truncated response (connection dropped mid-export) leaves open
+    /// brackets behind and is reported as `InfraHexError::Api`; features
+    /// already returned by [`FeatureStreamParser::push`] remain valid.
+    pub(crate) fn finish(&self) -> Result<(), InfraHexError> {
+        if self.depth != 0 || self.in_string {
+            return Err(InfraHexError::Api(
+                "GeoJSON export ended before the document was complete".to_string(),
+            ));
+        }
+        if !self.saw_features {
+            return Err(InfraHexError::Api(
+                "GeoJSON export has no features array".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bytes currently held for a partially read feature.
+    #[cfg(test)]
+    fn buffered_len(&self) -> usize {
+        self.feature.capacity().max(self.key.capacity())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feature_json(i: usize) -> String {
+        format!(
+            r#"{{"type":"Feature","properties":{{"asset_id":"A{}","note":"a \"quoted\" {{brace}}"}},"geometry":{{"type":"LineString","coordinates":[[-2.24,53.48],[-2.2401,53.48]]}}}}"#,
+            i
+        )
+    }
+
+    #[test]
+    fn test_parses_large_body_with_bounded_buffer() {
+        let total = 20_000;
+        let mut parser = FeatureStreamParser::new();
+        let mut count = 0;
+        let mut peak = 0;
+
+        // Produce the document piece by piece, never holding all of it, and
+        // split it into awkward 97 byte chunks
+        let pieces = std::iter::once(r#"{"type":"FeatureCollection","features":["#.to_string())
+            .chain((0..total).map(|i| {
+                let sep = if i == 0 { "" } else { "," };
+                format!("{}{}", sep, feature_json(i))
+            }))
+            .chain(std::iter::once(r#"],"name":"export"}"#.to_string()));
+
+        let mut pending = Vec::new();
+        for piece in pieces {
+            pending.extend_from_slice(piece.as_bytes());
+            while pending.len() >= 97 {
+                let chunk: Vec<u8> = pending.drain(..97).collect();
+                for feature in parser.push(&chunk).unwrap() {
+                    assert!(feature.geometry.is_some());
+                    count += 1;
+                }
+                peak = peak.max(parser.buffered_len());
+            }
+        }
+        count += parser.push(&pending).unwrap().len();
+        parser.finish().unwrap();
+
+        assert_eq!(count, total);
+        // Never more than about one feature's worth of bytes held
+        assert!(peak < 4 * feature_json(total).len(), "peak {}", peak);
+    }
+
+    #[test]
+    fn test_reports_truncated_document() {
+        let body = format!(
+            r#"{{"type":"FeatureCollection","features":[{},{}"#,
+            feature_json(0),
+            &feature_json(1)[..20]
+        );
+
+        let mut parser = FeatureStreamParser::new();
+        let features = parser.push(body.as_bytes()).unwrap();
+        assert_eq!(features.len(), 1);
+        assert!(matches!(parser.finish(), Err(InfraHexError::Api(_))));
+    }
+
+    #[test]
+    fn test_ignores_nested_features_keys() {
+        let body = format!(
+            r#"{{"meta":{{"features":[{{"x":1}}]}},"type":"FeatureCollection","features":[{}]}}"#,
+            feature_json(7)
+        );
+
+        let mut parser = FeatureStreamParser::new();
+        let features = parser.push(body.as_bytes()).unwrap();
+        parser.finish().unwrap();
+
+        assert_eq!(features.len(), 1);
+        assert_eq!(
+            features[0].property("asset_id").and_then(|v| v.as_str()),
+            Some("A7")
+        );
+    }
+}
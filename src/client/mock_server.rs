@@ -0,0 +1,128 @@
+//! Minimal HTTP server for client tests, standing in for the APIs.
+
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+
+/// A request received by a server from [`spawn`].
+#[derive(Debug, Clone)]
+pub(crate) struct MockRequest {
+    /// Path and query string, e.g. `/records?where=...&limit=100`.
+    pub target: String,
+    /// Request line and headers, as sent.
+    pub head: String,
+}
+
+impl MockRequest {
+    /// Value of a numeric query parameter, or 0 if it's missing.
+    pub fn param(&self, name: &str) -> usize {
+        self.target
+            .split(['?', '&'])
+            .find_map(|p| p.strip_prefix(name)?.strip_prefix('='))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0)
+    }
+
+    /// Value of a header, matching its name case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+}
+
+/// A response for a server from [`spawn_with`] to send.
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    pub status: u16,
+    /// Extra headers, sent before `Content-Type` and `Content-Length`.
+    pub headers: Vec<(String, String)>,
+    /// JSON body.
+    pub body: String,
+}
+
+impl MockResponse {
+    /// A `200 OK` response with a JSON body.
+    pub fn json(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// An empty JSON object body with the given status.
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            ..Self::json("{}")
+        }
+    }
+
+    /// Adds a header to the response.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Serves every request with the JSON body `respond` builds for it, on a
+/// background thread.
+///
+/// Returns the server's base URL (`http://127.0.0.1:<port>`, no trailing
+/// slash) and the requests received so far, in arrival order.
+pub(crate) fn spawn(
+    respond: impl Fn(&MockRequest) -> String + Send + 'static,
+) -> (String, Arc<Mutex<Vec<MockRequest>>>) {
+    spawn_with(move |request| MockResponse::json(respond(request)))
+}
+
+/// Like [`spawn`], with `respond` choosing the status and headers too.
+pub(crate) fn spawn_with(
+    respond: impl Fn(&MockRequest) -> MockResponse + Send + 'static,
+) -> (String, Arc<Mutex<Vec<MockRequest>>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+
+    std::thread::spawn(move || {
+        for mut socket in listener.incoming().flatten() {
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                match socket.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+
+            let head = String::from_utf8_lossy(&buf).into_owned();
+            let target = head.split_whitespace().nth(1).unwrap_or("").to_string();
+            let request = MockRequest { target, head };
+            let response = respond(&request);
+            seen.lock().unwrap().push(request);
+
+            let reason = reqwest::StatusCode::from_u16(response.status)
+                .ok()
+                .and_then(|s| s.canonical_reason())
+                .unwrap_or("Unknown");
+            let headers: String = response
+                .headers
+                .iter()
+                .map(|(name, value)| format!("{}: {}\r\n", name, value))
+                .collect();
+            let _ = write!(
+                socket,
+                "HTTP/1.1 {} {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.status,
+                reason,
+                headers,
+                response.body.len(),
+                response.body
+            );
+        }
+    });
+
+    (format!("http://{}", addr), requests)
+}
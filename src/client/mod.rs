@@ -1,11 +1,25 @@
+pub mod audit;
 pub mod built_up_area;
 pub mod cadent;
+pub(crate) mod geojson_stream;
+#[cfg(test)]
+pub(crate) mod mock_server;
+pub mod opendatasoft;
 pub mod pagination;
+pub mod rate_limit;
+pub mod retry;
 pub mod traits;
 pub mod types;
 
+pub use audit::{AuditEntry, AuditLog};
 pub use built_up_area::{BuiltUpArea, BuiltUpAreaClient, polygon_to_geojson};
-pub use cadent::{CadentClient, CadentPipelineRecord};
-pub use pagination::{PaginationConfig, fetch_all_pages};
+pub use cadent::{CadentClient, CadentPipelineRecord, Material, PipeFilter, records_to_geojson};
+pub use opendatasoft::OpenDataSoftClient;
+pub use pagination::{
+    FetchPlan, FetchProgress, PaginationConfig, ProgressCallback, fetch_all_pages,
+    fetch_all_pages_cancellable, page_count, stream_pages,
+};
+pub use rate_limit::RateLimiter;
+pub use retry::RetryConfig;
 pub use traits::{InfraClient, PipelineData};
-pub use types::{ApiResponse, BBox, GeoPoint2d, InfraResult};
+pub use types::{ApiResponse, BBox, GeoPoint2d, InfraResult, RegionStats};
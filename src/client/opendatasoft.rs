@@ -0,0 +1,753 @@
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::{Stream, TryStreamExt, stream};
+use geojson::{Feature, FeatureCollection};
+use serde::de::DeserializeOwned;
+
+use crate::client::audit::{AuditEntry, AuditLog};
+use crate::client::geojson_stream::FeatureStreamParser;
+use crate::client::pagination::{
+    FetchPlan, PaginationConfig, fetch_all_pages, fetch_pages_until_exhausted, stream_pages,
+};
+use crate::client::rate_limit::RateLimiter;
+use crate::client::retry::RetryConfig;
+use crate::client::traits::InfraClient;
+use crate::client::types::{ApiResponse, BBox, HttpClient, InfraResult};
+use crate::error::InfraHexError;
+
+/// Builds the ODSQL `where` clause selecting the records in a bounding box.
+type WhereBuilder = Arc<dyn Fn(&BBox) -> String + Send + Sync>;
+
+/// Client for any dataset served by the OpenDataSoft Explore v2.1 API.
+///
+/// Many utility portals (gas distribution networks, electricity DNOs) run on
+/// OpenDataSoft, so one client covers them all: point it at a portal's
+/// catalog URL and a dataset id, and name the record type to deserialise
+/// each result into. [`CadentClient`](crate::client::CadentClient) is a
+/// preset of this client for Cadent's gas pipe dataset.
+pub struct OpenDataSoftClient<R> {
+    pub(crate) http: HttpClient,
+    pub(crate) base_url: String,
+    probe_timeout: Option<Duration>,
+    audit_log: Option<Arc<AuditLog>>,
+    where_builder: WhereBuilder,
+    select: Option<Vec<String>>,
+    pub(crate) pagination: PaginationConfig,
+    /// Fields the record type can't deserialise without, always selected.
+    pub(crate) required_fields: &'static [&'static str],
+    record: PhantomData<fn() -> R>,
+}
+
+impl<R> OpenDataSoftClient<R> {
+    /// Creates a client for `dataset_id` in the catalog at `catalog_url`,
+    /// e.g. `https://example.opendatasoft.com/api/explore/v2.1/catalog`.
+    ///
+    /// Records are selected by bbox with
+    /// `in_bbox(geo_point_2d, ...)`, OpenDataSoft's default geo field; use
+    /// [`OpenDataSoftClient::with_where_builder`] for datasets that differ.
+    /// No API key is sent unless [`OpenDataSoftClient::with_api_key`] sets
+    /// one.
+    pub fn from_catalog(catalog_url: &str, dataset_id: &str) -> Self {
+        Self {
+            http: HttpClient::new(),
+            base_url: format!(
+                "{}/datasets/{}/records",
+                catalog_url.trim_end_matches('/'),
+                dataset_id
+            ),
+            probe_timeout: None,
+            audit_log: None,
+            where_builder: Arc::new(in_bbox_geo_point_2d),
+            select: None,
+            pagination: PaginationConfig::opendatasoft(),
+            required_fields: &[],
+            record: PhantomData,
+        }
+    }
+
+    /// Sends `key` as the API key with every request.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.http = self.http.with_api_key(key);
+        self
+    }
+
+    /// Replaces the function building the `where` clause for a bounding box.
+    ///
+    /// Every bbox query (fetches, counts, streams and exports) goes through
+    /// it, so it can target another geo field or add predicates the dataset
+    /// always needs, e.g. `in_bbox(geo_shape, ...) AND status = "live"`.
+    pub fn with_where_builder(
+        mut self,
+        builder: impl Fn(&BBox) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.where_builder = Arc::new(builder);
+        self
+    }
+
+    /// Requests only the given fields (ODSQL `select`) instead of whole
+    /// records.
+    ///
+    /// Cuts bandwidth on large fetches when only some fields are needed,
+    /// e.g. the geometry for a hex summary. Fields the record type requires
+    /// are added automatically (for [`CadentClient`](crate::client::CadentClient),
+    /// `geo_point_2d` and `geo_shape`); any other field left out deserialises
+    /// as `None`. Applies to every record query and to GeoJSON exports.
+    pub fn with_select(mut self, fields: &[&str]) -> Self {
+        self.select = Some(fields.iter().map(|f| f.to_string()).collect());
+        self
+    }
+
+    /// Sets how paginated fetches and streams page through results.
+    ///
+    /// Defaults to [`PaginationConfig::opendatasoft`]. Keep `max_offset` at
+    /// or below the portal's offset limit (10,000 on OpenDataSoft), and set
+    /// a [`PaginationConfig::with_progress`] callback here to follow
+    /// [`InfraClient::fetch_all_by_bbox`].
+    pub fn with_pagination(mut self, config: PaginationConfig) -> Self {
+        self.pagination = config;
+        self
+    }
+
+    /// Caps the idle connections kept open to the portal.
+    ///
+    /// See [`HttpClient::with_pool_max_idle_per_host`]; a value near the
+    /// pagination batch size (100) suits the default fetch concurrency.
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.http = self.http.with_pool_max_idle_per_host(max);
+        self
+    }
+
+    /// Closes idle connections to the portal after `timeout`.
+    ///
+    /// See [`HttpClient::with_pool_idle_timeout`].
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.with_pool_idle_timeout(timeout);
+        self
+    }
+
+    /// Sets the connect and overall request timeout, 30 seconds by default.
+    ///
+    /// See [`HttpClient::with_timeout`]. Raise it for large GeoJSON exports,
+    /// whose whole download must finish within the timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.with_timeout(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    ///
+    /// See [`HttpClient::with_user_agent`]. Public portals ask scrapers to
+    /// include a contact string. Returns `InfraHexError::Config` for a value
+    /// that isn't a valid header.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Result<Self, InfraHexError> {
+        self.http = self.http.with_user_agent(user_agent)?;
+        Ok(self)
+    }
+
+    /// Retries transient request failures with exponential backoff.
+    ///
+    /// See [`HttpClient::with_retry`]. This applies per request, including
+    /// each page of a paginated fetch.
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.http = self.http.with_retry(retry);
+        self
+    }
+
+    /// Paces every request through a shared [`RateLimiter`].
+    ///
+    /// See [`HttpClient::with_rate_limiter`]. Pagination's own batch delays
+    /// still apply on top.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.http = self.http.with_rate_limiter(limiter);
+        self
+    }
+
+    /// Limits how long the `total_count` probe that starts each full fetch
+    /// may take.
+    ///
+    /// The probe runs before any pages are requested, so a hung probe stalls
+    /// the whole fetch. With a timeout it fails fast with
+    /// `InfraHexError::Http` instead, distinguishing a stuck probe from slow
+    /// page downloads. Page fetches are unaffected. No timeout by default.
+    pub fn with_probe_timeout(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
+    /// Appends a line to a JSONL audit log after every completed
+    /// [`InfraClient::fetch_all_by_bbox`].
+    ///
+    /// Each line records the timestamp, bbox, dataset, record count, error
+    /// count and duration of the fetch (see [`AuditEntry`]), giving a record
+    /// of what data was pulled when, separate from general logging. If the
+    /// line can't be written, the failure is added to the fetch's `errors`.
+    pub fn with_audit_log(self, path: impl AsRef<Path>) -> Self {
+        self.with_shared_audit_log(Arc::new(AuditLog::new(path)))
+    }
+
+    /// Like [`OpenDataSoftClient::with_audit_log`], writing through an
+    /// existing [`AuditLog`] shared with other clients.
+    pub fn with_shared_audit_log(mut self, log: Arc<AuditLog>) -> Self {
+        self.audit_log = Some(log);
+        self
+    }
+
+    /// Name of the dataset the client reads, taken from its base URL.
+    pub(crate) fn dataset(&self) -> &str {
+        self.base_url
+            .split_once("/datasets/")
+            .and_then(|(_, rest)| rest.split('/').next())
+            .unwrap_or(&self.base_url)
+    }
+
+    pub(crate) fn bbox_query(&self, bbox: &BBox) -> String {
+        (self.where_builder)(bbox)
+    }
+
+    /// The encoded `&select=...` parameter, or an empty string when every
+    /// field is requested.
+    pub(crate) fn select_param(&self) -> String {
+        let Some(fields) = &self.select else {
+            return String::new();
+        };
+        let mut fields = fields.clone();
+        for required in self.required_fields {
+            if !fields.iter().any(|f| f == required) {
+                fields.push(required.to_string());
+            }
+        }
+        format!("&select={}", urlencoding::encode(&fields.join(",")))
+    }
+
+    /// Fetches every record in a bounding box as a single GeoJSON
+    /// `FeatureCollection`, via the OpenDataSoft `exports/geojson` endpoint.
+    ///
+    /// Unlike the paginated `records` endpoint, exports are not subject to the
+    /// 10,000 record offset cap, so this is the simpler path for large but
+    /// bounded regions. The features are parsed as the response arrives (see
+    /// [`OpenDataSoftClient::stream_export_geojson_by_bbox`]) and collected in
+    /// memory. The endpoint enforces its own size and time limits; when it
+    /// rejects a request the returned `InfraHexError::Api` says so, and a
+    /// smaller bbox is the usual fix.
+    pub async fn export_geojson_by_bbox(
+        &self,
+        bbox: &BBox,
+    ) -> Result<FeatureCollection, InfraHexError> {
+        let features: Vec<Feature> = self
+            .stream_export_geojson_by_bbox(bbox)
+            .try_collect()
+            .await?;
+
+        Ok(FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        })
+    }
+
+    /// Streams the features of a GeoJSON export as they are downloaded.
+    ///
+    /// The response body is parsed incrementally, so only the feature being
+    /// read is held in memory rather than the whole document, keeping memory
+    /// bounded for large regional exports. If the connection drops part-way,
+    /// the features already yielded stand and the stream ends with an
+    /// `InfraHexError::Api` describing the truncation.
+    pub fn stream_export_geojson_by_bbox(
+        &self,
+        bbox: &BBox,
+    ) -> impl Stream<Item = Result<Feature, InfraHexError>> + '_ {
+        let url = format!(
+            "{}?where={}{}",
+            self.export_url(),
+            urlencoding::encode(&self.bbox_query(bbox)),
+            self.select_param()
+        );
+
+        let response = async move {
+            self.http.get(&url, None).await.map_err(|e| match e {
+                InfraHexError::Api(msg) => InfraHexError::Api(format!(
+                    "GeoJSON export failed ({}); the export endpoint limits response size \
+                     and duration, so try a smaller bounding box",
+                    msg
+                )),
+                e => e,
+            })
+        };
+
+        stream::once(response)
+            .map_ok(|response| {
+                stream::try_unfold(
+                    (response, FeatureStreamParser::new()),
+                    |(mut response, mut parser)| async move {
+                        match response.chunk().await? {
+                            Some(chunk) => {
+                                let features = parser.push(&chunk)?;
+                                Ok(Some((features, (response, parser))))
+                            }
+                            None => parser.finish().map(|()| None),
+                        }
+                    },
+                )
+            })
+            .try_flatten()
+            .map_ok(|features| stream::iter(features.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// URL of the dataset's GeoJSON export endpoint, a sibling of `records`.
+    pub(crate) fn export_url(&self) -> String {
+        let dataset = self
+            .base_url
+            .strip_suffix("/records")
+            .unwrap_or(&self.base_url);
+        format!("{}/exports/geojson", dataset)
+    }
+}
+
+impl<R: DeserializeOwned + Send> OpenDataSoftClient<R> {
+    /// Returns the number of records in a bounding box, using a single
+    /// `limit=1` request.
+    pub async fn count_by_bbox(&self, bbox: &BBox) -> Result<usize, InfraHexError> {
+        self.count_where(&self.bbox_query(bbox), "").await
+    }
+
+    /// Works out what [`InfraClient::fetch_all_by_bbox`] would do for a
+    /// bounding box without fetching any records.
+    ///
+    /// Only the count probe is sent; the page and batch counts follow from
+    /// it under the client's pagination settings the fetch uses. Check
+    /// [`FetchPlan::truncated`] before fetching regions that may exceed the
+    /// 10,000 record offset limit.
+    pub async fn plan_fetch(&self, bbox: &BBox) -> Result<FetchPlan, InfraHexError> {
+        let total = self.count_by_bbox(bbox).await?;
+        Ok(FetchPlan::new(total, &self.pagination))
+    }
+
+    /// Streams the records in a bounding box page by page.
+    ///
+    /// `total_count` is the number of matching records, as returned by
+    /// [`OpenDataSoftClient::count_by_bbox`]; it decides how many pages are
+    /// requested (subject to the OpenDataSoft offset limit). Pages are
+    /// fetched a batch at a time, as by [`stream_pages`], and a failed page
+    /// is yielded as an `Err` without ending the stream.
+    pub fn stream_pages_by_bbox(
+        &self,
+        bbox: &BBox,
+        total_count: usize,
+    ) -> impl Stream<Item = Result<Vec<R>, InfraHexError>> + '_ {
+        self.stream_pages_where(self.bbox_query(bbox), total_count)
+    }
+
+    /// Streams every record in a bounding box as its page arrives.
+    ///
+    /// Sends the same count probe and page requests as
+    /// [`InfraClient::fetch_all_by_bbox`], through
+    /// [`OpenDataSoftClient::stream_pages_by_bbox`], but hands records on as
+    /// each batch of pages completes instead of collecting them, so only one
+    /// batch is held in memory. A failed count probe or page is yielded as an
+    /// `Err` without ending the stream.
+    pub fn stream_by_bbox(&self, bbox: &BBox) -> impl Stream<Item = Result<R, InfraHexError>> + '_ {
+        let where_clause = self.bbox_query(bbox);
+        stream::once(async move {
+            let total = self.count_where(&where_clause, "").await?;
+            Ok::<_, InfraHexError>(self.stream_pages_where(where_clause, total))
+        })
+        .try_flatten()
+        .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Streams the pages of the `total` records matching a `where` clause.
+    fn stream_pages_where(
+        &self,
+        where_clause: String,
+        total: usize,
+    ) -> impl Stream<Item = Result<Vec<R>, InfraHexError>> + '_ {
+        stream_pages(total, self.pagination.clone(), move |offset, limit| {
+            let where_clause = where_clause.clone();
+            async move { self.fetch_page(&where_clause, "", limit, offset).await }
+        })
+    }
+
+    /// Fetches all records in a bounding box, narrowed by facet filters.
+    ///
+    /// Each `(field, value)` pair becomes an OpenDataSoft `refine` parameter
+    /// (e.g. `("material", "PE")`), an exact-match categorical filter applied
+    /// alongside the bbox `where` clause, as the portal UI does. Refines on the
+    /// same field are ORed together; refines on different fields are ANDed, so
+    /// `[("material", "PE"), ("material", "ST"), ("pressure", "LP")]` selects
+    /// low-pressure pipes made of either PE or steel.
+    pub async fn fetch_all_by_bbox_refined(
+        &self,
+        bbox: &BBox,
+        refines: &[(&str, &str)],
+    ) -> InfraResult<R> {
+        self.fetch_all_query(&self.bbox_query(bbox), &refine_params(refines))
+            .await
+    }
+
+    /// Fetches all records in a bounding box that also match an ODSQL
+    /// `filter`, e.g. `type = "HP" AND material = "ST"`.
+    ///
+    /// The filter is applied server-side, so only matching records are
+    /// transferred. It is parenthesised and ANDed with the bbox clause, so an
+    /// `OR` inside it can't widen the query beyond the bbox; an empty filter
+    /// fetches the whole bbox. A filter whose parentheses don't balance
+    /// (outside string literals) could close that group early, so it is
+    /// reported as an `InfraHexError::Config` in the result's `errors`
+    /// without any request being sent.
+    pub async fn fetch_all_by_bbox_where(&self, bbox: &BBox, filter: &str) -> InfraResult<R> {
+        if !parens_balanced(filter) {
+            let mut result = InfraResult::new();
+            result.errors.push(InfraHexError::Config(format!(
+                "Unbalanced parentheses in where filter: {}",
+                filter
+            )));
+            return result;
+        }
+        self.fetch_all_where(&and_where(&self.bbox_query(bbox), filter))
+            .await
+    }
+
+    /// Fetches all records in a bounding box without first probing for
+    /// `total_count`.
+    ///
+    /// Pages are requested one after another until a short or empty page
+    /// comes back, saving the probe's round trip. Prefer this when the exact
+    /// total isn't needed up front (there is no progress denominator), or
+    /// when the API reports `total_count: 0` for a query that does match,
+    /// which would make [`InfraClient::fetch_all_by_bbox`] return nothing.
+    /// Pages are not fetched in parallel, so for large regions with a
+    /// reliable count `fetch_all_by_bbox` is usually faster. The OpenDataSoft
+    /// offset limit still applies.
+    pub async fn fetch_all_by_bbox_unbounded(&self, bbox: &BBox) -> InfraResult<R> {
+        let where_clause = self.bbox_query(bbox);
+        fetch_pages_until_exhausted(self.pagination.clone(), |offset, limit| {
+            self.fetch_page(&where_clause, "", limit, offset)
+        })
+        .await
+    }
+
+    /// Fetches all records in a bounding box, keeping only those for which
+    /// `predicate` returns true.
+    ///
+    /// The predicate runs client-side as each page is deserialised, so
+    /// rejected records are dropped before the next batch arrives instead of
+    /// being held until the whole fetch completes. Use it for conditions the
+    /// API's `where` clause can't express, such as geometry tests or parsed
+    /// installation dates; anything ODSQL can express is cheaper to filter
+    /// server-side.
+    pub async fn fetch_all_by_bbox_filtered<P>(&self, bbox: &BBox, predicate: P) -> InfraResult<R>
+    where
+        P: Fn(&R) -> bool + Sync,
+    {
+        self.fetch_all_query_filtered(&self.bbox_query(bbox), "", &predicate)
+            .await
+    }
+
+    async fn fetch_page(
+        &self,
+        where_clause: &str,
+        extra_params: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<R>, InfraHexError> {
+        let url = format!(
+            "{}?where={}{}{}&limit={}&offset={}",
+            self.base_url,
+            urlencoding::encode(where_clause),
+            self.select_param(),
+            extra_params,
+            limit,
+            offset
+        );
+
+        let response: ApiResponse<R> = self.http.fetch_json(&url).await?;
+        Ok(response.results)
+    }
+
+    /// Returns `total_count` for a query by requesting a single record,
+    /// subject to the probe timeout.
+    async fn count_where(
+        &self,
+        where_clause: &str,
+        extra_params: &str,
+    ) -> Result<usize, InfraHexError> {
+        let url = format!(
+            "{}?where={}{}{}&limit=1",
+            self.base_url,
+            urlencoding::encode(where_clause),
+            self.select_param(),
+            extra_params,
+        );
+
+        let response: ApiResponse<R> = self
+            .http
+            .fetch_json_with_timeout(&url, self.probe_timeout)
+            .await?;
+        Ok(response.total_count as usize)
+    }
+
+    /// Fetches every record matching an ODSQL `where` clause.
+    pub(crate) async fn fetch_all_where(&self, where_clause: &str) -> InfraResult<R> {
+        self.fetch_all_query(where_clause, "").await
+    }
+
+    /// Fetches every record matching a `where` clause plus any already-encoded
+    /// `extra_params` (each starting with `&`).
+    async fn fetch_all_query(&self, where_clause: &str, extra_params: &str) -> InfraResult<R> {
+        self.fetch_all_query_filtered(where_clause, extra_params, &|_| true)
+            .await
+    }
+
+    /// Like [`OpenDataSoftClient::fetch_all_query`], dropping records
+    /// rejected by `predicate` as each page arrives.
+    async fn fetch_all_query_filtered<P>(
+        &self,
+        where_clause: &str,
+        extra_params: &str,
+        predicate: &P,
+    ) -> InfraResult<R>
+    where
+        P: Fn(&R) -> bool + Sync,
+    {
+        // Get total count first
+        let total = match self.count_where(where_clause, extra_params).await {
+            Ok(total) => total,
+            Err(e) => {
+                let mut result = InfraResult::new();
+                result.errors.push(e);
+                return result;
+            }
+        };
+
+        // Use pagination helper with OpenDataSoft config. Count records
+        // before filtering so `fetched_count` stays comparable to the probe
+        let received = &AtomicUsize::new(0);
+        let mut result =
+            fetch_all_pages(total, self.pagination.clone(), |offset, limit| async move {
+                let mut page = self
+                    .fetch_page(where_clause, extra_params, limit, offset)
+                    .await?;
+                received.fetch_add(page.len(), Ordering::Relaxed);
+                page.retain(|record| predicate(record));
+                Ok(page)
+            })
+            .await;
+        result.fetched_count = Some(received.load(Ordering::Relaxed));
+        result
+    }
+}
+
+impl<R: DeserializeOwned + Send> InfraClient for OpenDataSoftClient<R> {
+    type Record = R;
+
+    async fn fetch_by_bbox(
+        &self,
+        bbox: &BBox,
+        limit: Option<usize>,
+    ) -> Result<Vec<Self::Record>, InfraHexError> {
+        let limit = limit.unwrap_or(100);
+        let url = format!(
+            "{}?where={}{}&limit={}",
+            self.base_url,
+            urlencoding::encode(&self.bbox_query(bbox)),
+            self.select_param(),
+            limit
+        );
+
+        let response: ApiResponse<R> = self.http.fetch_json(&url).await?;
+        Ok(response.results)
+    }
+
+    async fn fetch_all_by_bbox(&self, bbox: &BBox) -> InfraResult<Self::Record> {
+        let started = Instant::now();
+        let mut result = self.fetch_all_where(&self.bbox_query(bbox)).await;
+
+        if let Some(log) = &self.audit_log {
+            let entry = AuditEntry::new(
+                self.dataset(),
+                bbox,
+                result.records.len(),
+                result.errors.len(),
+                started.elapsed(),
+            );
+            if let Err(e) = log.append(&entry) {
+                result.errors.push(e);
+            }
+        }
+
+        result
+    }
+}
+
+/// The default bbox `where` clause, on OpenDataSoft's `geo_point_2d` field.
+fn in_bbox_geo_point_2d(bbox: &BBox) -> String {
+    format!(
+        "in_bbox(geo_point_2d,{},{},{},{})",
+        bbox.min_lat, bbox.min_lon, bbox.max_lat, bbox.max_lon
+    )
+}
+
+/// Joins two `where` clauses with `AND`, parenthesising each. An empty
+/// `filter` leaves `clause` unchanged.
+pub(crate) fn and_where(clause: &str, filter: &str) -> String {
+    if filter.trim().is_empty() {
+        clause.to_string()
+    } else {
+        format!("({}) AND ({})", clause, filter)
+    }
+}
+
+/// Whether every `(` in an ODSQL clause is closed by a later `)`, ignoring
+/// parentheses inside string literals and backquoted field names.
+fn parens_balanced(clause: &str) -> bool {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut chars = clause.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            (None, _) => {}
+        }
+    }
+    depth == 0 && quote.is_none()
+}
+
+/// Encodes facet filters as `&refine=field:"value"` query parameters.
+fn refine_params(refines: &[(&str, &str)]) -> String {
+    refines
+        .iter()
+        .map(|(field, value)| {
+            let refine = format!("{}:{}", field, odsql_string(value));
+            format!("&refine={}", urlencoding::encode(&refine))
+        })
+        .collect()
+}
+
+/// Escapes a value as an ODSQL double-quoted string literal.
+pub(crate) fn odsql_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Client = OpenDataSoftClient<serde_json::Value>;
+
+    #[test]
+    fn test_from_catalog_builds_records_url() {
+        let client = Client::from_catalog(
+            "https://northerngasopendataportal.co.uk/api/explore/v2.1/catalog/",
+            "pipes",
+        );
+        assert_eq!(
+            client.base_url,
+            "https://northerngasopendataportal.co.uk/api/explore/v2.1/catalog/datasets/pipes/records"
+        );
+        assert_eq!(client.dataset(), "pipes");
+    }
+
+    #[test]
+    fn test_where_builder_replaces_bbox_query() {
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let client = Client::from_catalog("https://example.com/catalog", "pipes");
+        assert_eq!(
+            client.bbox_query(&bbox),
+            "in_bbox(geo_point_2d,53.47,-2.26,53.49,-2.22)"
+        );
+
+        let client = client.with_where_builder(|b: &BBox| {
+            format!(
+                "in_bbox(geo_shape,{},{},{},{})",
+                b.min_lat, b.min_lon, b.max_lat, b.max_lon
+            )
+        });
+        assert_eq!(
+            client.bbox_query(&bbox),
+            "in_bbox(geo_shape,53.47,-2.26,53.49,-2.22)"
+        );
+    }
+
+    #[test]
+    fn test_select_param_adds_required_fields() {
+        let client = Client::from_catalog("https://example.com/catalog", "pipes");
+        assert_eq!(client.select_param(), "");
+
+        let mut client = client.with_select(&["type", "geo_shape"]);
+        assert_eq!(client.select_param(), "&select=type%2Cgeo_shape");
+
+        client.required_fields = &["geo_point_2d", "geo_shape"];
+        assert_eq!(
+            client.select_param(),
+            "&select=type%2Cgeo_shape%2Cgeo_point_2d"
+        );
+    }
+
+    #[test]
+    fn test_and_where_parenthesises_both_sides() {
+        assert_eq!(
+            and_where("in_bbox(geo_point_2d,1,2,3,4)", "a = 1 OR b = 2"),
+            "(in_bbox(geo_point_2d,1,2,3,4)) AND (a = 1 OR b = 2)"
+        );
+        assert_eq!(and_where("in_bbox(x)", " "), "in_bbox(x)");
+    }
+
+    #[test]
+    fn test_parens_balanced() {
+        assert!(parens_balanced("(a = 1 OR b = 2) AND c = 3"));
+        assert!(parens_balanced("name = \")(\" OR `odd)` = 1"));
+        assert!(!parens_balanced("a = 1) OR (b = 2"));
+        assert!(!parens_balanced("(a = 1"));
+        assert!(!parens_balanced("a = \"unterminated"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_by_bbox_where_rejects_unbalanced_filter() {
+        // Nothing listens here, so any request would fail with Http
+        let mut client = Client::from_catalog("http://127.0.0.1:9", "pipes");
+        client.base_url = "http://127.0.0.1:9/records".to_string();
+
+        let bbox = BBox::new(53.47, -2.26, 53.49, -2.22);
+        let result = client
+            .fetch_all_by_bbox_where(&bbox, "a = 1) OR (b = 2")
+            .await;
+        assert!(result.records.is_empty());
+        assert!(matches!(result.errors[..], [InfraHexError::Config(_)]));
+    }
+
+    #[test]
+    fn test_odsql_string_escapes_quotes() {
+        assert_eq!(odsql_string("ABC-1"), "\"ABC-1\"");
+        assert_eq!(odsql_string("a\"b"), "\"a\\\"b\"");
+        assert_eq!(odsql_string("a\\b"), "\"a\\\\b\"");
+    }
+
+    #[test]
+    fn test_refine_params_encoding() {
+        assert_eq!(refine_params(&[]), "");
+        assert_eq!(
+            refine_params(&[("material", "PE"), ("pressure", "LP")]),
+            "&refine=material%3A%22PE%22&refine=pressure%3A%22LP%22"
+        );
+        assert_eq!(
+            refine_params(&[("material", "A&B")]),
+            "&refine=material%3A%22A%26B%22"
+        );
+    }
+}
@@ -1,19 +1,43 @@
 use futures::future::join_all;
+use futures::{Stream, StreamExt};
+use std::fmt;
 use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::time::{Duration, sleep};
 
 use crate::error::InfraHexError;
 
 use super::types::InfraResult;
 
+/// Callback invoked with a [`FetchProgress`] as a paginated fetch advances.
+pub type ProgressCallback = Arc<dyn Fn(FetchProgress) + Send + Sync>;
+
 /// TODO: Need to make this trait based to allow for other pagination options
 /// Configuration for paginated fetching.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PaginationConfig {
     pub page_size: usize,
     pub batch_size: usize,
     pub batch_delay: Duration,
     pub max_offset: Option<usize>,
+    pub page_retries: usize,
+    pub total_retry_budget: Option<usize>,
+    pub progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for PaginationConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PaginationConfig")
+            .field("page_size", &self.page_size)
+            .field("batch_size", &self.batch_size)
+            .field("batch_delay", &self.batch_delay)
+            .field("max_offset", &self.max_offset)
+            .field("page_retries", &self.page_retries)
+            .field("total_retry_budget", &self.total_retry_budget)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
 }
 
 impl Default for PaginationConfig {
@@ -23,6 +47,9 @@ impl Default for PaginationConfig {
             batch_size: 100,
             batch_delay: Duration::from_millis(100),
             max_offset: None,
+            page_retries: 0,
+            total_retry_budget: None,
+            progress: None,
         }
     }
 }
@@ -36,6 +63,16 @@ impl PaginationConfig {
         }
     }
 
+    /// Creates a config for ArcGIS Feature Servers: pages of 2,000 records,
+    /// the usual `maxRecordCount`, four pages at a time and no offset limit.
+    pub fn arcgis() -> Self {
+        Self {
+            page_size: 2_000,
+            batch_size: 4,
+            ..Default::default()
+        }
+    }
+
     /// Sets the page size.
     pub fn with_page_size(mut self, size: usize) -> Self {
         self.page_size = size;
@@ -59,6 +96,272 @@ impl PaginationConfig {
         self.max_offset = Some(max);
         self
     }
+
+    /// Sets how many times a failed page is retried (waiting `batch_delay`
+    /// between attempts). Defaults to 0, i.e. no retries.
+    ///
+    /// This is on top of any per-request retries set with
+    /// [`HttpClient::with_retry`]; each page retry starts a fresh round of
+    /// those, so enabling both multiplies the attempts per page.
+    ///
+    /// [`HttpClient::with_retry`]: crate::client::types::HttpClient::with_retry
+    pub fn with_page_retries(mut self, retries: usize) -> Self {
+        self.page_retries = retries;
+        self
+    }
+
+    /// Caps the total number of retries across all pages in one fetch.
+    ///
+    /// Once the budget is spent, further page failures go straight to
+    /// `errors` without retrying, so a struggling server isn't hit with a
+    /// retry storm. Has no effect unless `page_retries` is non-zero.
+    pub fn with_total_retry_budget(mut self, budget: usize) -> Self {
+        self.total_retry_budget = Some(budget);
+        self
+    }
+
+    /// Calls `progress` after each batch of pages completes, e.g. to drive
+    /// a progress bar.
+    ///
+    /// Used by [`fetch_all_pages`] and [`stream_pages`]. Unset by
+    /// default, in which case nothing is computed or called.
+    pub fn with_progress(mut self, progress: ProgressCallback) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Reports progress to the callback, if one is set.
+    fn report_progress(&self, completed_pages: usize, total_pages: usize, records_so_far: usize) {
+        if let Some(progress) = &self.progress {
+            progress(FetchProgress {
+                completed_pages,
+                total_pages,
+                records_so_far,
+            });
+        }
+    }
+}
+
+/// Progress of a paginated fetch, reported as pages complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchProgress {
+    /// Pages finished so far, whether they succeeded or failed.
+    pub completed_pages: usize,
+    /// Pages the fetch will request in total.
+    pub total_pages: usize,
+    /// Records successfully fetched so far.
+    pub records_so_far: usize,
+}
+
+/// What a paginated fetch would do, worked out from the record count alone.
+///
+/// Returned by [`CadentClient::plan_fetch`] so tools can warn before an
+/// expensive fetch, e.g. "this will fetch 23 pages, ~2300 records, no
+/// truncation".
+///
+/// [`CadentClient::plan_fetch`]: crate::client::CadentClient::plan_fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchPlan {
+    /// Records matching the query.
+    pub total_count: usize,
+    /// Records the fetch can actually reach under the offset limit.
+    pub fetchable_count: usize,
+    /// Page requests the fetch will make.
+    pub pages: usize,
+    /// Batches of concurrent page requests, separated by `batch_delay`.
+    pub batches: usize,
+    /// Whether the offset limit would cut the fetch short.
+    pub truncated: bool,
+}
+
+impl FetchPlan {
+    /// Plans a fetch of `total_count` records under `config`.
+    pub fn new(total_count: usize, config: &PaginationConfig) -> Self {
+        let pages = page_count(total_count, config);
+        let fetchable_count = config
+            .max_offset
+            .map_or(total_count, |max| total_count.min(max));
+
+        Self {
+            total_count,
+            fetchable_count,
+            pages,
+            batches: pages.div_ceil(config.batch_size.max(1)),
+            truncated: fetchable_count < total_count,
+        }
+    }
+
+    /// Whether the bounding box should be split into smaller ones, each
+    /// under the offset limit, so that no records are missed.
+    pub fn subdivision_recommended(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// Retries remaining for a single `fetch_all_pages` call, shared by all pages.
+struct RetryBudget {
+    remaining: Option<AtomicUsize>,
+}
+
+impl RetryBudget {
+    fn new(budget: Option<usize>) -> Self {
+        Self {
+            remaining: budget.map(AtomicUsize::new),
+        }
+    }
+
+    /// Takes one retry from the budget, returning false if it's exhausted.
+    fn try_take(&self) -> bool {
+        match &self.remaining {
+            Some(remaining) => remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok(),
+            None => true,
+        }
+    }
+}
+
+/// Fetches a single page, retrying failures while both the per-page limit and
+/// the shared budget allow.
+async fn fetch_page_with_retries<T, F, Fut>(
+    fetch_page: &F,
+    offset: usize,
+    config: &PaginationConfig,
+    budget: &RetryBudget,
+) -> Result<Vec<T>, InfraHexError>
+where
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, InfraHexError>>,
+{
+    let mut retries = 0;
+    loop {
+        match fetch_page(offset, config.page_size).await {
+            Ok(records) => return Ok(records),
+            Err(e) => {
+                if retries >= config.page_retries || !budget.try_take() {
+                    return Err(e);
+                }
+                retries += 1;
+                sleep(config.batch_delay).await;
+            }
+        }
+    }
+}
+
+/// Number of pages needed to fetch `total_count` items under `config`'s
+/// page size and offset limit.
+pub fn page_count(total_count: usize, config: &PaginationConfig) -> usize {
+    let fetchable = match config.max_offset {
+        Some(max) => total_count.min(max),
+        None => total_count,
+    };
+    fetchable.div_ceil(config.page_size.max(1))
+}
+
+/// Streams pages in offset order instead of collecting them.
+///
+/// The streaming counterpart to [`fetch_all_pages`], with the same
+/// concurrency, retries, progress reports and `batch_delay` pause between
+/// batches: each batch of `batch_size` pages is fetched concurrently and its
+/// pages are yielded as soon as the batch completes, so only one batch is
+/// held in memory (a `batch_size` of 1 fetches and holds one page at a
+/// time). A failed page is yielded as an `Err` and the stream carries on
+/// with the next page, so callers decide whether to stop.
+pub fn stream_pages<T, F, Fut>(
+    total_count: usize,
+    config: PaginationConfig,
+    fetch_page: F,
+) -> impl Stream<Item = Result<Vec<T>, InfraHexError>>
+where
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, InfraHexError>>,
+{
+    let offsets: Vec<usize> = (0..page_count(total_count, &config))
+        .map(|page| page * config.page_size)
+        .collect();
+    let budget = RetryBudget::new(config.total_retry_budget);
+
+    futures::stream::unfold(
+        (0usize, 0usize, offsets, config, budget, fetch_page),
+        move |(start, records_so_far, offsets, config, budget, fetch_page)| async move {
+            if start >= offsets.len() {
+                return None;
+            }
+
+            // Rate limiting delay between batches, as in `fetch_all_pages`
+            if start > 0 {
+                sleep(config.batch_delay).await;
+            }
+
+            let end = (start + config.batch_size.max(1)).min(offsets.len());
+            let pages = join_all(
+                offsets[start..end]
+                    .iter()
+                    .map(|&offset| fetch_page_with_retries(&fetch_page, offset, &config, &budget)),
+            )
+            .await;
+
+            let records_so_far = records_so_far
+                + pages
+                    .iter()
+                    .map(|page| page.as_ref().map_or(0, Vec::len))
+                    .sum::<usize>();
+            config.report_progress(end, offsets.len(), records_so_far);
+
+            Some((
+                futures::stream::iter(pages),
+                (end, records_so_far, offsets, config, budget, fetch_page),
+            ))
+        },
+    )
+    .flatten()
+}
+
+/// Fetches pages sequentially until one comes back short or empty, without
+/// knowing the total count up front.
+///
+/// Honours `max_offset`, the page retries and the retry budget, and pauses
+/// for `batch_delay` after every `batch_size` pages. A page that still fails
+/// after its retries ends the fetch, since there's no count to say whether
+/// more pages follow; its error is returned alongside the records so far.
+pub async fn fetch_pages_until_exhausted<T, F, Fut>(
+    config: PaginationConfig,
+    fetch_page: F,
+) -> InfraResult<T>
+where
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, InfraHexError>>,
+{
+    let mut result = InfraResult::new();
+    let budget = RetryBudget::new(config.total_retry_budget);
+    let page_size = config.page_size.max(1);
+
+    for page in 0.. {
+        let offset = page * page_size;
+        if config.max_offset.is_some_and(|max| offset >= max) {
+            break;
+        }
+
+        if page > 0 && page % config.batch_size.max(1) == 0 {
+            sleep(config.batch_delay).await;
+        }
+
+        match fetch_page_with_retries(&fetch_page, offset, &config, &budget).await {
+            Ok(records) => {
+                let exhausted = records.len() < page_size;
+                result.records.extend(records);
+                if exhausted {
+                    break;
+                }
+            }
+            Err(e) => {
+                result.errors.push(e);
+                break;
+            }
+        }
+    }
+
+    result
 }
 
 /// Fetches all pages in parallel batches with rate limiting.
@@ -71,7 +374,10 @@ impl PaginationConfig {
 ///
 /// # Returns
 ///
-/// An `InfraResult` containing all successfully fetched records and any errors.
+/// An `InfraResult` containing all successfully fetched records and any errors,
+/// with `expected_count` set to `total_count` and `fetched_count` to the
+/// number of records received. These differ when `max_offset` caps the fetch
+/// or pages fail; see [`InfraResult::is_count_consistent`].
 ///
 /// # Example
 ///
@@ -93,27 +399,66 @@ where
     T: Send,
     F: Fn(usize, usize) -> Fut,
     Fut: Future<Output = Result<Vec<T>, InfraHexError>> + Send,
+{
+    fetch_all_pages_cancellable(total_count, config, fetch_page, std::future::pending()).await
+}
+
+/// Like [`fetch_all_pages`], but stops early once `cancel` completes.
+///
+/// Cancellation is checked between batches: a batch already in flight is
+/// allowed to finish, then no further batches are started and the records
+/// and errors collected so far are returned. A cancelled fetch therefore
+/// reports `fetched_count` below `expected_count`. Any future resolving to
+/// `()` works as the signal, such as `tokio_util`'s
+/// `CancellationToken::cancelled()` or a `oneshot` receiver.
+///
+/// ```ignore
+/// let token = CancellationToken::new();
+/// let result = fetch_all_pages_cancellable(
+///     total_count,
+///     PaginationConfig::opendatasoft(),
+///     |offset, limit| async move { client.fetch_page(offset, limit).await },
+///     token.cancelled(),
+/// ).await;
+/// ```
+pub async fn fetch_all_pages_cancellable<T, F, Fut, C>(
+    total_count: usize,
+    config: PaginationConfig,
+    fetch_page: F,
+    cancel: C,
+) -> InfraResult<T>
+where
+    T: Send,
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, InfraHexError>> + Send,
+    C: Future<Output = ()>,
 {
     let mut result = InfraResult::new();
+    result.expected_count = Some(total_count);
+    result.fetched_count = Some(0);
 
     if total_count == 0 {
         return result;
     }
 
-    // Apply max offset limit if configured
-    let fetchable = match config.max_offset {
-        Some(max) => total_count.min(max),
-        None => total_count,
-    };
-
-    // Generate all offsets
-    let offsets: Vec<usize> = (0..fetchable).step_by(config.page_size).collect();
+    // Generate all offsets, applying the max offset limit if configured
+    let offsets: Vec<usize> = (0..page_count(total_count, &config))
+        .map(|page| page * config.page_size)
+        .collect();
+    let budget = RetryBudget::new(config.total_retry_budget);
+    let mut completed_pages = 0;
+    let mut cancel = std::pin::pin!(cancel);
+    let mut cancelled = false;
 
     // Process in batches
     for chunk in offsets.chunks(config.batch_size) {
+        if cancelled || futures::poll!(cancel.as_mut()).is_ready() {
+            break;
+        }
+
         let futures: Vec<_> = chunk
             .iter()
-            .map(|&offset| fetch_page(offset, config.page_size))
+            .map(|&offset| fetch_page_with_retries(&fetch_page, offset, &config, &budget))
             .collect();
 
         let batch_results = join_all(futures).await;
@@ -125,13 +470,20 @@ where
             }
         }
 
+        completed_pages += chunk.len();
+        config.report_progress(completed_pages, offsets.len(), result.records.len());
+
         // Rate limiting delay between batches (skip delay after last batch)
         // This is because the API will throttle bursts
         if !chunk.is_empty() && chunk.last() != offsets.last() {
-            sleep(config.batch_delay).await;
+            tokio::select! {
+                _ = sleep(config.batch_delay) => {}
+                _ = cancel.as_mut() => cancelled = true,
+            }
         }
     }
 
+    result.fetched_count = Some(result.records.len());
     result
 }
 
@@ -139,7 +491,6 @@ where
 mod tests {
     use super::*;
     use std::sync::Arc;
-    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[tokio::test]
     async fn test_fetch_all_pages_empty() {
@@ -217,6 +568,37 @@ mod tests {
         assert!(result.errors.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_fetch_all_pages_reports_capped_count_mismatch() {
+        let result = fetch_all_pages(
+            12_000,
+            PaginationConfig::opendatasoft()
+                .with_page_size(1000)
+                .with_batch_delay(Duration::from_millis(1)),
+            |_offset, limit| async move { Ok(vec![0u8; limit]) },
+        )
+        .await;
+
+        // No page failed, but the 10,000 offset cap left 2,000 records behind
+        assert!(result.is_complete());
+        assert_eq!(result.expected_count, Some(12_000));
+        assert_eq!(result.fetched_count, Some(10_000));
+        assert!(!result.is_count_consistent());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_count_consistent() {
+        let result = fetch_all_pages(
+            250,
+            PaginationConfig::default().with_batch_delay(Duration::from_millis(1)),
+            |offset, limit| async move { Ok(vec![0u8; limit.min(250 - offset)]) },
+        )
+        .await;
+
+        assert_eq!(result.fetched_count, Some(250));
+        assert!(result.is_count_consistent());
+    }
+
     #[tokio::test]
     async fn test_fetch_all_pages_handles_errors() {
         let result = fetch_all_pages(
@@ -237,4 +619,247 @@ mod tests {
         assert_eq!(result.records, vec![1, 2, 3]);
         assert_eq!(result.errors.len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_cancelled_after_first_batch() {
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        let tx = std::sync::Mutex::new(Some(tx));
+        let hits = Arc::new(AtomicUsize::new(0));
+
+        let config = PaginationConfig::default()
+            .with_page_size(10)
+            .with_batch_size(2)
+            .with_batch_delay(Duration::from_millis(50));
+
+        let result: InfraResult<usize> = fetch_all_pages_cancellable(
+            50,
+            config,
+            |offset, limit| {
+                hits.fetch_add(1, Ordering::SeqCst);
+                // Cancel while the first batch is in flight
+                if let Some(tx) = tx.lock().unwrap().take() {
+                    let _ = tx.send(());
+                }
+                async move { Ok((offset..offset + limit).collect()) }
+            },
+            async {
+                let _ = rx.await;
+            },
+        )
+        .await;
+
+        // The first batch completes; the other three of five pages are never
+        // sent
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert_eq!(result.records.len(), 20);
+        assert_eq!(result.expected_count, Some(50));
+        assert_eq!(result.fetched_count, Some(20));
+        assert!(!result.is_count_consistent());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_reports_progress_per_batch() {
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = reports.clone();
+
+        let config = PaginationConfig::default()
+            .with_page_size(10)
+            .with_batch_size(2)
+            .with_batch_delay(Duration::from_millis(1))
+            .with_progress(Arc::new(move |progress| {
+                seen.lock().unwrap().push(progress)
+            }));
+
+        let result: InfraResult<usize> = fetch_all_pages(45, config, |offset, limit| async move {
+            Ok((offset..(offset + limit).min(45)).collect())
+        })
+        .await;
+        assert_eq!(result.records.len(), 45);
+
+        let reports = reports.lock().unwrap();
+        let completed: Vec<_> = reports.iter().map(|p| p.completed_pages).collect();
+        let records: Vec<_> = reports.iter().map(|p| p.records_so_far).collect();
+        assert_eq!(completed, vec![2, 4, 5]);
+        assert_eq!(records, vec![20, 40, 45]);
+        assert!(reports.iter().all(|p| p.total_pages == 5));
+    }
+
+    #[test]
+    fn test_fetch_plan_matches_page_count() {
+        let config = PaginationConfig::opendatasoft().with_batch_size(10);
+        for total in [0, 1, 100, 2_301, 10_000, 12_000] {
+            let plan = FetchPlan::new(total, &config);
+            assert_eq!(plan.pages, page_count(total, &config));
+            assert_eq!(plan.batches, plan.pages.div_ceil(10));
+        }
+
+        let plan = FetchPlan::new(2_301, &config);
+        assert_eq!(plan.pages, 24);
+        assert_eq!(plan.fetchable_count, 2_301);
+        assert!(!plan.truncated);
+
+        let plan = FetchPlan::new(12_000, &config);
+        assert_eq!(plan.pages, 100);
+        assert_eq!(plan.fetchable_count, 10_000);
+        assert!(plan.truncated);
+        assert!(plan.subdivision_recommended());
+    }
+
+    #[tokio::test]
+    async fn test_stream_pages_fetches_batches_concurrently_in_order() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let pages: Vec<_> = stream_pages(
+            450,
+            PaginationConfig::default()
+                .with_page_size(100)
+                .with_batch_size(2)
+                .with_batch_delay(Duration::from_millis(1)),
+            |offset, _limit| {
+                let in_flight = in_flight.clone();
+                let peak = peak.clone();
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    sleep(Duration::from_millis(5)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    if offset == 100 {
+                        Err(InfraHexError::Api("Test error".to_string()))
+                    } else {
+                        Ok(vec![offset as i32])
+                    }
+                }
+            },
+        )
+        .collect()
+        .await;
+
+        assert_eq!(pages.len(), 5);
+        assert_eq!(pages[0].as_ref().unwrap(), &vec![0]);
+        assert!(pages[1].is_err());
+        assert_eq!(pages[4].as_ref().unwrap(), &vec![400]);
+        // Two pages at a time, never more
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pages_until_exhausted_stops_on_short_page() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = call_count.clone();
+
+        let result = fetch_pages_until_exhausted(
+            PaginationConfig::default().with_page_size(2),
+            move |offset, limit| {
+                let cc = call_count_clone.clone();
+                async move {
+                    cc.fetch_add(1, Ordering::SeqCst);
+                    // Five records in total
+                    Ok((offset..(offset + limit).min(5)).collect::<Vec<_>>())
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(call_count.load(Ordering::SeqCst), 3);
+        assert_eq!(result.records, vec![0, 1, 2, 3, 4]);
+        assert!(result.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pages_until_exhausted_respects_max_offset() {
+        let result = fetch_pages_until_exhausted(
+            PaginationConfig::default()
+                .with_page_size(2)
+                .with_max_offset(4),
+            |offset, _limit| async move { Ok(vec![offset, offset + 1]) },
+        )
+        .await;
+
+        assert_eq!(result.records, vec![0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pages_until_exhausted_stops_on_error() {
+        let result: InfraResult<usize> = fetch_pages_until_exhausted(
+            PaginationConfig::default().with_page_size(2),
+            |offset, _limit| async move {
+                if offset == 0 {
+                    Ok(vec![0, 1])
+                } else {
+                    Err(InfraHexError::Api("Test error".to_string()))
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result.records, vec![0, 1]);
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_page_count_respects_max_offset() {
+        let config = PaginationConfig::default().with_page_size(100);
+        assert_eq!(page_count(0, &config), 0);
+        assert_eq!(page_count(250, &config), 3);
+        assert_eq!(page_count(1000, &config.with_max_offset(300)), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_retries_failed_page() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = call_count.clone();
+
+        let result = fetch_all_pages(
+            100,
+            PaginationConfig::default()
+                .with_page_size(100)
+                .with_page_retries(2)
+                .with_batch_delay(Duration::from_millis(1)),
+            move |_offset, _limit| {
+                let cc = call_count_clone.clone();
+                async move {
+                    // Fail the first attempt only
+                    if cc.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(InfraHexError::Api("Transient".to_string()))
+                    } else {
+                        Ok(vec![1])
+                    }
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        assert_eq!(result.records, vec![1]);
+        assert!(result.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_total_retry_budget() {
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = call_count.clone();
+
+        let result: InfraResult<i32> = fetch_all_pages(
+            500,
+            PaginationConfig::default()
+                .with_page_size(100)
+                .with_page_retries(3)
+                .with_total_retry_budget(2)
+                .with_batch_delay(Duration::from_millis(1)),
+            move |_offset, _limit| {
+                let cc = call_count_clone.clone();
+                async move {
+                    cc.fetch_add(1, Ordering::SeqCst);
+                    Err(InfraHexError::Api("Always fails".to_string()))
+                }
+            },
+        )
+        .await;
+
+        // 5 initial attempts plus only 2 retries from the shared budget
+        assert_eq!(call_count.load(Ordering::SeqCst), 7);
+        assert_eq!(result.errors.len(), 5);
+    }
 }
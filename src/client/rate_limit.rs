@@ -0,0 +1,135 @@
+use std::sync::Mutex;
+use std::time::Instant;
+use tokio::time::{Duration, sleep};
+
+use crate::error::InfraHexError;
+
+/// A token bucket limiting how often requests are sent.
+///
+/// Build one and share it, as an `Arc<RateLimiter>`, between every client
+/// that talks to the same API (see [`HttpClient::with_rate_limiter`]) so
+/// their combined traffic stays under a shared per-API or per-IP quota.
+/// Each request takes one token; tokens refill continuously at
+/// `requests_per_second`, and up to `burst` can be saved up while idle.
+///
+/// [`HttpClient::with_rate_limiter`]: crate::client::types::HttpClient::with_rate_limiter
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    /// Available tokens; negative when waiters have reserved future tokens.
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a full bucket allowing `requests_per_second` on average and
+    /// bursts of up to `burst` requests.
+    ///
+    /// Returns `InfraHexError::Config` unless the rate is positive and
+    /// finite and the burst is at least 1.
+    pub fn new(requests_per_second: f64, burst: u32) -> Result<Self, InfraHexError> {
+        if !requests_per_second.is_finite() || requests_per_second <= 0.0 {
+            return Err(InfraHexError::Config(format!(
+                "Rate limit must be positive, got {}",
+                requests_per_second
+            )));
+        }
+        if burst == 0 {
+            return Err(InfraHexError::Config(
+                "Rate limit burst must be at least 1".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            rate: requests_per_second,
+            burst: f64::from(burst),
+            state: Mutex::new(BucketState {
+                tokens: f64::from(burst),
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    /// Waits until a token is available and takes it.
+    ///
+    /// Tokens are reserved in call order, so concurrent callers are served
+    /// first come, first served rather than racing on each refill.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            sleep(wait).await;
+        }
+    }
+
+    /// Takes a token, returning how long the caller must wait before it is
+    /// valid.
+    fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.burst);
+        state.last_refill = now;
+
+        state.tokens -= 1.0;
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_rate_limiter_rejects_invalid_config() {
+        assert!(RateLimiter::new(0.0, 1).is_err());
+        assert!(RateLimiter::new(f64::NAN, 1).is_err());
+        assert!(RateLimiter::new(10.0, 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_caps_request_rate() {
+        // 50 requests per second with no burst beyond the first request
+        let limiter = Arc::new(RateLimiter::new(50.0, 1).unwrap());
+        let start = Instant::now();
+
+        // Two "clients" sharing one limiter
+        let tasks: Vec<_> = (0..2)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    for _ in 0..3 {
+                        limiter.acquire().await;
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // Six requests: the first is free, the other five wait 20 ms each
+        assert!(start.elapsed() >= Duration::from_millis(95));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_allows_burst() {
+        let limiter = RateLimiter::new(1.0, 5).unwrap();
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+}
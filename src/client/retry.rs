@@ -0,0 +1,213 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Retry policy for transient HTTP failures.
+///
+/// Used by [`HttpClient::with_retry`]. A request is retried when the server
+/// answers 429, 500, 502, 503 or 504, or when the connection fails; other
+/// statuses, such as 400, 401 or 404, fail immediately. The wait before
+/// retry `n` (counting from 0) is `base_delay * 2^n`, capped at `max_delay`,
+/// unless the response carries a `Retry-After` header, in which case the
+/// server's requested wait is used instead, still capped at `max_delay` so a
+/// misbehaving server can't stall the client for hours.
+///
+/// [`HttpClient::with_retry`]: crate::client::types::HttpClient::with_retry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Retries after the first attempt, so a request is sent at most
+    /// `max_retries + 1` times.
+    pub max_retries: usize,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Creates the default policy: 3 retries, starting at 500 ms and capped
+    /// at 30 seconds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many times a failed request is retried.
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the wait before the first retry.
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Caps the wait between retries.
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Wait before retry number `attempt`, counting from 0, preferring the
+    /// server's `Retry-After` value when one was sent. Either way the wait
+    /// never exceeds `max_delay`.
+    pub(crate) fn delay(&self, attempt: usize, retry_after: Option<Duration>) -> Duration {
+        match retry_after {
+            Some(wait) => wait.min(self.max_delay),
+            None => self.backoff(attempt),
+        }
+    }
+
+    /// Exponential backoff before retry number `attempt`, counting from 0.
+    pub(crate) fn backoff(&self, attempt: usize) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.min(31) as u32);
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+}
+
+/// Whether a response status is worth retrying.
+pub(crate) fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Reads a `Retry-After` header, in either its delay-seconds form (`120`)
+/// or its HTTP-date form (`Wed, 21 Oct 2015 07:28:00 GMT`).
+///
+/// A date in the past gives a zero wait. Returns `None` if the header is
+/// missing or unparseable.
+pub(crate) fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let value = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let at = parse_http_date(value)?;
+    Some(
+        at.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Parses an IMF-fixdate such as `Wed, 21 Oct 2015 07:28:00 GMT`, the form
+/// HTTP/1.1 servers must send.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, rest) = value.split_once(", ")?;
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+
+    let day: u64 = day.parse().ok()?;
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|m| m == month)? as u64
+        + 1;
+    let year: u64 = year.parse().ok()?;
+
+    let mut hms = time.split(':').map(|p| p.parse::<u64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if !(1..=31).contains(&day) || year < 1970 || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Days since the Unix epoch of a proleptic Gregorian date
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y / 400;
+    let year_of_era = y % 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+
+    let secs = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+/// Whether a failed request is worth retrying: connection failures and
+/// errors sending the request, such as a reset connection.
+pub(crate) fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_request()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let config = RetryConfig::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(500));
+
+        assert_eq!(config.backoff(0), Duration::from_millis(100));
+        assert_eq!(config.backoff(1), Duration::from_millis(200));
+        assert_eq!(config.backoff(2), Duration::from_millis(400));
+        assert_eq!(config.backoff(3), Duration::from_millis(500));
+        assert_eq!(config.backoff(100), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_parse_http_date() {
+        let at = parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+        assert_eq!(
+            at.duration_since(UNIX_EPOCH).unwrap(),
+            Duration::from_secs(1_445_412_480)
+        );
+        let leap = parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT").unwrap();
+        assert_eq!(
+            leap.duration_since(UNIX_EPOCH).unwrap(),
+            Duration::from_secs(1_709_164_800)
+        );
+
+        assert!(parse_http_date("21 Oct 2015").is_none());
+        assert!(parse_http_date("Wed, 21 Foo 2015 07:28:00 GMT").is_none());
+    }
+
+    #[test]
+    fn test_delay_prefers_retry_after() {
+        let config = RetryConfig::new().with_max_delay(Duration::from_secs(10));
+        assert_eq!(
+            config.delay(0, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.delay(0, Some(Duration::from_secs(60))),
+            Duration::from_secs(10)
+        );
+        assert_eq!(config.delay(1, None), config.backoff(1));
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        for code in [429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(
+                reqwest::StatusCode::from_u16(code).unwrap()
+            ));
+        }
+        for code in [400, 401, 403, 404] {
+            assert!(!is_retryable_status(
+                reqwest::StatusCode::from_u16(code).unwrap()
+            ));
+        }
+    }
+}
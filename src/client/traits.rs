@@ -38,4 +38,34 @@ pub trait PipelineData: Send + Sync {
 
     /// Returns the pressure classification, if available.
     fn pressure(&self) -> Option<&str>;
+
+    /// Returns the pipe's diameter in millimetres, if known.
+    /// Defaults to `None`.
+    fn diameter_mm(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns whether the asset is above ground, if known.
+    /// Defaults to `None` for sources that don't record this.
+    fn is_above_ground(&self) -> Option<bool> {
+        None
+    }
+
+    /// Returns the material of the carrier pipe the asset runs inside, if
+    /// any. Defaults to `None`.
+    fn carrier_material(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the carrier pipe's diameter in millimetres, if known.
+    /// Defaults to `None`.
+    fn carrier_diameter_mm(&self) -> Option<f64> {
+        None
+    }
+
+    /// Returns the carrier diameter's unit as recorded at source, if any.
+    /// Defaults to `None`.
+    fn carrier_diameter_unit(&self) -> Option<&str> {
+        None
+    }
 }
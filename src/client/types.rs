@@ -1,9 +1,15 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use geo_types::Point;
 use serde::Deserialize;
 use serde::de::DeserializeOwned;
 
 use crate::error::InfraHexError;
 
+use super::rate_limit::RateLimiter;
+use super::retry::{RetryConfig, is_retryable_error, is_retryable_status, retry_after};
+
 #[derive(Debug, Deserialize)]
 pub struct ApiResponse<T> {
     pub total_count: u64,
@@ -14,6 +20,11 @@ pub struct ApiResponse<T> {
 pub struct InfraResult<T> {
     pub records: Vec<T>,
     pub errors: Vec<InfraHexError>,
+    /// Records the count probe said matched, when the fetch probed first.
+    pub expected_count: Option<usize>,
+    /// Records actually received from the API, before any client-side
+    /// filtering, when known.
+    pub fetched_count: Option<usize>,
 }
 
 impl<T> InfraResult<T> {
@@ -21,6 +32,8 @@ impl<T> InfraResult<T> {
         Self {
             records: Vec::new(),
             errors: Vec::new(),
+            expected_count: None,
+            fetched_count: None,
         }
     }
 
@@ -31,6 +44,19 @@ impl<T> InfraResult<T> {
     pub fn is_complete(&self) -> bool {
         self.errors.is_empty()
     }
+
+    /// Whether the number of records fetched matches the probe's count.
+    ///
+    /// A mismatch means the result is incomplete or stale even if no page
+    /// failed: the OpenDataSoft 10,000 offset cap cut the fetch short, or
+    /// the dataset changed between the probe and the last page. Returns
+    /// `true` when either count is unknown.
+    pub fn is_count_consistent(&self) -> bool {
+        match (self.expected_count, self.fetched_count) {
+            (Some(expected), Some(fetched)) => expected == fetched,
+            _ => true,
+        }
+    }
 }
 
 impl<T> Default for InfraResult<T> {
@@ -42,14 +68,95 @@ impl<T> Default for InfraResult<T> {
 pub struct HttpClient {
     client: reqwest::Client,
     api_key: Option<String>,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    timeout: Duration,
+    user_agent: String,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    retry: Option<RetryConfig>,
 }
 
 impl HttpClient {
+    /// Connect and overall request timeout used unless
+    /// [`HttpClient::with_timeout`] sets another.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// `User-Agent` sent unless [`HttpClient::with_user_agent`] sets another.
+    pub const DEFAULT_USER_AGENT: &'static str =
+        concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
     pub fn new() -> Self {
         Self {
             client: reqwest::Client::new(),
             api_key: None,
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            timeout: Self::DEFAULT_TIMEOUT,
+            user_agent: Self::DEFAULT_USER_AGENT.to_string(),
+            rate_limiter: None,
+            retry: None,
         }
+        .rebuild()
+    }
+
+    /// Sets both the connect timeout and the overall timeout of each request.
+    ///
+    /// Without one a hung endpoint could stall a fetch indefinitely; a timed
+    /// out request fails with `InfraHexError::Http` like any other reqwest
+    /// error. The overall timeout covers reading the whole body, so raise it
+    /// for large streamed exports. Defaults to
+    /// [`HttpClient::DEFAULT_TIMEOUT`]. A per-request timeout passed to
+    /// [`HttpClient::fetch_json_with_timeout`] takes precedence.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self.rebuild()
+    }
+
+    /// Sets the `User-Agent` header sent with every request.
+    ///
+    /// ONS and OpenDataSoft operators ask clients to identify themselves;
+    /// include a contact address, e.g. `"my-app/1.0 (ops@example.com)"`.
+    /// Defaults to [`HttpClient::DEFAULT_USER_AGENT`].
+    ///
+    /// Returns `InfraHexError::Config` if the value isn't a valid header,
+    /// such as one containing a newline.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Result<Self, InfraHexError> {
+        let user_agent = user_agent.into();
+        if reqwest::header::HeaderValue::from_str(&user_agent).is_err() {
+            return Err(InfraHexError::Config(format!(
+                "Invalid User-Agent header value: {:?}",
+                user_agent
+            )));
+        }
+        self.user_agent = user_agent;
+        Ok(self.rebuild())
+    }
+
+    /// Retries transient failures (429, 500, 502, 503, 504 and connection
+    /// errors) with exponential backoff, as described by [`RetryConfig`].
+    ///
+    /// Other failures are returned straight away. Retries are off by default,
+    /// so a single failed page lands in `InfraResult::errors`.
+    ///
+    /// These retries stack with [`PaginationConfig::page_retries`]: each
+    /// pagination retry re-sends a request that has already used up its
+    /// `max_retries`, so a page may be sent up to
+    /// `(max_retries + 1) * (page_retries + 1)` times. Usually only one of
+    /// the two is needed.
+    ///
+    /// [`PaginationConfig::page_retries`]: crate::client::PaginationConfig::page_retries
+    pub fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Waits for a token from `limiter` before every request.
+    ///
+    /// Pass clones of the same `Arc` to several clients to hold their
+    /// combined request rate under one shared quota.
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
     }
 
     pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
@@ -57,25 +164,141 @@ impl HttpClient {
         self
     }
 
+    /// Caps the number of idle connections kept open per host.
+    ///
+    /// reqwest keeps every idle connection by default. Paginated OpenDataSoft
+    /// fetches issue up to `PaginationConfig::batch_size` (100 by default)
+    /// requests at once, so a cap around the batch size keeps connections
+    /// reused between batches without holding more open than the server
+    /// will serve concurrently.
+    pub fn with_pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self.rebuild()
+    }
+
+    /// Closes idle pooled connections after `timeout`.
+    ///
+    /// reqwest's default is 90 seconds, which comfortably spans the 100 ms
+    /// default delay between OpenDataSoft page batches. Lower it if a proxy
+    /// or load balancer drops idle connections sooner.
+    pub fn with_pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self.rebuild()
+    }
+
+    /// Rebuilds the underlying reqwest client from the current settings,
+    /// keeping the last good client if the new one can't be built.
+    fn rebuild(mut self) -> Self {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.timeout)
+            .timeout(self.timeout)
+            .user_agent(self.user_agent.as_str());
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+
+        // Only fails if the TLS backend can't be initialised, in which case
+        // the existing client is no worse than a fresh one
+        if let Ok(client) = builder.build() {
+            self.client = client;
+        }
+        self
+    }
+
     pub async fn fetch_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, InfraHexError> {
+        self.fetch_json_with_timeout(url, None).await
+    }
+
+    /// Like [`HttpClient::fetch_json`], but fails with `InfraHexError::Http`
+    /// if the request hasn't completed within `timeout`.
+    pub async fn fetch_json_with_timeout<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        timeout: Option<Duration>,
+    ) -> Result<T, InfraHexError> {
+        let response = self.get(url, timeout).await?;
+        let data: T = response.json().await?;
+        Ok(data)
+    }
+
+    /// Sends a GET request and checks its status, returning the response
+    /// with its body still unread so callers can consume it incrementally.
+    ///
+    /// Fails with `InfraHexError::Auth` on 401/403 and `InfraHexError::Api`
+    /// on any other unsuccessful status, once any configured retries are
+    /// used up.
+    pub async fn get(
+        &self,
+        url: &str,
+        timeout: Option<Duration>,
+    ) -> Result<reqwest::Response, InfraHexError> {
+        let mut attempt = 0;
+        loop {
+            let outcome = self.send_get(url, timeout).await;
+            let (retryable, wait) = match &outcome {
+                Ok(response) => (
+                    is_retryable_status(response.status()),
+                    retry_after(response),
+                ),
+                Err(e) => (is_retryable_error(e), None),
+            };
+
+            match &self.retry {
+                Some(retry) if retryable && attempt < retry.max_retries => {
+                    tokio::time::sleep(retry.delay(attempt, wait)).await;
+                    attempt += 1;
+                }
+                _ => return check_status(outcome?),
+            }
+        }
+    }
+
+    /// Sends a single GET request, without checking its status.
+    async fn send_get(
+        &self,
+        url: &str,
+        timeout: Option<Duration>,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire().await;
+        }
+
         let mut request = self.client.get(url);
 
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
         if let Some(key) = &self.api_key {
             request = request.header("Authorization", format!("Apikey {}", key));
         }
 
-        let response = request.send().await?;
+        request.send().await
+    }
+}
 
-        if !response.status().is_success() {
-            return Err(InfraHexError::Api(format!(
-                "API returned status {}",
-                response.status()
-            )));
-        }
+/// Converts an unsuccessful status into `InfraHexError::Auth` (401/403) or
+/// `InfraHexError::Api`.
+fn check_status(response: reqwest::Response) -> Result<reqwest::Response, InfraHexError> {
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err(InfraHexError::Auth(format!(
+            "API returned status {}; check the API key",
+            status
+        )));
+    }
 
-        let data: T = response.json().await?;
-        Ok(data)
+    if !status.is_success() {
+        return Err(InfraHexError::Api(format!(
+            "API returned status {}",
+            response.status()
+        )));
     }
+
+    Ok(response)
 }
 
 impl Default for HttpClient {
@@ -115,9 +338,163 @@ impl BBox {
     }
 }
 
+/// Lightweight aggregate statistics for a region, computed without
+/// downloading every record.
+#[derive(Debug, Clone, Copy)]
+pub struct RegionStats {
+    /// Total number of records matching the region query.
+    pub total_count: u64,
+    /// Geometry extent derived from a small sample of the matching records.
+    pub extent: BBox,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::client::mock_server::{self, MockRequest, MockResponse};
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_http_client_pool_settings() {
+        let client = HttpClient::new()
+            .with_api_key("key")
+            .with_pool_max_idle_per_host(16)
+            .with_pool_idle_timeout(Duration::from_secs(30));
+
+        assert_eq!(client.pool_max_idle_per_host, Some(16));
+        assert_eq!(client.pool_idle_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(client.api_key.as_deref(), Some("key"));
+        assert_eq!(client.timeout, HttpClient::DEFAULT_TIMEOUT);
+        assert_eq!(client.user_agent, HttpClient::DEFAULT_USER_AGENT);
+    }
+
+    /// Serves `{}` to every request, returning the server's URL.
+    fn spawn_echo_server() -> (String, Arc<Mutex<Vec<MockRequest>>>) {
+        let (base_url, requests) = mock_server::spawn(|_| "{}".to_string());
+        (format!("{}/", base_url), requests)
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_header() {
+        let (url, requests) = spawn_echo_server();
+        let client = HttpClient::new()
+            .with_user_agent("infra-test/1.0 (ops@example.com)")
+            .unwrap();
+        let _: serde_json::Value = client.fetch_json(&url).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            requests[0].header("user-agent"),
+            Some("infra-test/1.0 (ops@example.com)")
+        );
+    }
+
+    #[test]
+    fn test_invalid_user_agent_is_rejected() {
+        let result = HttpClient::new().with_user_agent("bad\nagent");
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_default_user_agent_header() {
+        let (url, requests) = spawn_echo_server();
+        let _: serde_json::Value = HttpClient::new().fetch_json(&url).await.unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(
+            requests[0].header("user-agent"),
+            Some(HttpClient::DEFAULT_USER_AGENT)
+        );
+    }
+
+    /// Serves each response in turn, repeating the last once they run out.
+    fn spawn_status_server(responses: Vec<MockResponse>) -> (String, Arc<Mutex<Vec<MockRequest>>>) {
+        let next = AtomicUsize::new(0);
+        let (base_url, requests) = mock_server::spawn_with(move |_| {
+            let i = next.fetch_add(1, Ordering::SeqCst);
+            responses[i.min(responses.len() - 1)].clone()
+        });
+        (format!("{}/", base_url), requests)
+    }
+
+    fn fast_retry() -> RetryConfig {
+        RetryConfig::new()
+            .with_base_delay(Duration::from_millis(1))
+            .with_max_delay(Duration::from_millis(5))
+    }
+
+    #[tokio::test]
+    async fn test_retry_recovers_from_503() {
+        let (url, requests) = spawn_status_server(vec![
+            MockResponse::status(503),
+            MockResponse::status(503),
+            MockResponse::status(200),
+        ]);
+        let client = HttpClient::new().with_retry(fast_retry());
+
+        let result: Result<serde_json::Value, _> = client.fetch_json(&url).await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_max_retries() {
+        let (url, requests) = spawn_status_server(vec![MockResponse::status(503)]);
+        let client = HttpClient::new().with_retry(fast_retry().with_max_retries(2));
+
+        let result: Result<serde_json::Value, _> = client.fetch_json(&url).await;
+        assert!(matches!(result, Err(InfraHexError::Api(_))));
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_honours_retry_after() {
+        let (url, requests) = spawn_status_server(vec![
+            MockResponse::status(429).with_header("Retry-After", "1"),
+            MockResponse::status(200),
+        ]);
+        let client =
+            HttpClient::new().with_retry(fast_retry().with_max_delay(Duration::from_millis(100)));
+
+        let start = std::time::Instant::now();
+        let result: Result<serde_json::Value, _> = client.fetch_json(&url).await;
+        assert!(result.is_ok(), "{:?}", result);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        // The header's wait applies over the 1 ms backoff, capped at 100 ms
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(100), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(1), "{:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn test_no_retry_on_404() {
+        let (url, requests) =
+            spawn_status_server(vec![MockResponse::status(404), MockResponse::status(200)]);
+        let client = HttpClient::new().with_retry(fast_retry());
+
+        let result: Result<serde_json::Value, _> = client.fetch_json(&url).await;
+        assert!(matches!(result, Err(InfraHexError::Api(_))));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_http_client_timeout() {
+        // Accepts connections but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+
+        let client = HttpClient::new().with_timeout(Duration::from_millis(50));
+        let result: Result<serde_json::Value, _> =
+            client.fetch_json(&format!("http://{}/", addr)).await;
+        assert!(matches!(result, Err(InfraHexError::Http(e)) if e.is_timeout()));
+    }
 
     #[test]
     fn test_geopoint_to_point() {
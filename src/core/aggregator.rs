@@ -0,0 +1,143 @@
+use arrow_array::RecordBatch;
+use n3gb_rs::HexCell;
+use std::collections::HashMap;
+
+use crate::client::PipelineData;
+use crate::error::InfraHexError;
+
+use super::arrow::{
+    HexSummaryOptions, apply_hex_id_filter, build_hex_summary, extract_cells_per_pipeline,
+    fold_hex_counts, fold_hex_lengths, sort_hex_counts,
+};
+
+/// Builds a hex summary incrementally from batches of records.
+///
+/// Each call to [`HexAggregator::add`] hexes its records and folds them into
+/// running per-hex counts, after which the records can be dropped. This lets
+/// pages be aggregated as they're fetched, so memory is bounded by the number
+/// of occupied hexes rather than the number of records. [`HexAggregator::finish`]
+/// produces the same batch `to_hex_summary_with_options` would for the
+/// concatenated input.
+///
+/// # Example
+///
+/// ```no_run
+/// # use infra_hex_rs::{CadentPipelineRecord, HexAggregator};
+/// # fn example(pages: Vec<Vec<CadentPipelineRecord>>) -> Result<(), infra_hex_rs::InfraHexError> {
+/// let mut aggregator = HexAggregator::new(10);
+/// for page in pages {
+///     aggregator.add(&page)?;
+/// }
+/// let summary = aggregator.finish()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct HexAggregator {
+    zoom: u8,
+    options: HexSummaryOptions,
+    counts: HashMap<String, usize>,
+    cells_map: HashMap<String, HexCell>,
+    lengths: HashMap<String, f64>,
+    records_seen: usize,
+}
+
+impl HexAggregator {
+    /// Creates an empty aggregator producing a default (BNG, with geometry)
+    /// summary at `zoom`.
+    pub fn new(zoom: u8) -> Self {
+        Self {
+            zoom,
+            options: HexSummaryOptions::default(),
+            counts: HashMap::new(),
+            cells_map: HashMap::new(),
+            lengths: HashMap::new(),
+            records_seen: 0,
+        }
+    }
+
+    /// Sets the output options used by [`HexAggregator::finish`].
+    pub fn with_options(mut self, options: HexSummaryOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Hexes `records` and folds their cells into the running counts.
+    pub fn add<T: PipelineData>(&mut self, records: &[T]) -> Result<(), InfraHexError> {
+        let mut cells_per_pipe =
+            extract_cells_per_pipeline(records, self.zoom, &None, &self.options.hex_cells)?;
+        apply_hex_id_filter(&mut cells_per_pipe, self.options.hex_id_filter.as_ref());
+        fold_hex_lengths(records, &cells_per_pipe, &self.options, &mut self.lengths);
+        fold_hex_counts(cells_per_pipe, &mut self.counts, &mut self.cells_map);
+        self.records_seen += records.len();
+        Ok(())
+    }
+
+    /// Number of records added so far.
+    pub fn records_seen(&self) -> usize {
+        self.records_seen
+    }
+
+    /// Number of distinct hexes occupied so far.
+    pub fn hex_count(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Builds the summary batch from everything added.
+    pub fn finish(self) -> Result<RecordBatch, InfraHexError> {
+        let sorted = sort_hex_counts(self.counts);
+        build_hex_summary(&sorted, &self.cells_map, &self.lengths, None, &self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::to_hex_summary_no_geom;
+    use arrow_array::{StringArray, UInt32Array};
+
+    fn counts(batch: &RecordBatch) -> HashMap<String, u32> {
+        let ids = batch
+            .column_by_name("hex_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let counts = batch
+            .column_by_name("pipe_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        (0..batch.num_rows())
+            .map(|i| (ids.value(i).to_string(), counts.value(i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_incremental_matches_one_shot() {
+        let records: Vec<_> = (0..6)
+            .map(|i| {
+                let lat = 53.48 + i as f64 * 0.0005;
+                line_record(vec![vec![-2.250, lat], vec![-2.248, lat + 0.001]])
+            })
+            .collect();
+
+        let mut aggregator =
+            HexAggregator::new(12).with_options(HexSummaryOptions::new().with_geometry(false));
+        aggregator.add(&records[..2]).unwrap();
+        aggregator.add(&records[2..]).unwrap();
+        assert_eq!(aggregator.records_seen(), 6);
+
+        let incremental = aggregator.finish().unwrap();
+        let one_shot = to_hex_summary_no_geom(&records, 12).unwrap();
+
+        assert_eq!(counts(&incremental), counts(&one_shot));
+    }
+
+    #[test]
+    fn test_empty_aggregator() {
+        let summary = HexAggregator::new(10).finish().unwrap();
+        assert_eq!(summary.num_rows(), 0);
+    }
+}
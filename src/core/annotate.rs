@@ -0,0 +1,180 @@
+use arrow_array::{RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use geo::{BoundingRect, Contains, Intersects, Rect};
+use n3gb_rs::HexCell;
+use std::sync::Arc;
+
+use crate::client::BuiltUpArea;
+use crate::error::InfraHexError;
+
+use super::arrow::ColumnNames;
+use super::reproject::bng_to_wgs84;
+
+/// Tags each hex in a summary with the Built-Up Area containing it.
+///
+/// Adds nullable `bua_name` and `bua_code` columns to `summary`, which must
+/// have a `hex_id` column. A hex is assigned to the area that contains its
+/// centroid, so a hex straddling a boundary belongs wholly to whichever side
+/// its centre falls on; hexes whose centroid is in no area get nulls. If
+/// areas overlap, the first in `areas` wins.
+///
+/// Each area's bounding rectangle is checked before the full
+/// point-in-polygon test, so most hex/area pairs are rejected cheaply.
+pub fn annotate_hexes_with_areas(
+    summary: &RecordBatch,
+    areas: &[BuiltUpArea],
+) -> Result<RecordBatch, InfraHexError> {
+    annotate_hexes_with_areas_with_columns(summary, areas, &ColumnNames::default())
+}
+
+/// Like [`annotate_hexes_with_areas`], for a summary whose hex id column is
+/// named by `names` rather than `hex_id`.
+pub fn annotate_hexes_with_areas_with_columns(
+    summary: &RecordBatch,
+    areas: &[BuiltUpArea],
+    names: &ColumnNames,
+) -> Result<RecordBatch, InfraHexError> {
+    let hex_ids = names.hex_ids(summary)?;
+
+    let bounds: Vec<Option<Rect<f64>>> = areas.iter().map(|a| a.geometry.bounding_rect()).collect();
+
+    let mut names = Vec::with_capacity(summary.num_rows());
+    let mut codes = Vec::with_capacity(summary.num_rows());
+
+    for i in 0..summary.num_rows() {
+        let cell = HexCell::from_hex_id(hex_ids.value(i))?;
+        let centroid = bng_to_wgs84(cell.center.0);
+
+        let area = areas.iter().zip(&bounds).find(|(area, bounds)| {
+            bounds.is_some_and(|b| b.intersects(&centroid)) && area.geometry.contains(&centroid)
+        });
+
+        names.push(area.map(|(a, _)| a.name.as_str()));
+        codes.push(area.map(|(a, _)| a.code.as_str()));
+    }
+
+    let mut fields: Vec<Field> = summary
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| f.as_ref().clone())
+        .collect();
+    fields.push(Field::new("bua_name", DataType::Utf8, true));
+    fields.push(Field::new("bua_code", DataType::Utf8, true));
+
+    let mut columns = summary.columns().to_vec();
+    columns.push(Arc::new(StringArray::from(names)));
+    columns.push(Arc::new(StringArray::from(codes)));
+
+    let schema = Schema::new_with_metadata(fields, summary.schema().metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::wgs84_to_bng;
+    use arrow_array::{Array, UInt32Array};
+    use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+
+    fn square(min_x: f64, min_y: f64, size: f64) -> Polygon<f64> {
+        Polygon::new(
+            LineString::from(vec![
+                (min_x, min_y),
+                (min_x + size, min_y),
+                (min_x + size, min_y + size),
+                (min_x, min_y + size),
+                (min_x, min_y),
+            ]),
+            vec![],
+        )
+    }
+
+    fn area(name: &str, code: &str, polygon: Polygon<f64>) -> BuiltUpArea {
+        BuiltUpArea {
+            object_id: 1,
+            code: code.to_string(),
+            name: name.to_string(),
+            name_welsh: None,
+            area_hectares: None,
+            geometry: MultiPolygon::new(vec![polygon]),
+        }
+    }
+
+    #[test]
+    fn test_annotate_hexes_by_centroid() {
+        let point = |lon: f64, lat: f64| {
+            let bng = wgs84_to_bng(Coord { x: lon, y: lat });
+            HexCell::from_bng(&bng.into(), 10).unwrap()
+        };
+        let inside = point(-2.24, 53.48);
+        let outside = point(-1.50, 52.00);
+
+        let summary = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![
+                Field::new("hex_id", DataType::Utf8, false),
+                Field::new("pipe_count", DataType::UInt32, false),
+            ])),
+            vec![
+                Arc::new(StringArray::from(vec![
+                    inside.id.clone(),
+                    outside.id.clone(),
+                ])),
+                Arc::new(UInt32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+
+        let areas = vec![area("Manchester", "E63000001", square(-2.4, 53.3, 0.4))];
+        let annotated = annotate_hexes_with_areas(&summary, &areas).unwrap();
+
+        let names = annotated
+            .column_by_name("bua_name")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let codes = annotated
+            .column_by_name("bua_code")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        assert_eq!(names.value(0), "Manchester");
+        assert_eq!(codes.value(0), "E63000001");
+        assert!(names.is_null(1));
+        assert!(codes.is_null(1));
+    }
+
+    #[test]
+    fn test_annotate_requires_hex_id() {
+        let summary = RecordBatch::new_empty(Arc::new(Schema::empty()));
+        assert!(annotate_hexes_with_areas(&summary, &[]).is_err());
+    }
+
+    #[test]
+    fn test_annotate_with_renamed_hex_column() {
+        let cell =
+            HexCell::from_bng(&wgs84_to_bng(Coord { x: -2.24, y: 53.48 }).into(), 10).unwrap();
+        let summary = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("cell", DataType::Utf8, false)])),
+            vec![Arc::new(StringArray::from(vec![cell.id]))],
+        )
+        .unwrap();
+        let areas = vec![area("Manchester", "E63000001", square(-2.4, 53.3, 0.4))];
+
+        assert!(annotate_hexes_with_areas(&summary, &areas).is_err());
+
+        let names = ColumnNames::default().with_hex_id("cell");
+        let annotated = annotate_hexes_with_areas_with_columns(&summary, &areas, &names).unwrap();
+        let codes = annotated
+            .column_by_name("bua_code")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(codes.value(0), "E63000001");
+    }
+}
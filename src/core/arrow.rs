@@ -1,7 +1,8 @@
 use arrow_array::builder::ListBuilder;
 use arrow_array::builder::StringBuilder;
-use arrow_array::{RecordBatch, StringArray, UInt32Array};
+use arrow_array::{BooleanArray, Float64Array, RecordBatch, StringArray, UInt32Array};
 use arrow_schema::{DataType, Field, Schema};
+use geo::Area;
 use geo_types::{MultiPolygon, Polygon};
 use geoarrow_array::IntoArrow;
 use geoarrow_array::array::{MultiPolygonArray, PolygonArray};
@@ -15,7 +16,341 @@ use std::sync::Arc;
 use crate::client::PipelineData;
 use crate::error::InfraHexError;
 
-use super::hex::get_hex_cells;
+use super::geometry::{RingOrientation, normalise_winding};
+use super::hex::{HexCellOptions, get_hex_cells_with_options};
+use super::length::{length_per_cell, pipe_length_m};
+use super::reproject::polygon_bng_to_wgs84;
+
+// =============================================================================
+// Output Options
+// =============================================================================
+
+/// Records hexed per progress update by [`to_hex_summary_with_progress`]
+/// when no chunk size is configured.
+pub const PROGRESS_CHUNK_SIZE: usize = 10_000;
+
+/// Progress of a hex summary computation, reported after each chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeProgress {
+    pub records_processed: usize,
+    pub total_records: usize,
+}
+
+impl ComputeProgress {
+    /// Fraction of records processed, from 0.0 to 1.0.
+    pub fn fraction(&self) -> f64 {
+        if self.total_records == 0 {
+            1.0
+        } else {
+            self.records_processed as f64 / self.total_records as f64
+        }
+    }
+}
+
+/// How [`HexSummaryOptions::with_count_normalisation`] scales pipe counts
+/// into `count_normalised`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountNormalisation {
+    /// `pipe_count / max_count`.
+    Linear,
+    /// `ln(1 + pipe_count) / ln(1 + max_count)`, which spreads out heavily
+    /// skewed distributions where a few hexes dwarf the rest.
+    Log,
+}
+
+impl CountNormalisation {
+    /// Scales `count` against the batch's `max` count.
+    fn normalise(self, count: usize, max: usize) -> f64 {
+        if max == 0 {
+            return 0.0;
+        }
+        match self {
+            Self::Linear => count as f64 / max as f64,
+            Self::Log => (count as f64).ln_1p() / (max as f64).ln_1p(),
+        }
+    }
+}
+
+/// Restricts hex summaries to, or excludes, a fixed set of hex ids.
+///
+/// Applied during aggregation, after any boundary filter, so excluded cells
+/// are never counted or given geometry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HexIdFilter {
+    /// Only these hexes are counted and emitted, e.g. for a focused study.
+    Allow(HashSet<String>),
+    /// Every hex except these is counted, e.g. to drop known-noisy cells.
+    Deny(HashSet<String>),
+}
+
+impl HexIdFilter {
+    /// Whether the hex with `id` passes the filter.
+    pub fn keeps(&self, id: &str) -> bool {
+        match self {
+            Self::Allow(ids) => ids.contains(id),
+            Self::Deny(ids) => !ids.contains(id),
+        }
+    }
+}
+
+/// Output column names for hex summaries.
+///
+/// Lets summaries slot into an existing data model (e.g. `h3_index` instead
+/// of `hex_id`) without a downstream rename. Helpers which read a summary
+/// back by column name, such as the sinks or `annotate_hexes_with_areas`,
+/// assume the defaults; pass the same names to their `with_column_names`
+/// builder or `_with_columns` variant when reading renamed summaries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnNames {
+    pub hex_id: String,
+    pub pipe_count: String,
+    pub geometry: String,
+}
+
+impl Default for ColumnNames {
+    fn default() -> Self {
+        Self {
+            hex_id: "hex_id".to_string(),
+            pipe_count: "pipe_count".to_string(),
+            geometry: "geometry".to_string(),
+        }
+    }
+}
+
+impl ColumnNames {
+    /// Sets the name of the hex id column.
+    pub fn with_hex_id(mut self, name: impl Into<String>) -> Self {
+        self.hex_id = name.into();
+        self
+    }
+
+    /// Sets the name of the pipe count column.
+    pub fn with_pipe_count(mut self, name: impl Into<String>) -> Self {
+        self.pipe_count = name.into();
+        self
+    }
+
+    /// Sets the name of the geometry column.
+    pub fn with_geometry(mut self, name: impl Into<String>) -> Self {
+        self.geometry = name.into();
+        self
+    }
+
+    /// Returns the summary's hex id column, or `InfraHexError::Config` if
+    /// `batch` has no Utf8 column of that name.
+    pub(crate) fn hex_ids<'a>(
+        &self,
+        batch: &'a RecordBatch,
+    ) -> Result<&'a StringArray, InfraHexError> {
+        batch
+            .column_by_name(&self.hex_id)
+            .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+            .ok_or_else(|| InfraHexError::Config(format!("Summary has no {} column", self.hex_id)))
+    }
+}
+
+/// Options controlling the shape of the batches produced by the `to_*`
+/// functions.
+///
+/// The same options drive both the per-pipeline record batches and the
+/// per-hex summaries so the two geometry-producing paths stay consistent.
+#[derive(Debug, Clone)]
+pub struct HexSummaryOptions {
+    pub include_geometry: bool,
+    pub reproject_to_wgs84: bool,
+    pub chunk_size: Option<usize>,
+    pub include_cell_area: bool,
+    pub include_on_boundary: bool,
+    pub include_record_index: bool,
+    pub include_carrier: bool,
+    pub include_h3_index: bool,
+    pub ring_orientation: RingOrientation,
+    pub count_normalisation: Option<CountNormalisation>,
+    pub include_share_of_region: bool,
+    pub include_total_length: bool,
+    pub hex_id_filter: Option<HexIdFilter>,
+    pub column_names: ColumnNames,
+    pub hex_cells: HexCellOptions,
+}
+
+impl Default for HexSummaryOptions {
+    fn default() -> Self {
+        Self {
+            include_geometry: true,
+            reproject_to_wgs84: false,
+            chunk_size: None,
+            include_cell_area: false,
+            include_on_boundary: false,
+            include_record_index: false,
+            include_carrier: false,
+            include_h3_index: false,
+            ring_orientation: RingOrientation::default(),
+            count_normalisation: None,
+            include_share_of_region: false,
+            include_total_length: true,
+            hex_id_filter: None,
+            column_names: ColumnNames::default(),
+            hex_cells: HexCellOptions::default(),
+        }
+    }
+}
+
+impl HexSummaryOptions {
+    /// Creates options matching `to_hex_summary` / `to_record_batch`:
+    /// geometry included, in British National Grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Includes or omits the geometry column.
+    pub fn with_geometry(mut self, include: bool) -> Self {
+        self.include_geometry = include;
+        self
+    }
+
+    /// Outputs hex geometry in WGS84 (EPSG:4326) instead of BNG (EPSG:27700),
+    /// for web-map consumers.
+    pub fn with_reproject_to_wgs84(mut self, reproject: bool) -> Self {
+        self.reproject_to_wgs84 = reproject;
+        self
+    }
+
+    /// Aggregates summaries `chunk_size` records at a time.
+    ///
+    /// Each chunk's cells are folded into the running counts and dropped
+    /// before the next chunk is hexed, so peak memory is bounded by the
+    /// aggregated maps plus one chunk rather than every cell of every pipe.
+    /// Smaller chunks lower peak memory at the cost of less parallelism.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Adds a `cell_area_m2` column to hex summaries, computed from each
+    /// cell's BNG polygon.
+    ///
+    /// At a fixed zoom every hex is the same size, so the value is effectively
+    /// constant; it is included so density can be derived downstream without
+    /// carrying the polygons (e.g. alongside `with_geometry(false)`).
+    pub fn with_cell_area(mut self, include: bool) -> Self {
+        self.include_cell_area = include;
+        self
+    }
+
+    /// Adds an `on_boundary` column to boundary-filtered hex summaries,
+    /// marking cells crossed by the boundary's rings rather than lying fully
+    /// inside it.
+    ///
+    /// Pipes in edge cells may continue outside the region, so their counts
+    /// are less reliable. Without a boundary every cell is `false`.
+    pub fn with_on_boundary(mut self, include: bool) -> Self {
+        self.include_on_boundary = include;
+        self
+    }
+
+    /// Adds a `record_index` column to per-pipeline record batches holding
+    /// each row's position in the input slice.
+    ///
+    /// Rows already follow input order, but an explicit index survives
+    /// filtering and joins. It always refers to the slice passed in, so if
+    /// records are dropped beforehand (e.g. by a pre-aggregation filter) the
+    /// index points into the filtered slice, not the original fetch.
+    pub fn with_record_index(mut self, include: bool) -> Self {
+        self.include_record_index = include;
+        self
+    }
+
+    /// Adds `carrier_material`, `carrier_diameter_mm` and
+    /// `carrier_diameter_unit` columns to per-pipeline record batches,
+    /// describing the carrier pipe an asset runs inside.
+    ///
+    /// Values come from [`PipelineData`]'s carrier methods; the diameter is
+    /// normalised to millimetres, and assets without a carrier get nulls.
+    pub fn with_carrier_attributes(mut self, include: bool) -> Self {
+        self.include_carrier = include;
+        self
+    }
+
+    /// Adds an `h3_index` (`UInt64`) column to hex summaries holding, for each
+    /// hex, the H3 cell containing its centroid.
+    ///
+    /// This bridges the n3gb grid to H3-based tooling without changing the
+    /// grid itself. See [`h3_resolution_for_zoom`] for how the H3 resolution
+    /// is chosen. Requires the `h3` feature; without it, building a summary
+    /// with this enabled returns `InfraHexError::Config`.
+    ///
+    /// [`h3_resolution_for_zoom`]: crate::core::h3_resolution_for_zoom
+    pub fn with_h3_index(mut self, include: bool) -> Self {
+        self.include_h3_index = include;
+        self
+    }
+
+    /// Sets the winding order of output hex polygons. Defaults to
+    /// counter-clockwise exteriors, as GeoJSON and OGC consumers expect.
+    pub fn with_ring_orientation(mut self, orientation: RingOrientation) -> Self {
+        self.ring_orientation = orientation;
+        self
+    }
+
+    /// Adds a `count_normalised` Float64 column to hex summaries, scaling each
+    /// pipe count to 0–1 against the largest count in the batch.
+    ///
+    /// Saves web-map renderers a second pass to find the maximum before
+    /// mapping counts onto a colour ramp.
+    pub fn with_count_normalisation(mut self, normalisation: CountNormalisation) -> Self {
+        self.count_normalisation = Some(normalisation);
+        self
+    }
+
+    /// Adds a `share_of_region` Float64 column to hex summaries: each hex's
+    /// pipe count divided by the sum of all counts in the summary.
+    ///
+    /// Meant for boundary-filtered summaries (e.g. via
+    /// [`to_hex_summary_with_options`] with a `MultiPolygon`), where it puts
+    /// differently sized regions on a comparable scale. The shares sum to
+    /// 1.0. Because a pipe crossing several hexes counts once in each, the
+    /// denominator is pipe-hex incidences rather than distinct pipes, so a
+    /// share is not the fraction of the region's pipes touching that hex.
+    pub fn with_share_of_region(mut self, include: bool) -> Self {
+        self.include_share_of_region = include;
+        self
+    }
+
+    /// Includes or omits the `total_length_m` Float64 column of hex
+    /// summaries (included by default): the BNG length in metres of all
+    /// pipe segments lying inside each hex.
+    ///
+    /// Each segment is clipped to the hexagon's boundary, so a pipe running
+    /// through several hexes has its length split between them rather than
+    /// counted in full for each. Non-linear geometry contributes nothing.
+    pub fn with_total_length(mut self, include: bool) -> Self {
+        self.include_total_length = include;
+        self
+    }
+
+    /// Counts only the hexes an allowlist names, or all but those a denylist
+    /// names, in hex summaries.
+    ///
+    /// Composes with boundary filtering, and is cheaper than filtering the
+    /// finished batch since excluded cells never get geometry built.
+    pub fn with_hex_id_filter(mut self, filter: HexIdFilter) -> Self {
+        self.hex_id_filter = Some(filter);
+        self
+    }
+
+    /// Overrides the summary's `hex_id`/`pipe_count`/`geometry` column names.
+    pub fn with_column_names(mut self, names: ColumnNames) -> Self {
+        self.column_names = names;
+        self
+    }
+
+    /// Sets the options used to hex each pipeline, such as coordinate
+    /// snapping or a cap on cells per pipe.
+    pub fn with_hex_cell_options(mut self, hex_cells: HexCellOptions) -> Self {
+        self.hex_cells = hex_cells;
+        self
+    }
+}
 
 // =============================================================================
 // Boundary Filter Trait
@@ -38,6 +373,31 @@ pub trait BoundaryFilter {
     /// against the hex IDs used in their data model and discard any records
     /// whose hex IDs are not in this set - use Intersect for this.
     fn valid_cell_ids(&self, zoom: u8) -> Result<Option<HashSet<String>>, InfraHexError>;
+
+    /// Returns the IDs of hex cells crossed by the boundary's edge at `zoom`,
+    /// or `None` if there is no boundary.
+    ///
+    /// These cells are only partly inside the region. The default returns
+    /// `None`.
+    fn boundary_cell_ids(&self, _zoom: u8) -> Result<Option<HashSet<String>>, InfraHexError> {
+        Ok(None)
+    }
+}
+
+/// Hexes every ring of `polygons`, returning the IDs of the cells they cross.
+fn ring_cell_ids<'a>(
+    polygons: impl IntoIterator<Item = &'a Polygon<f64>>,
+    zoom: u8,
+) -> Result<HashSet<String>, InfraHexError> {
+    let mut ids = HashSet::new();
+    for polygon in polygons {
+        for ring in std::iter::once(polygon.exterior()).chain(polygon.interiors()) {
+            for cell in HexCell::from_line_string_wgs84(ring, zoom)? {
+                ids.insert(cell.id);
+            }
+        }
+    }
+    Ok(ids)
 }
 
 /// No boundary filtering - include all the hex cells.
@@ -64,6 +424,10 @@ impl BoundaryFilter for Polygon<f64> {
         let ids: HashSet<String> = grid.cells().iter().map(|c| c.id.clone()).collect();
         Ok(Some(ids))
     }
+
+    fn boundary_cell_ids(&self, zoom: u8) -> Result<Option<HashSet<String>>, InfraHexError> {
+        ring_cell_ids(std::iter::once(self), zoom).map(Some)
+    }
 }
 
 /// Filter hex cells intersecting a multipolygon boundary.
@@ -78,6 +442,10 @@ impl BoundaryFilter for MultiPolygon<f64> {
         let ids: HashSet<String> = grid.cells().iter().map(|c| c.id.clone()).collect();
         Ok(Some(ids))
     }
+
+    fn boundary_cell_ids(&self, zoom: u8) -> Result<Option<HashSet<String>>, InfraHexError> {
+        ring_cell_ids(&self.0, zoom).map(Some)
+    }
 }
 
 // =============================================================================
@@ -90,19 +458,49 @@ fn bng_metadata() -> Arc<Metadata> {
     Arc::new(Metadata::new(crs, None))
 }
 
+/// CRS object for reprojected WGS84 output.
+fn wgs84_metadata() -> Arc<Metadata> {
+    let crs = Crs::from_authority_code("EPSG:4326".to_string());
+    Arc::new(Metadata::new(crs, None))
+}
+
+/// CRS metadata matching the output CRS chosen in `options`.
+fn output_metadata(options: &HexSummaryOptions) -> Arc<Metadata> {
+    if options.reproject_to_wgs84 {
+        wgs84_metadata()
+    } else {
+        bng_metadata()
+    }
+}
+
+/// Returns a cell's polygon in the output CRS and ring orientation chosen in
+/// `options`.
+fn cell_polygon(cell: &HexCell, options: &HexSummaryOptions) -> Polygon<f64> {
+    let polygon = normalise_winding(&cell.to_polygon(), options.ring_orientation);
+    if options.reproject_to_wgs84 {
+        polygon_bng_to_wgs84(&polygon)
+    } else {
+        polygon
+    }
+}
+
 /// Extracts hex cells for each pipeline, optionally filtering by boundary.
 /// If `valid_ids` is:
 /// - `Some(set)`: only hex cells whose IDs are in `set` are kept for each pipeline.
 /// - `None`: no boundary filtering is applied and all computed cells are returned.
 /// - This is the boundary filter logic in practice.
-fn extract_cells_per_pipeline<T: PipelineData>(
+///
+/// The outer vec is in `records` order and each pipe's cells keep the order
+/// returned by `get_hex_cells_with_options`; filtering never reorders them.
+pub(super) fn extract_cells_per_pipeline<T: PipelineData>(
     records: &[T],
     zoom: u8,
     valid_ids: &Option<HashSet<String>>,
+    cell_options: &HexCellOptions,
 ) -> Result<Vec<Vec<HexCell>>, InfraHexError> {
     let cells_per_pipe: Result<Vec<Vec<HexCell>>, InfraHexError> = records
         .par_iter()
-        .map(|record| get_hex_cells(record, zoom))
+        .map(|record| get_hex_cells_with_options(record, zoom, cell_options))
         .collect();
 
     let cells_per_pipe = cells_per_pipe?;
@@ -121,6 +519,18 @@ fn extract_cells_per_pipeline<T: PipelineData>(
     }
 }
 
+/// Drops cells rejected by `filter` from each pipeline's cells.
+pub(super) fn apply_hex_id_filter(
+    cells_per_pipe: &mut [Vec<HexCell>],
+    filter: Option<&HexIdFilter>,
+) {
+    if let Some(filter) = filter {
+        for cells in cells_per_pipe {
+            cells.retain(|c| filter.keeps(&c.id));
+        }
+    }
+}
+
 /// Builds the pipeline attribute arrays (asset_id, pipe_type, material, pressure).
 fn build_pipeline_attributes<T: PipelineData>(
     records: &[T],
@@ -146,39 +556,142 @@ fn build_hex_ids_list(cells_per_pipe: &[Vec<HexCell>]) -> arrow_array::ListArray
 }
 
 /// Builds a MultiPolygon geometry array from cells per pipeline.
-fn build_multipolygon_geometry(cells_per_pipe: &[Vec<HexCell>]) -> (MultiPolygonArray, Field) {
-    let multi_polygons: Vec<MultiPolygon<f64>> = cells_per_pipe
-        .iter()
-        .map(|cells| {
-            let polygons: Vec<_> = cells.iter().map(|c| c.to_polygon()).collect();
-            MultiPolygon::new(polygons)
-        })
-        .collect();
+///
+/// Fails with `InfraHexError::Geometry` naming the offending hex if a cell
+/// polygon is degenerate or rejected by the builder.
+fn build_multipolygon_geometry(
+    cells_per_pipe: &[Vec<HexCell>],
+    options: &HexSummaryOptions,
+) -> Result<(MultiPolygonArray, Field), InfraHexError> {
+    let mp_type = MultiPolygonType::new(Dimension::XY, output_metadata(options));
+    let mut builder = MultiPolygonBuilder::new(mp_type);
+
+    for cells in cells_per_pipe {
+        let polygons = cells
+            .iter()
+            .map(|c| {
+                let polygon = cell_polygon(c, options);
+                check_polygon(&c.id, &polygon)?;
+                Ok(polygon)
+            })
+            .collect::<Result<Vec<_>, InfraHexError>>()?;
+
+        builder
+            .push_multi_polygon(Some(&MultiPolygon::new(polygons)))
+            .map_err(|e| {
+                let ids: Vec<&str> = cells.iter().map(|c| c.id.as_str()).collect();
+                InfraHexError::Geometry(format!(
+                    "Failed to build multipolygon for hexes [{}]: {}",
+                    ids.join(", "),
+                    e
+                ))
+            })?;
+    }
 
-    let mp_type = MultiPolygonType::new(Dimension::XY, bng_metadata());
-    let geometry_array =
-        MultiPolygonBuilder::from_multi_polygons(&multi_polygons, mp_type).finish();
-    let geometry_field = geometry_array.extension_type().to_field("geometry", false);
-    (geometry_array, geometry_field)
+    let geometry_array = builder.finish();
+    let geometry_field = geometry_array
+        .extension_type()
+        .to_field(&options.column_names.geometry, false);
+    Ok((geometry_array, geometry_field))
 }
 
 /// Builds a Polygon geometry array from a list of hex cells.
-fn build_polygon_geometry(cells: &[&HexCell]) -> (PolygonArray, Field) {
-    let polygons: Vec<_> = cells.iter().map(|c| c.to_polygon()).collect();
-    let poly_type = PolygonType::new(Dimension::XY, bng_metadata());
-    let geometry_array = PolygonBuilder::from_polygons(&polygons, poly_type).finish();
-    let geometry_field = geometry_array.extension_type().to_field("geometry", false);
-    (geometry_array, geometry_field)
+///
+/// Fails with `InfraHexError::Geometry` naming the offending hex if a cell
+/// polygon is degenerate or rejected by the builder.
+pub(super) fn build_polygon_geometry(
+    cells: &[&HexCell],
+    options: &HexSummaryOptions,
+) -> Result<(PolygonArray, Field), InfraHexError> {
+    let poly_type = PolygonType::new(Dimension::XY, output_metadata(options));
+    let mut builder = PolygonBuilder::new(poly_type);
+
+    for cell in cells {
+        let polygon = cell_polygon(cell, options);
+        check_polygon(&cell.id, &polygon)?;
+        builder.push_polygon(Some(&polygon)).map_err(|e| {
+            InfraHexError::Geometry(format!(
+                "Failed to build polygon for hex {}: {}",
+                cell.id, e
+            ))
+        })?;
+    }
+
+    let geometry_array = builder.finish();
+    let geometry_field = geometry_array
+        .extension_type()
+        .to_field(&options.column_names.geometry, false);
+    Ok((geometry_array, geometry_field))
 }
 
-/// Aggregates hex cells across pipelines, counting unique cells per pipeline.
-/// Returns sorted (by count descending) vec of (hex_id, count) and a map of id -> HexCell.
-fn aggregate_hex_counts(
-    cells_per_pipe: Vec<Vec<HexCell>>,
-) -> (Vec<(String, usize)>, HashMap<String, HexCell>) {
-    let mut counts: HashMap<String, usize> = HashMap::new();
-    let mut cells_map: HashMap<String, HexCell> = HashMap::new();
+/// Rejects polygons the geometry builders can't represent meaningfully: an
+/// exterior ring with fewer than four positions (three distinct vertices
+/// plus the closing one), or any non-finite coordinate.
+fn check_polygon(hex_id: &str, polygon: &Polygon<f64>) -> Result<(), InfraHexError> {
+    if polygon.exterior().0.len() < 4 {
+        return Err(InfraHexError::Geometry(format!(
+            "Degenerate polygon for hex {}: exterior ring has {} positions",
+            hex_id,
+            polygon.exterior().0.len()
+        )));
+    }
+
+    let rings = std::iter::once(polygon.exterior()).chain(polygon.interiors());
+    if rings
+        .flat_map(|r| r.coords())
+        .any(|c| !c.x.is_finite() || !c.y.is_finite())
+    {
+        return Err(InfraHexError::Geometry(format!(
+            "Degenerate polygon for hex {}: non-finite coordinate",
+            hex_id
+        )));
+    }
+
+    Ok(())
+}
 
+/// Hex ids sorted by pipe count (descending), plus the cell and total pipe
+/// length for each id.
+type HexCounts = (
+    Vec<(String, usize)>,
+    HashMap<String, HexCell>,
+    HashMap<String, f64>,
+);
+
+/// Adds each pipeline's clipped length per cell to running per-hex totals.
+/// `cells_per_pipe` must be aligned with `records`. Does nothing unless the
+/// summary includes `total_length_m`.
+pub(super) fn fold_hex_lengths<T: PipelineData>(
+    records: &[T],
+    cells_per_pipe: &[Vec<HexCell>],
+    options: &HexSummaryOptions,
+    lengths: &mut HashMap<String, f64>,
+) {
+    if !options.include_total_length {
+        return;
+    }
+
+    let per_pipe: Vec<HashMap<String, f64>> = records
+        .par_iter()
+        .zip(cells_per_pipe.par_iter())
+        .map(|(record, cells)| length_per_cell(record, cells, options.hex_cells.crs))
+        .collect();
+
+    for pipe in per_pipe {
+        for (id, length) in pipe {
+            *lengths.entry(id).or_insert(0.0) += length;
+        }
+    }
+}
+
+/// Folds one batch of per-pipeline cells into running count and cell maps.
+/// Each pipeline contributes at most 1 to a cell's count, however many of
+/// its segments fall in that cell.
+pub(super) fn fold_hex_counts(
+    cells_per_pipe: Vec<Vec<HexCell>>,
+    counts: &mut HashMap<String, usize>,
+    cells_map: &mut HashMap<String, HexCell>,
+) {
     for cells in cells_per_pipe {
         let mut seen_in_pipe = HashSet::new();
         for cell in cells {
@@ -188,11 +701,66 @@ fn aggregate_hex_counts(
             }
         }
     }
+}
 
-    let mut sorted: Vec<_> = counts.into_iter().collect();
-    sorted.sort_by(|a, b| b.1.cmp(&a.1));
+/// Aggregates hex cells across pipelines, counting unique cells per pipeline.
+/// Returns sorted (by count descending) vec of (hex_id, count) and a map of id -> HexCell.
+///
+/// Records are hexed `chunk_size` at a time (all at once when `None`), and
+/// each chunk's cell vectors are dropped once folded into the maps.
+/// `on_progress` is called after each chunk.
+fn aggregate_hex_counts<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    valid_ids: &Option<HashSet<String>>,
+    options: &HexSummaryOptions,
+    on_progress: &mut dyn FnMut(ComputeProgress),
+) -> Result<HexCounts, InfraHexError> {
+    let chunk_size = match options.chunk_size {
+        Some(0) => {
+            return Err(InfraHexError::Config(
+                "Chunk size must be greater than zero".to_string(),
+            ));
+        }
+        Some(n) => n,
+        None => records.len().max(1),
+    };
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut cells_map: HashMap<String, HexCell> = HashMap::new();
+    let mut lengths: HashMap<String, f64> = HashMap::new();
+
+    let mut records_processed = 0;
+    for chunk in records.chunks(chunk_size) {
+        let mut cells_per_pipe =
+            extract_cells_per_pipeline(chunk, zoom, valid_ids, &options.hex_cells)?;
+        apply_hex_id_filter(&mut cells_per_pipe, options.hex_id_filter.as_ref());
+        fold_hex_lengths(chunk, &cells_per_pipe, options, &mut lengths);
+        fold_hex_counts(cells_per_pipe, &mut counts, &mut cells_map);
+
+        records_processed += chunk.len();
+        on_progress(ComputeProgress {
+            records_processed,
+            total_records: records.len(),
+        });
+    }
+
+    // No chunks ran, but callers still expect a final report
+    if records.is_empty() {
+        on_progress(ComputeProgress {
+            records_processed: 0,
+            total_records: 0,
+        });
+    }
 
-    (sorted, cells_map)
+    Ok((sort_hex_counts(counts), cells_map, lengths))
+}
+
+/// Sorts hex counts by count, descending.
+pub(super) fn sort_hex_counts(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut sorted: Vec<_> = counts.into_iter().collect();
+    sorted.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    sorted
 }
 
 // =============================================================================
@@ -203,13 +771,30 @@ fn to_record_batch_impl<T: PipelineData, F: BoundaryFilter>(
     records: &[T],
     zoom: u8,
     filter: &F,
-    include_geom: bool,
+    options: &HexSummaryOptions,
 ) -> Result<RecordBatch, InfraHexError> {
     let valid_ids = filter.valid_cell_ids(zoom)?;
-    let cells_per_pipe = extract_cells_per_pipeline(records, zoom, &valid_ids)?;
+    build_record_batch(records, zoom, &valid_ids, options, 0)
+}
+
+/// Builds the per-pipeline batch for `records`, numbering `record_index`
+/// from `index_offset`.
+fn build_record_batch<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    valid_ids: &Option<HashSet<String>>,
+    options: &HexSummaryOptions,
+    index_offset: usize,
+) -> Result<RecordBatch, InfraHexError> {
+    let cells_per_pipe = extract_cells_per_pipeline(records, zoom, valid_ids, &options.hex_cells)?;
 
     let (asset_ids, pipe_types, materials, pressures) = build_pipeline_attributes(records);
     let hex_ids_list = build_hex_ids_list(&cells_per_pipe);
+    let lengths: Float64Array = records
+        .par_iter()
+        .map(pipe_length_m)
+        .collect::<Vec<f64>>()
+        .into();
 
     let base_fields = vec![
         Field::new("asset_id", DataType::Utf8, true),
@@ -221,6 +806,7 @@ fn to_record_batch_impl<T: PipelineData, F: BoundaryFilter>(
             DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
             false,
         ),
+        Field::new("length_m", DataType::Float64, false),
     ];
 
     let base_columns: Vec<Arc<dyn arrow_array::Array>> = vec![
@@ -229,21 +815,45 @@ fn to_record_batch_impl<T: PipelineData, F: BoundaryFilter>(
         Arc::new(materials),
         Arc::new(pressures),
         Arc::new(hex_ids_list),
+        Arc::new(lengths),
     ];
 
-    if include_geom {
-        let (geometry_array, geometry_field) = build_multipolygon_geometry(&cells_per_pipe);
-        let mut fields = base_fields;
+    let mut fields = base_fields;
+    let mut columns = base_columns;
+
+    if options.include_carrier {
+        let materials: StringArray = records.iter().map(|r| r.carrier_material()).collect();
+        let diameters: Float64Array = records.iter().map(|r| r.carrier_diameter_mm()).collect();
+        let units: StringArray = records.iter().map(|r| r.carrier_diameter_unit()).collect();
+        fields.extend([
+            Field::new("carrier_material", DataType::Utf8, true),
+            Field::new("carrier_diameter_mm", DataType::Float64, true),
+            Field::new("carrier_diameter_unit", DataType::Utf8, true),
+        ]);
+        columns.extend([
+            Arc::new(materials) as Arc<dyn arrow_array::Array>,
+            Arc::new(diameters),
+            Arc::new(units),
+        ]);
+    }
+
+    if options.include_record_index {
+        let indices: UInt32Array = (index_offset..index_offset + records.len())
+            .map(|i| Some(i as u32))
+            .collect();
+        fields.push(Field::new("record_index", DataType::UInt32, false));
+        columns.push(Arc::new(indices));
+    }
+
+    if options.include_geometry {
+        let (geometry_array, geometry_field) =
+            build_multipolygon_geometry(&cells_per_pipe, options)?;
         fields.push(geometry_field);
-        let mut columns = base_columns;
         columns.push(Arc::new(geometry_array.into_arrow()));
-
-        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
-            .map_err(|e| InfraHexError::Geometry(e.to_string()))
-    } else {
-        RecordBatch::try_new(Arc::new(Schema::new(base_fields)), base_columns)
-            .map_err(|e| InfraHexError::Geometry(e.to_string()))
     }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
 }
 
 // -----------------------------------------------------------------------------
@@ -254,14 +864,74 @@ pub fn to_record_batch_no_geom<T: PipelineData>(
     records: &[T],
     zoom: u8,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_record_batch_impl(records, zoom, &(), false)
+    to_record_batch_impl(
+        records,
+        zoom,
+        &(),
+        &HexSummaryOptions::new().with_geometry(false),
+    )
 }
 
+/// Builds a record batch with one row per pipeline.
+///
+/// Output is deterministic: rows are in the same order as `records` (hexing
+/// runs in parallel, but results are collected in input order), and each
+/// row's `hex_ids` list holds the pipe's cells in traversal order, from the
+/// first vertex of the line to the last. Boundary-filtered variants drop
+/// cells without reordering the rest. Identical input therefore yields
+/// identical batches across runs.
 pub fn to_record_batch<T: PipelineData>(
     records: &[T],
     zoom: u8,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_record_batch_impl(records, zoom, &(), true)
+    to_record_batch_impl(records, zoom, &(), &HexSummaryOptions::new())
+}
+
+/// Builds a per-pipeline record batch with explicit output options.
+///
+/// Pass `&()` as `boundary` to skip boundary filtering. Row and `hex_ids`
+/// ordering follow [`to_record_batch`].
+pub fn to_record_batch_with_options<T: PipelineData, F: BoundaryFilter>(
+    records: &[T],
+    zoom: u8,
+    boundary: &F,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    to_record_batch_impl(records, zoom, boundary, options)
+}
+
+/// Builds per-pipeline record batches lazily, `batch_rows` records at a time.
+///
+/// For areas too large to hold as one batch: each batch is built only when
+/// the iterator reaches it, so pairing this with [`GeoParquetBatchWriter`]
+/// keeps just one batch in memory at a time. Batches share a schema and,
+/// concatenated, equal [`to_record_batch_with_options`] over all of
+/// `records` (`record_index` counts across batches). The boundary's cells
+/// are computed once up front.
+///
+/// Returns `InfraHexError::Config` if `batch_rows` is zero.
+///
+/// [`GeoParquetBatchWriter`]: crate::core::GeoParquetBatchWriter
+pub fn to_record_batches<'a, T: PipelineData, F: BoundaryFilter>(
+    records: &'a [T],
+    zoom: u8,
+    boundary: &F,
+    options: &'a HexSummaryOptions,
+    batch_rows: usize,
+) -> Result<impl Iterator<Item = Result<RecordBatch, InfraHexError>> + 'a, InfraHexError> {
+    if batch_rows == 0 {
+        return Err(InfraHexError::Config(
+            "batch_rows must be greater than zero".to_string(),
+        ));
+    }
+
+    let valid_ids = boundary.valid_cell_ids(zoom)?;
+    Ok(records
+        .chunks(batch_rows)
+        .enumerate()
+        .map(move |(i, chunk)| {
+            build_record_batch(chunk, zoom, &valid_ids, options, i * batch_rows)
+        }))
 }
 
 pub fn to_record_batch_for_polygon_no_geom<T: PipelineData>(
@@ -269,7 +939,12 @@ pub fn to_record_batch_for_polygon_no_geom<T: PipelineData>(
     zoom: u8,
     polygon: &Polygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_record_batch_impl(records, zoom, polygon, false)
+    to_record_batch_impl(
+        records,
+        zoom,
+        polygon,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
 }
 
 pub fn to_record_batch_for_polygon<T: PipelineData>(
@@ -277,7 +952,7 @@ pub fn to_record_batch_for_polygon<T: PipelineData>(
     zoom: u8,
     polygon: &Polygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_record_batch_impl(records, zoom, polygon, true)
+    to_record_batch_impl(records, zoom, polygon, &HexSummaryOptions::new())
 }
 
 pub fn to_record_batch_for_multipolygon_no_geom<T: PipelineData>(
@@ -285,7 +960,12 @@ pub fn to_record_batch_for_multipolygon_no_geom<T: PipelineData>(
     zoom: u8,
     multipolygon: &MultiPolygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_record_batch_impl(records, zoom, multipolygon, false)
+    to_record_batch_impl(
+        records,
+        zoom,
+        multipolygon,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
 }
 
 pub fn to_record_batch_for_multipolygon<T: PipelineData>(
@@ -293,7 +973,7 @@ pub fn to_record_batch_for_multipolygon<T: PipelineData>(
     zoom: u8,
     multipolygon: &MultiPolygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_record_batch_impl(records, zoom, multipolygon, true)
+    to_record_batch_impl(records, zoom, multipolygon, &HexSummaryOptions::new())
 }
 
 // =============================================================================
@@ -304,42 +984,162 @@ fn to_hex_summary_impl<T: PipelineData, F: BoundaryFilter>(
     records: &[T],
     zoom: u8,
     filter: &F,
-    include_geom: bool,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_impl_with_progress(records, zoom, filter, options, &mut |_| {})
+}
+
+fn to_hex_summary_impl_with_progress<T: PipelineData, F: BoundaryFilter>(
+    records: &[T],
+    zoom: u8,
+    filter: &F,
+    options: &HexSummaryOptions,
+    on_progress: &mut dyn FnMut(ComputeProgress),
 ) -> Result<RecordBatch, InfraHexError> {
     let valid_ids = filter.valid_cell_ids(zoom)?;
-    let cells_per_pipe = extract_cells_per_pipeline(records, zoom, &valid_ids)?;
+    let (sorted, cells_map, lengths) =
+        aggregate_hex_counts(records, zoom, &valid_ids, options, on_progress)?;
 
-    let (sorted, cells_map) = aggregate_hex_counts(cells_per_pipe);
+    let boundary_ids = if options.include_on_boundary {
+        filter.boundary_cell_ids(zoom)?
+    } else {
+        None
+    };
 
+    build_hex_summary(
+        &sorted,
+        &cells_map,
+        &lengths,
+        boundary_ids.as_ref(),
+        options,
+    )
+}
+
+/// Builds the per-hex summary batch from aggregated counts.
+///
+/// `lengths` feeds the optional `total_length_m` column, with missing ids
+/// taken as 0.0. `boundary_ids` feeds the optional `on_boundary` column;
+/// `None` marks every cell as interior.
+pub(super) fn build_hex_summary(
+    sorted: &[(String, usize)],
+    cells_map: &HashMap<String, HexCell>,
+    lengths: &HashMap<String, f64>,
+    boundary_ids: Option<&HashSet<String>>,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
     let hex_ids: StringArray = sorted.iter().map(|(id, _)| Some(id.as_str())).collect();
     let pipe_counts: UInt32Array = sorted.iter().map(|(_, c)| Some(*c as u32)).collect();
 
-    let base_fields = vec![
-        Field::new("hex_id", DataType::Utf8, false),
-        Field::new("pipe_count", DataType::UInt32, false),
-    ];
+    let cells: Vec<&HexCell> = sorted
+        .iter()
+        .map(|(id, _)| cells_map.get(id).unwrap())
+        .collect();
 
-    let base_columns: Vec<Arc<dyn arrow_array::Array>> =
+    // Columns are pushed in the order `hex_summary_schema` lists them
+    let mut columns: Vec<Arc<dyn arrow_array::Array>> =
         vec![Arc::new(hex_ids), Arc::new(pipe_counts)];
 
-    if include_geom {
-        let cells: Vec<&HexCell> = sorted
+    if options.include_total_length {
+        let totals: Float64Array = sorted
             .iter()
-            .map(|(id, _)| cells_map.get(id).unwrap())
+            .map(|(id, _)| Some(lengths.get(id).copied().unwrap_or(0.0)))
             .collect();
+        columns.push(Arc::new(totals));
+    }
 
-        let (geometry_array, geometry_field) = build_polygon_geometry(&cells);
-        let mut fields = base_fields;
-        fields.push(geometry_field);
-        let mut columns = base_columns;
+    if let Some(normalisation) = options.count_normalisation {
+        let max = sorted.iter().map(|(_, c)| *c).max().unwrap_or(0);
+        let normalised: Float64Array = sorted
+            .iter()
+            .map(|(_, c)| Some(normalisation.normalise(*c, max)))
+            .collect();
+        columns.push(Arc::new(normalised));
+    }
+
+    if options.include_share_of_region {
+        let total: usize = sorted.iter().map(|(_, c)| *c).sum();
+        let shares: Float64Array = sorted
+            .iter()
+            .map(|(_, c)| Some(*c as f64 / total as f64))
+            .collect();
+        columns.push(Arc::new(shares));
+    }
+
+    if options.include_cell_area {
+        let areas: Float64Array = cells
+            .iter()
+            .map(|c| Some(c.to_polygon().unsigned_area()))
+            .collect();
+        columns.push(Arc::new(areas));
+    }
+
+    if options.include_on_boundary {
+        let on_boundary: BooleanArray = sorted
+            .iter()
+            .map(|(id, _)| Some(boundary_ids.is_some_and(|b| b.contains(id))))
+            .collect();
+        columns.push(Arc::new(on_boundary));
+    }
+
+    if options.include_h3_index {
+        #[cfg(feature = "h3")]
+        columns.push(Arc::new(super::h3::h3_index_column(&cells)?));
+        #[cfg(not(feature = "h3"))]
+        return Err(InfraHexError::Config(
+            "h3_index requires the `h3` feature".to_string(),
+        ));
+    }
+
+    if options.include_geometry {
+        let (geometry_array, _) = build_polygon_geometry(&cells, options)?;
         columns.push(Arc::new(geometry_array.into_arrow()));
+    }
 
-        RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
-            .map_err(|e| InfraHexError::Geometry(e.to_string()))
-    } else {
-        RecordBatch::try_new(Arc::new(Schema::new(base_fields)), base_columns)
-            .map_err(|e| InfraHexError::Geometry(e.to_string()))
+    RecordBatch::try_new(Arc::new(hex_summary_schema(options)), columns)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Returns the Arrow schema of the hex summary `options` would produce,
+/// without computing anything.
+///
+/// Lets downstream code validate or generate readers for the exact columns,
+/// names and geometry metadata a summary will have. Covers the ungrouped
+/// `to_hex_summary*` functions; see [`schema_to_json`] for a portable form.
+///
+/// [`schema_to_json`]: crate::core::schema_to_json
+pub fn hex_summary_schema(options: &HexSummaryOptions) -> Schema {
+    let names = &options.column_names;
+    let mut fields = vec![
+        Field::new(&names.hex_id, DataType::Utf8, false),
+        Field::new(&names.pipe_count, DataType::UInt32, false),
+    ];
+
+    if options.include_total_length {
+        fields.push(Field::new("total_length_m", DataType::Float64, false));
+    }
+    if options.count_normalisation.is_some() {
+        fields.push(Field::new("count_normalised", DataType::Float64, false));
+    }
+    if options.include_share_of_region {
+        fields.push(Field::new("share_of_region", DataType::Float64, false));
+    }
+    if options.include_cell_area {
+        fields.push(Field::new("cell_area_m2", DataType::Float64, false));
     }
+    if options.include_on_boundary {
+        fields.push(Field::new("on_boundary", DataType::Boolean, false));
+    }
+    if options.include_h3_index {
+        fields.push(Field::new("h3_index", DataType::UInt64, false));
+    }
+    if options.include_geometry {
+        fields.push(
+            PolygonType::new(Dimension::XY, output_metadata(options))
+                .to_field(&names.geometry, false),
+        );
+    }
+
+    Schema::new(fields)
 }
 
 // -----------------------------------------------------------------------------
@@ -350,14 +1150,68 @@ pub fn to_hex_summary_no_geom<T: PipelineData>(
     records: &[T],
     zoom: u8,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_hex_summary_impl(records, zoom, &(), false)
+    to_hex_summary_impl(
+        records,
+        zoom,
+        &(),
+        &HexSummaryOptions::new().with_geometry(false),
+    )
 }
 
 pub fn to_hex_summary<T: PipelineData>(
     records: &[T],
     zoom: u8,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_hex_summary_impl(records, zoom, &(), true)
+    to_hex_summary_impl(records, zoom, &(), &HexSummaryOptions::new())
+}
+
+/// Builds a per-hex summary counting each pipe in every hex within
+/// `buffer_m` metres of it, e.g. to map easements or exclusion zones.
+///
+/// See [`HexCellOptions::with_buffer_m`]. Combine other options with
+/// [`to_hex_summary_with_options`] and
+/// [`HexSummaryOptions::with_hex_cell_options`].
+pub fn to_hex_summary_buffered_m<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    buffer_m: f64,
+) -> Result<RecordBatch, InfraHexError> {
+    let options = HexSummaryOptions::new()
+        .with_hex_cell_options(HexCellOptions::new().with_buffer_m(buffer_m));
+    to_hex_summary_impl(records, zoom, &(), &options)
+}
+
+/// Builds a per-hex summary with explicit output options.
+///
+/// Pass `&()` as `boundary` to skip boundary filtering.
+pub fn to_hex_summary_with_options<T: PipelineData, F: BoundaryFilter>(
+    records: &[T],
+    zoom: u8,
+    boundary: &F,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_impl(records, zoom, boundary, options)
+}
+
+/// Builds a per-hex summary like [`to_hex_summary_with_options`], calling
+/// `on_progress` as records are hexed.
+///
+/// Records are processed in chunks (`options.chunk_size`, or
+/// [`PROGRESS_CHUNK_SIZE`] when unset); each chunk is hexed in parallel and
+/// `on_progress` runs on the calling thread between chunks, so it needn't be
+/// `Send`. The final call reports every record processed; empty input gets
+/// a single call reporting 0 of 0.
+pub fn to_hex_summary_with_progress<T: PipelineData, F: BoundaryFilter>(
+    records: &[T],
+    zoom: u8,
+    boundary: &F,
+    options: &HexSummaryOptions,
+    mut on_progress: impl FnMut(ComputeProgress),
+) -> Result<RecordBatch, InfraHexError> {
+    let mut options = options.clone();
+    options.chunk_size = options.chunk_size.or(Some(PROGRESS_CHUNK_SIZE));
+
+    to_hex_summary_impl_with_progress(records, zoom, boundary, &options, &mut on_progress)
 }
 
 pub fn to_hex_summary_for_polygon_no_geom<T: PipelineData>(
@@ -365,7 +1219,12 @@ pub fn to_hex_summary_for_polygon_no_geom<T: PipelineData>(
     zoom: u8,
     polygon: &Polygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_hex_summary_impl(records, zoom, polygon, false)
+    to_hex_summary_impl(
+        records,
+        zoom,
+        polygon,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
 }
 
 pub fn to_hex_summary_for_polygon<T: PipelineData>(
@@ -373,7 +1232,7 @@ pub fn to_hex_summary_for_polygon<T: PipelineData>(
     zoom: u8,
     polygon: &Polygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_hex_summary_impl(records, zoom, polygon, true)
+    to_hex_summary_impl(records, zoom, polygon, &HexSummaryOptions::new())
 }
 
 pub fn to_hex_summary_for_multipolygon_no_geom<T: PipelineData>(
@@ -381,7 +1240,12 @@ pub fn to_hex_summary_for_multipolygon_no_geom<T: PipelineData>(
     zoom: u8,
     multipolygon: &MultiPolygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_hex_summary_impl(records, zoom, multipolygon, false)
+    to_hex_summary_impl(
+        records,
+        zoom,
+        multipolygon,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
 }
 
 pub fn to_hex_summary_for_multipolygon<T: PipelineData>(
@@ -389,5 +1253,784 @@ pub fn to_hex_summary_for_multipolygon<T: PipelineData>(
     zoom: u8,
     multipolygon: &MultiPolygon<f64>,
 ) -> Result<RecordBatch, InfraHexError> {
-    to_hex_summary_impl(records, zoom, multipolygon, true)
+    to_hex_summary_impl(records, zoom, multipolygon, &HexSummaryOptions::new())
+}
+
+// =============================================================================
+// Record-Hex Pair Functions (one row per pipeline per hex cell)
+// =============================================================================
+
+fn to_record_hex_pairs_impl<T: PipelineData, F: BoundaryFilter>(
+    records: &[T],
+    zoom: u8,
+    filter: &F,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    let valid_ids = filter.valid_cell_ids(zoom)?;
+    let cells_per_pipe = extract_cells_per_pipeline(records, zoom, &valid_ids, &options.hex_cells)?;
+
+    // (record index, cell) for each distinct cell of each pipe
+    let mut pairs: Vec<(usize, &HexCell)> = Vec::new();
+    for (index, cells) in cells_per_pipe.iter().enumerate() {
+        let mut seen_in_pipe = HashSet::new();
+        for cell in cells {
+            if seen_in_pipe.insert(cell.id.as_str()) {
+                pairs.push((index, cell));
+            }
+        }
+    }
+
+    let attribute = |get: fn(&T) -> Option<&str>| -> StringArray {
+        pairs.iter().map(|(i, _)| get(&records[*i])).collect()
+    };
+    let hex_ids: StringArray = pairs.iter().map(|(_, c)| Some(c.id.as_str())).collect();
+
+    let mut fields = vec![
+        Field::new("asset_id", DataType::Utf8, true),
+        Field::new("pipe_type", DataType::Utf8, true),
+        Field::new("material", DataType::Utf8, true),
+        Field::new("pressure", DataType::Utf8, true),
+        Field::new(&options.column_names.hex_id, DataType::Utf8, false),
+    ];
+    let mut columns: Vec<Arc<dyn arrow_array::Array>> = vec![
+        Arc::new(attribute(T::asset_id)),
+        Arc::new(attribute(T::pipe_type)),
+        Arc::new(attribute(T::material)),
+        Arc::new(attribute(T::pressure)),
+        Arc::new(hex_ids),
+    ];
+
+    if options.include_record_index {
+        let indices: UInt32Array = pairs.iter().map(|(i, _)| Some(*i as u32)).collect();
+        fields.push(Field::new("record_index", DataType::UInt32, false));
+        columns.push(Arc::new(indices));
+    }
+
+    if options.include_geometry {
+        let cells: Vec<&HexCell> = pairs.iter().map(|(_, c)| *c).collect();
+        let (geometry_array, geometry_field) = build_polygon_geometry(&cells, options)?;
+        fields.push(geometry_field);
+        columns.push(Arc::new(geometry_array.into_arrow()));
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+// -----------------------------------------------------------------------------
+// Public Record-Hex Pair API
+// -----------------------------------------------------------------------------
+
+/// Builds a long table with one row per (pipeline, hex cell) pair: the
+/// unnested form of [`to_record_batch`]'s `hex_ids` list, with each cell's
+/// polygon as geometry.
+///
+/// A pipe touching `n` distinct cells expands to `n` rows carrying copies of
+/// its attributes (pipes with no cells produce no rows). A cell the pipe
+/// re-enters appears once, so the batch has as many rows as the sum of the
+/// distinct ids in every `hex_ids` list, which can be fewer than the lists'
+/// total length. Rows are grouped by pipe in input order, cells in order of
+/// first visit. Convenient for joining against a hex lookup table in SQL.
+pub fn to_record_hex_pairs<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    to_record_hex_pairs_impl(records, zoom, &(), &HexSummaryOptions::new())
+}
+
+pub fn to_record_hex_pairs_no_geom<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    to_record_hex_pairs_impl(
+        records,
+        zoom,
+        &(),
+        &HexSummaryOptions::new().with_geometry(false),
+    )
+}
+
+/// Builds (pipeline, hex cell) pairs with explicit output options.
+///
+/// Pass `&()` as `boundary` to skip boundary filtering. Honours
+/// `with_record_index` to trace rows back to `records`.
+pub fn to_record_hex_pairs_with_options<T: PipelineData, F: BoundaryFilter>(
+    records: &[T],
+    zoom: u8,
+    boundary: &F,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    to_record_hex_pairs_impl(records, zoom, boundary, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use arrow_array::Array;
+    use geo_types::LineString;
+
+    fn make_record(asset_id: &str, coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            pipe_type: Some("MP".to_string()),
+            asset_id: Some(asset_id.to_string()),
+            ..line_record(coords)
+        }
+    }
+
+    fn make_records() -> Vec<CadentPipelineRecord> {
+        vec![
+            make_record("A", vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]]),
+            make_record("B", vec![vec![-2.2484, 53.4804], vec![-2.2484, 53.4814]]),
+        ]
+    }
+
+    #[test]
+    fn test_record_batch_length_column() {
+        let batch = to_record_batch_no_geom(&make_records(), 10).unwrap();
+
+        let lengths = batch
+            .column_by_name("length_m")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+
+        assert_eq!(lengths.len(), 2);
+        assert_eq!(lengths.null_count(), 0);
+        // Second pipe runs ~111m north-south
+        assert!((lengths.value(1) - 111.0).abs() < 2.0);
+    }
+
+    fn geometry_crs(batch: &RecordBatch) -> String {
+        let schema = batch.schema();
+        let field = schema.field_with_name("geometry").unwrap();
+        field
+            .metadata()
+            .get("ARROW:extension:metadata")
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_record_batch_geometry_crs_follows_options() {
+        let records = make_records();
+
+        let bng = to_record_batch(&records, 10).unwrap();
+        assert!(geometry_crs(&bng).contains("EPSG:27700"));
+
+        let options = HexSummaryOptions::new().with_reproject_to_wgs84(true);
+        let wgs84 = to_record_batch_with_options(&records, 10, &(), &options).unwrap();
+        assert!(geometry_crs(&wgs84).contains("EPSG:4326"));
+    }
+
+    fn summary_counts(batch: &RecordBatch) -> HashMap<String, u32> {
+        let ids = batch
+            .column_by_name("hex_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let counts = batch
+            .column_by_name("pipe_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        (0..batch.num_rows())
+            .map(|i| (ids.value(i).to_string(), counts.value(i)))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunked_summary_matches_unchunked() {
+        let mut records = make_records();
+        records.push(make_record(
+            "C",
+            vec![vec![-2.2502, 53.4806], vec![-2.2484, 53.4814]],
+        ));
+
+        let expected = summary_counts(&to_hex_summary_no_geom(&records, 12).unwrap());
+
+        for chunk_size in [1, 2, 5] {
+            let options = HexSummaryOptions::new()
+                .with_geometry(false)
+                .with_chunk_size(chunk_size);
+            let chunked = to_hex_summary_with_options(&records, 12, &(), &options).unwrap();
+            assert_eq!(summary_counts(&chunked), expected);
+        }
+    }
+
+    #[test]
+    fn test_no_geom_summary_cell_area_column() {
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_cell_area(true);
+        let summary = to_hex_summary_with_options(&make_records(), 10, &(), &options).unwrap();
+
+        assert!(summary.column_by_name("geometry").is_none());
+        let areas = summary
+            .column_by_name("cell_area_m2")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+
+        assert_eq!(areas.len(), summary.num_rows());
+        assert_eq!(areas.null_count(), 0);
+        assert!(areas.iter().all(|a| a.unwrap() > 0.0));
+    }
+
+    #[test]
+    fn test_custom_column_names() {
+        let names = ColumnNames::default()
+            .with_hex_id("h3_index")
+            .with_pipe_count("count")
+            .with_geometry("geom");
+        let options = HexSummaryOptions::new().with_column_names(names);
+        let summary = to_hex_summary_with_options(&make_records(), 10, &(), &options).unwrap();
+
+        let schema = summary.schema();
+        let field_names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            field_names,
+            vec!["h3_index", "count", "total_length_m", "geom"]
+        );
+    }
+
+    #[test]
+    fn test_total_length_splits_straight_pipe_between_two_cells() {
+        // Join the centres of two neighbouring cells, so the pipe crosses
+        // their shared edge halfway along
+        let start = HexCell::from_bng(&geo_types::Point::new(383_500.0, 398_200.0), 10).unwrap();
+        let end = (1..)
+            .map(|step| {
+                let x = start.center.x() + step as f64;
+                HexCell::from_bng(&geo_types::Point::new(x, start.center.y()), 10).unwrap()
+            })
+            .find(|cell| cell.id != start.id)
+            .unwrap();
+        let record = make_record(
+            "A",
+            vec![
+                vec![start.center.x(), start.center.y()],
+                vec![end.center.x(), end.center.y()],
+            ],
+        );
+        let pipe_length =
+            (end.center.x() - start.center.x()).hypot(end.center.y() - start.center.y());
+
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_hex_cell_options(HexCellOptions::default().with_crs(crate::core::Crs::Bng));
+        let summary = to_hex_summary_with_options(&[record], 10, &(), &options).unwrap();
+
+        let ids = summary
+            .column_by_name("hex_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let lengths = summary
+            .column_by_name("total_length_m")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+
+        let by_id: HashMap<&str, f64> = (0..summary.num_rows())
+            .map(|i| (ids.value(i), lengths.value(i)))
+            .collect();
+        assert_eq!(
+            by_id.keys().copied().collect::<HashSet<_>>(),
+            HashSet::from([start.id.as_str(), end.id.as_str()])
+        );
+        for length in by_id.values() {
+            assert!((length - pipe_length / 2.0).abs() < 1e-6 * pipe_length);
+        }
+    }
+
+    #[test]
+    fn test_total_length_can_be_omitted() {
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_total_length(false);
+        let summary = to_hex_summary_with_options(&make_records(), 10, &(), &options).unwrap();
+
+        assert!(summary.column_by_name("total_length_m").is_none());
+        assert_eq!(summary.schema().as_ref(), &hex_summary_schema(&options));
+    }
+
+    #[test]
+    fn test_count_normalisation() {
+        // Two pipes share one cell, so counts differ across the batch
+        let records = vec![
+            make_record("A", vec![vec![-2.2400, 53.4800], vec![-2.2401, 53.4800]]),
+            make_record("B", vec![vec![-2.2400, 53.4800], vec![-2.2401, 53.4800]]),
+            make_record("C", vec![vec![-2.1000, 53.4000], vec![-2.1001, 53.4000]]),
+        ];
+
+        for normalisation in [CountNormalisation::Linear, CountNormalisation::Log] {
+            let options = HexSummaryOptions::new()
+                .with_geometry(false)
+                .with_count_normalisation(normalisation);
+            let summary = to_hex_summary_with_options(&records, 12, &(), &options).unwrap();
+
+            assert_eq!(summary.schema().field(3).name(), "count_normalised");
+            let normalised = summary
+                .column(3)
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+
+            // Rows are sorted by count descending, so the first is the max
+            assert_eq!(normalised.value(0), 1.0);
+            for i in 1..normalised.len() {
+                assert!(normalised.value(i) > 0.0 && normalised.value(i) < 1.0);
+            }
+        }
+
+        assert_eq!(CountNormalisation::Linear.normalise(1, 2), 0.5);
+        assert_eq!(CountNormalisation::Log.normalise(3, 3), 1.0);
+    }
+
+    #[test]
+    fn test_share_of_region_sums_to_one() {
+        let boundary = MultiPolygon::new(vec![Polygon::new(
+            LineString::from(vec![
+                (-2.30, 53.35),
+                (-2.05, 53.35),
+                (-2.05, 53.50),
+                (-2.30, 53.50),
+                (-2.30, 53.35),
+            ]),
+            vec![],
+        )]);
+        let records = vec![
+            make_record("A", vec![vec![-2.2400, 53.4800], vec![-2.2300, 53.4850]]),
+            make_record("B", vec![vec![-2.2400, 53.4800], vec![-2.2401, 53.4800]]),
+            make_record("C", vec![vec![-2.1000, 53.4000], vec![-2.1001, 53.4000]]),
+        ];
+
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_share_of_region(true);
+        let summary = to_hex_summary_with_options(&records, 12, &boundary, &options).unwrap();
+        assert!(summary.num_rows() > 0);
+
+        let shares = summary
+            .column_by_name("share_of_region")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        let total: f64 = shares.iter().flatten().sum();
+        assert!((total - 1.0).abs() < 1e-9, "shares sum to {}", total);
+        assert!(shares.iter().flatten().all(|s| s > 0.0 && s <= 1.0));
+    }
+
+    #[test]
+    fn test_hex_id_filter_allowlist_and_denylist() {
+        let records = vec![
+            make_record("A", vec![vec![-2.2400, 53.4800], vec![-2.2401, 53.4800]]),
+            make_record("B", vec![vec![-2.1000, 53.4000], vec![-2.1001, 53.4000]]),
+            make_record("C", vec![vec![-2.0000, 53.3000], vec![-2.0001, 53.3000]]),
+        ];
+        let all = to_hex_summary_no_geom(&records, 12).unwrap();
+        let all_ids: HashSet<String> = summary_hex_ids(&all).into_iter().collect();
+        assert!(all_ids.len() >= 3);
+
+        let allowed: HashSet<String> = all_ids.iter().take(2).cloned().collect();
+        let options =
+            HexSummaryOptions::new().with_hex_id_filter(HexIdFilter::Allow(allowed.clone()));
+        let summary = to_hex_summary_with_options(&records, 12, &(), &options).unwrap();
+        let ids: HashSet<String> = summary_hex_ids(&summary).into_iter().collect();
+        assert_eq!(ids, allowed);
+
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_hex_id_filter(HexIdFilter::Deny(allowed.clone()));
+        let summary = to_hex_summary_with_options(&records, 12, &(), &options).unwrap();
+        let ids: HashSet<String> = summary_hex_ids(&summary).into_iter().collect();
+        assert_eq!(ids, &all_ids - &allowed);
+    }
+
+    fn summary_hex_ids(summary: &RecordBatch) -> Vec<String> {
+        summary
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .iter()
+            .map(|id| id.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_max_cells_per_pipe_is_threaded_through() {
+        let options = HexSummaryOptions::new()
+            .with_hex_cell_options(HexCellOptions::new().with_max_cells_per_pipe(1));
+        // A ~15 km line spans far more than one cell at zoom 12
+        let records = vec![make_record(
+            "LONG",
+            vec![vec![-2.40, 53.40], vec![-2.20, 53.50]],
+        )];
+
+        let result = to_hex_summary_with_options(&records, 12, &(), &options);
+        assert!(matches!(result, Err(InfraHexError::Geometry(_))));
+
+        let result = to_record_batch_with_options(&records, 12, &(), &options);
+        assert!(matches!(result, Err(InfraHexError::Geometry(_))));
+    }
+
+    #[cfg(not(feature = "h3"))]
+    #[test]
+    fn test_h3_index_without_feature_is_config_error() {
+        let options = HexSummaryOptions::new().with_h3_index(true);
+        let result = to_hex_summary_with_options(&make_records(), 10, &(), &options);
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_progress_reported_per_chunk() {
+        let records: Vec<_> = (0..5).flat_map(|_| make_records()).collect();
+        let options = HexSummaryOptions::new().with_chunk_size(3);
+
+        let mut updates = Vec::new();
+        let with_progress =
+            to_hex_summary_with_progress(&records, 10, &(), &options, |p| updates.push(p)).unwrap();
+        let without = to_hex_summary_with_options(&records, 10, &(), &options).unwrap();
+
+        let processed: Vec<usize> = updates.iter().map(|p| p.records_processed).collect();
+        assert_eq!(processed, vec![3, 6, 9, 10]);
+        assert!(updates.iter().all(|p| p.total_records == 10));
+        assert_eq!(updates.last().unwrap().fraction(), 1.0);
+        assert_eq!(with_progress.num_rows(), without.num_rows());
+
+        let mut updates = Vec::new();
+        to_hex_summary_with_progress::<CadentPipelineRecord, _>(&[], 10, &(), &options, |p| {
+            updates.push(p)
+        })
+        .unwrap();
+        assert_eq!(
+            updates,
+            vec![ComputeProgress {
+                records_processed: 0,
+                total_records: 0
+            }]
+        );
+    }
+
+    fn hex_ids_per_row(batch: &RecordBatch) -> Vec<Vec<String>> {
+        let list = batch
+            .column_by_name("hex_ids")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow_array::ListArray>()
+            .unwrap();
+        (0..list.len())
+            .map(|i| {
+                let values = list.value(i);
+                let values = values.as_any().downcast_ref::<StringArray>().unwrap();
+                values.iter().map(|v| v.unwrap().to_string()).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_record_batch_row_order_matches_input() {
+        let records: Vec<_> = (0..50)
+            .map(|i| {
+                let lat = 53.40 + i as f64 * 0.002;
+                make_record(
+                    &format!("P{:02}", i),
+                    vec![vec![-2.2484, lat], vec![-2.2502, lat + 0.0002]],
+                )
+            })
+            .collect();
+
+        let batch = to_record_batch_no_geom(&records, 12).unwrap();
+        let asset_ids = batch
+            .column_by_name("asset_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+
+        for (i, asset_id) in asset_ids.iter().enumerate() {
+            assert_eq!(asset_id, Some(format!("P{:02}", i).as_str()));
+        }
+    }
+
+    #[test]
+    fn test_record_batch_hex_ids_in_traversal_order() {
+        let records = vec![make_record(
+            "LONG",
+            vec![vec![-2.30, 53.40], vec![-2.25, 53.42], vec![-2.20, 53.41]],
+        )];
+
+        let batch = to_record_batch_no_geom(&records, 12).unwrap();
+        let expected: Vec<String> =
+            get_hex_cells_with_options(&records[0], 12, &HexCellOptions::new())
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect();
+
+        assert_eq!(hex_ids_per_row(&batch), vec![expected]);
+
+        // Repeated runs are identical
+        let again = to_record_batch_no_geom(&records, 12).unwrap();
+        assert_eq!(batch, again);
+    }
+
+    #[test]
+    fn test_on_boundary_flags_edge_cells() {
+        let boundary = MultiPolygon::new(vec![Polygon::new(
+            LineString::from(vec![
+                (-2.2601, 53.4701),
+                (-2.2201, 53.4701),
+                (-2.2201, 53.4901),
+                (-2.2601, 53.4901),
+                (-2.2601, 53.4701),
+            ]),
+            vec![],
+        )]);
+        let records = vec![
+            // Well inside the boundary
+            make_record(
+                "INNER",
+                vec![vec![-2.2400, 53.4800], vec![-2.2401, 53.4800]],
+            ),
+            // Crossing the western edge
+            make_record("EDGE", vec![vec![-2.2606, 53.4800], vec![-2.2596, 53.4800]]),
+        ];
+        let inner_ids: HashSet<String> =
+            get_hex_cells_with_options(&records[0], 12, &HexCellOptions::new())
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect();
+
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_on_boundary(true);
+        let summary = to_hex_summary_with_options(&records, 12, &boundary, &options).unwrap();
+
+        let hex_ids = summary
+            .column_by_name("hex_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let on_boundary = summary
+            .column_by_name("on_boundary")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+
+        let mut saw_edge = false;
+        for (id, flag) in hex_ids.iter().zip(on_boundary.iter()) {
+            let (id, flag) = (id.unwrap(), flag.unwrap());
+            if inner_ids.contains(id) {
+                assert!(!flag, "interior cell {} flagged", id);
+            } else {
+                saw_edge |= flag;
+            }
+        }
+        assert!(saw_edge);
+    }
+
+    #[test]
+    fn test_on_boundary_without_boundary_is_false() {
+        let options = HexSummaryOptions::new().with_on_boundary(true);
+        let summary = to_hex_summary_with_options(&make_records(), 10, &(), &options).unwrap();
+        let on_boundary = summary
+            .column_by_name("on_boundary")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+
+        assert!(on_boundary.iter().all(|flag| flag == Some(false)));
+    }
+
+    #[test]
+    fn test_record_batches_concat_to_single_batch() {
+        let mut records = make_records();
+        records.push(make_record(
+            "C",
+            vec![vec![-2.2502, 53.4806], vec![-2.2484, 53.4814]],
+        ));
+        let options = HexSummaryOptions::new().with_record_index(true);
+
+        let batches: Vec<RecordBatch> = to_record_batches(&records, 10, &(), &options, 2)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+
+        let whole = to_record_batch_with_options(&records, 10, &(), &options).unwrap();
+        let concatenated = arrow_select::concat::concat_batches(&whole.schema(), &batches).unwrap();
+        assert_eq!(concatenated, whole);
+    }
+
+    #[test]
+    fn test_record_batches_rejects_zero_rows() {
+        let records = make_records();
+        let options = HexSummaryOptions::new();
+        let result = to_record_batches(&records, 10, &(), &options, 0);
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_record_index_matches_positions() {
+        let records: Vec<_> = (0..3).flat_map(|_| make_records()).collect();
+        let options = HexSummaryOptions::new().with_record_index(true);
+        let batch = to_record_batch_with_options(&records, 10, &(), &options).unwrap();
+
+        let indices = batch
+            .column_by_name("record_index")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+        let expected: Vec<Option<u32>> = (0..records.len() as u32).map(Some).collect();
+        assert_eq!(indices.iter().collect::<Vec<_>>(), expected);
+
+        let without = to_record_batch(&records, 10).unwrap();
+        assert!(without.column_by_name("record_index").is_none());
+    }
+
+    #[test]
+    fn test_carrier_attributes() {
+        let mut records = make_records();
+        records[0].carr_mat = Some("ST".to_string());
+        records[0].carr_dia = Some(12.0);
+        records[0].carr_di_un = Some("in".to_string());
+
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_carrier_attributes(true);
+        let batch = to_record_batch_with_options(&records, 10, &(), &options).unwrap();
+
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let materials = column("carrier_material");
+        let materials = materials.as_any().downcast_ref::<StringArray>().unwrap();
+        let diameters = column("carrier_diameter_mm");
+        let diameters = diameters.as_any().downcast_ref::<Float64Array>().unwrap();
+        let units = column("carrier_diameter_unit");
+        let units = units.as_any().downcast_ref::<StringArray>().unwrap();
+
+        assert_eq!(materials.value(0), "ST");
+        assert!((diameters.value(0) - 304.8).abs() < 1e-9);
+        assert_eq!(units.value(0), "in");
+        assert!(materials.is_null(1) && diameters.is_null(1) && units.is_null(1));
+
+        let without = to_record_batch_no_geom(&records, 10).unwrap();
+        assert!(without.column_by_name("carrier_material").is_none());
+    }
+
+    #[test]
+    fn test_cell_polygon_ring_orientation() {
+        use geo::Winding;
+
+        let cell = HexCell::from_wgs84(&geo_types::Point::new(-2.2484, 53.4804), 10).unwrap();
+
+        let ccw = cell_polygon(&cell, &HexSummaryOptions::new());
+        assert!(ccw.exterior().is_ccw());
+
+        let options = HexSummaryOptions::new()
+            .with_ring_orientation(RingOrientation::Clockwise)
+            .with_reproject_to_wgs84(true);
+        assert!(cell_polygon(&cell, &options).exterior().is_cw());
+    }
+
+    #[test]
+    fn test_record_hex_pairs_one_row_per_cell() {
+        let records = vec![make_record(
+            "LONG",
+            vec![vec![-2.2484, 53.4804], vec![-2.2600, 53.4830]],
+        )];
+        let cells: HashSet<String> =
+            get_hex_cells_with_options(&records[0], 12, &HexCellOptions::new())
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect();
+        assert!(cells.len() >= 3);
+
+        let pairs = to_record_hex_pairs(&records, 12).unwrap();
+        assert_eq!(pairs.num_rows(), cells.len());
+
+        let asset_ids = pairs
+            .column_by_name("asset_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert!(asset_ids.iter().all(|id| id == Some("LONG")));
+
+        let hex_ids = pairs
+            .column_by_name("hex_id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let pair_ids: HashSet<String> = hex_ids.iter().map(|id| id.unwrap().to_string()).collect();
+        assert_eq!(pair_ids, cells);
+        assert!(pairs.column_by_name("geometry").is_some());
+    }
+
+    #[test]
+    fn test_record_hex_pairs_match_record_batch_lists() {
+        let records = make_records();
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_record_index(true);
+        let pairs = to_record_hex_pairs_with_options(&records, 12, &(), &options).unwrap();
+        let batch = to_record_batch_no_geom(&records, 12).unwrap();
+
+        let list_total: usize = hex_ids_per_row(&batch)
+            .iter()
+            .map(|ids| ids.iter().collect::<HashSet<_>>().len())
+            .sum();
+        assert_eq!(pairs.num_rows(), list_total);
+        assert!(pairs.column_by_name("record_index").is_some());
+        assert!(pairs.column_by_name("geometry").is_none());
+    }
+
+    #[test]
+    fn test_check_polygon_rejects_degenerate() {
+        let collapsed = Polygon::new(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]), vec![]);
+        let result = check_polygon("Z10R1C1", &collapsed);
+        assert!(matches!(&result, Err(InfraHexError::Geometry(msg)) if msg.contains("Z10R1C1")));
+
+        let non_finite = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (f64::NAN, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+            vec![],
+        );
+        assert!(check_polygon("Z10R1C1", &non_finite).is_err());
+
+        let cell = HexCell::from_wgs84(&geo_types::Point::new(-2.2484, 53.4804), 10).unwrap();
+        assert!(check_polygon(&cell.id, &cell.to_polygon()).is_ok());
+    }
+
+    #[test]
+    fn test_zero_chunk_size_is_rejected() {
+        let options = HexSummaryOptions::new().with_chunk_size(0);
+        assert!(to_hex_summary_with_options(&make_records(), 10, &(), &options).is_err());
+    }
+
+    #[test]
+    fn test_hex_summary_geometry_crs_follows_options() {
+        let options = HexSummaryOptions::new().with_reproject_to_wgs84(true);
+        let summary = to_hex_summary_with_options(&make_records(), 10, &(), &options).unwrap();
+        assert!(geometry_crs(&summary).contains("EPSG:4326"));
+    }
 }
@@ -0,0 +1,199 @@
+use arrow_array::RecordBatch;
+use arrow_schema::Field;
+use serde_json::Value;
+
+use super::reproject::Crs;
+
+/// Arrow field metadata key holding an extension type's name.
+const EXTENSION_NAME_KEY: &str = "ARROW:extension:name";
+
+/// Arrow field metadata key holding an extension type's JSON parameters.
+const EXTENSION_METADATA_KEY: &str = "ARROW:extension:metadata";
+
+/// Prefix shared by all GeoArrow extension type names (`geoarrow.polygon` etc.).
+const GEOARROW_PREFIX: &str = "geoarrow.";
+
+/// Estimates the in-memory footprint of a RecordBatch in bytes.
+///
+/// This sums `get_array_memory_size()` across every column, which includes
+/// buffer capacity as well as the used length, so it is an upper bound on the
+/// data actually held by the batch.
+pub fn estimate_batch_memory(batch: &RecordBatch) -> usize {
+    batch
+        .columns()
+        .iter()
+        .map(|c| c.get_array_memory_size())
+        .sum()
+}
+
+/// Returns true if the batch's estimated footprint is within `limit_bytes`.
+///
+/// Useful as a quick check before writing or holding onto a large summary,
+/// e.g. to decide whether to partition the input first.
+pub fn would_fit(batch: &RecordBatch, limit_bytes: usize) -> bool {
+    estimate_batch_memory(batch) <= limit_bytes
+}
+
+/// Returns the index of the first GeoArrow geometry column in the batch.
+///
+/// Detection uses the field's extension type metadata rather than its name,
+/// so it finds a geometry column wherever it sits in the schema and whatever
+/// it's called. Returns `None` for batches without geometry (e.g. the
+/// `_no_geom` outputs).
+pub fn geometry_column_index(batch: &RecordBatch) -> Option<usize> {
+    batch
+        .schema()
+        .fields()
+        .iter()
+        .position(|field| is_geometry_field(field))
+}
+
+/// Whether `field` carries a GeoArrow extension type.
+pub(crate) fn is_geometry_field(field: &Field) -> bool {
+    field
+        .metadata()
+        .get(EXTENSION_NAME_KEY)
+        .is_some_and(|name| name.starts_with(GEOARROW_PREFIX))
+}
+
+/// Reads the CRS of a GeoArrow geometry field from its extension metadata.
+///
+/// The `crs` member may be an `authority:code` string (`EPSG:4326`,
+/// `OGC:CRS84`, as written by the `to_*` functions) or a PROJJSON object
+/// with an `id` (as GeoParquet files are read back). Returns `None` when the
+/// CRS is missing, can't be parsed, or is neither WGS84 nor BNG.
+pub(crate) fn geometry_crs(field: &Field) -> Option<Crs> {
+    let metadata: Value =
+        serde_json::from_str(field.metadata().get(EXTENSION_METADATA_KEY)?).ok()?;
+
+    let (authority, code) = match metadata.get("crs")? {
+        Value::String(crs) => {
+            let crs = crs.strip_prefix("urn:ogc:def:crs:").unwrap_or(crs);
+            let (authority, code) = crs.split_once(':')?;
+            // URNs carry an empty version between authority and code
+            (
+                authority.to_string(),
+                code.trim_start_matches(':').to_string(),
+            )
+        }
+        Value::Object(projjson) => {
+            let id = projjson.get("id")?;
+            let code = match id.get("code")? {
+                Value::Number(n) => n.to_string(),
+                Value::String(s) => s.clone(),
+                _ => return None,
+            };
+            (id.get("authority")?.as_str()?.to_string(), code)
+        }
+        _ => return None,
+    };
+
+    match (authority.to_ascii_uppercase().as_str(), code.as_str()) {
+        ("EPSG", "4326") | ("OGC", "CRS84") | ("OGC", "1.3:CRS84") => Some(Crs::Wgs84),
+        ("EPSG", "27700") => Some(Crs::Bng),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{BinaryArray, StringArray, UInt32Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn make_batch(rows: usize) -> RecordBatch {
+        let ids: StringArray = (0..rows).map(|i| Some(format!("hex-{}", i))).collect();
+        let counts: UInt32Array = (0..rows).map(|i| Some(i as u32)).collect();
+        let schema = Schema::new(vec![
+            Field::new("hex_id", DataType::Utf8, false),
+            Field::new("pipe_count", DataType::UInt32, false),
+        ]);
+        RecordBatch::try_new(Arc::new(schema), vec![Arc::new(ids), Arc::new(counts)]).unwrap()
+    }
+
+    #[test]
+    fn test_estimate_batch_memory_grows_with_rows() {
+        let small = estimate_batch_memory(&make_batch(10));
+        let large = estimate_batch_memory(&make_batch(10_000));
+
+        assert!(small > 0);
+        assert!(large > small);
+    }
+
+    #[test]
+    fn test_geometry_crs() {
+        let field = |metadata: &str| {
+            Field::new("geometry", DataType::Binary, false).with_metadata(HashMap::from([(
+                EXTENSION_METADATA_KEY.to_string(),
+                metadata.to_string(),
+            )]))
+        };
+
+        assert_eq!(
+            geometry_crs(&field(r#"{"crs":"EPSG:4326","crs_type":"authority_code"}"#)),
+            Some(Crs::Wgs84)
+        );
+        assert_eq!(
+            geometry_crs(&field(r#"{"crs":"OGC:CRS84"}"#)),
+            Some(Crs::Wgs84)
+        );
+        assert_eq!(
+            geometry_crs(&field(r#"{"crs":"urn:ogc:def:crs:EPSG::27700"}"#)),
+            Some(Crs::Bng)
+        );
+        assert_eq!(
+            geometry_crs(&field(
+                r#"{"crs":{"name":"OSGB36","id":{"authority":"EPSG","code":27700}}}"#
+            )),
+            Some(Crs::Bng)
+        );
+
+        // Other CRSs, and codes that merely contain 4326, are not WGS84
+        assert_eq!(geometry_crs(&field(r#"{"crs":"EPSG:43260"}"#)), None);
+        assert_eq!(geometry_crs(&field(r#"{"crs":"EPSG:3857"}"#)), None);
+        assert_eq!(geometry_crs(&field(r#"{"edges":"4326"}"#)), None);
+        assert_eq!(
+            geometry_crs(&Field::new("geometry", DataType::Binary, false)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_would_fit() {
+        let batch = make_batch(100);
+        let size = estimate_batch_memory(&batch);
+
+        assert!(would_fit(&batch, size));
+        assert!(!would_fit(&batch, size - 1));
+    }
+
+    #[test]
+    fn test_geometry_column_index_none_without_geometry() {
+        assert_eq!(geometry_column_index(&make_batch(3)), None);
+    }
+
+    #[test]
+    fn test_geometry_column_index_not_last() {
+        let geom_field = Field::new("shape", DataType::Binary, false).with_metadata(HashMap::from(
+            [(EXTENSION_NAME_KEY.to_string(), "geoarrow.wkb".to_string())],
+        ));
+        let schema = Schema::new(vec![
+            Field::new("hex_id", DataType::Utf8, false),
+            geom_field,
+            Field::new("pipe_count", DataType::UInt32, false),
+        ]);
+        let batch = RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec!["a"])),
+                Arc::new(BinaryArray::from(vec![&b"\x01"[..]])),
+                Arc::new(UInt32Array::from(vec![1])),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(geometry_column_index(&batch), Some(1));
+    }
+}
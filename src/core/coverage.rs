@@ -0,0 +1,181 @@
+use arrow_array::{RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use geo_types::MultiPolygon;
+use geoarrow_array::IntoArrow;
+use n3gb_rs::{HexCell, HexGrid};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::error::InfraHexError;
+
+use super::arrow::{ColumnNames, HexSummaryOptions, build_polygon_geometry};
+
+/// Lists the hexes covering `boundary` that hold no pipes in `summary`.
+///
+/// Every cell of the boundary's grid at `zoom` is enumerated, and those
+/// whose id appears in the summary's `hex_id` column are removed. The result
+/// has a `hex_id` column and a BNG polygon `geometry` column, one row per
+/// empty cell, sorted by id, highlighting under-served parts of a region.
+///
+/// The summary must have been computed at the same `zoom`, ideally against
+/// the same boundary: ids from another zoom never match, so every cell would
+/// be reported as a gap. A summary at a different zoom is therefore rejected
+/// with `InfraHexError::Config`.
+pub fn coverage_gaps(
+    summary: &RecordBatch,
+    boundary: &MultiPolygon<f64>,
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    coverage_gaps_with_columns(summary, boundary, zoom, &ColumnNames::default())
+}
+
+/// Like [`coverage_gaps`], reading the summary's hex id column and naming
+/// the result's `hex_id` and `geometry` columns from `names`.
+pub fn coverage_gaps_with_columns(
+    summary: &RecordBatch,
+    boundary: &MultiPolygon<f64>,
+    zoom: u8,
+    names: &ColumnNames,
+) -> Result<RecordBatch, InfraHexError> {
+    let hex_ids = names.hex_ids(summary)?;
+
+    if let Some(id) = hex_ids.iter().flatten().next() {
+        let summary_zoom = HexCell::from_hex_id(id)?.zoom_level;
+        if summary_zoom != zoom {
+            return Err(InfraHexError::Config(format!(
+                "Summary is at zoom {}, but coverage was requested at zoom {}",
+                summary_zoom, zoom
+            )));
+        }
+    }
+
+    let occupied: HashSet<&str> = hex_ids.iter().flatten().collect();
+    let grid = HexGrid::from_wgs84_multipolygon(boundary, zoom)?;
+
+    let mut gaps: Vec<&HexCell> = grid
+        .cells()
+        .iter()
+        .filter(|c| !occupied.contains(c.id.as_str()))
+        .collect();
+    gaps.sort_by(|a, b| a.id.cmp(&b.id));
+    gaps.dedup_by(|a, b| a.id == b.id);
+
+    let ids: StringArray = gaps.iter().map(|c| Some(c.id.as_str())).collect();
+    let options = HexSummaryOptions::new().with_column_names(names.clone());
+    let (geometry, geometry_field) = build_polygon_geometry(&gaps, &options)?;
+
+    let schema = Schema::new(vec![
+        Field::new(&names.hex_id, DataType::Utf8, false),
+        geometry_field,
+    ]);
+    RecordBatch::try_new(
+        Arc::new(schema),
+        vec![Arc::new(ids), Arc::new(geometry.into_arrow())],
+    )
+    .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::{
+        to_hex_summary_for_multipolygon, to_hex_summary_no_geom, to_hex_summary_with_options,
+    };
+    use geo_types::{LineString, Polygon};
+
+    fn make_record(coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            asset_id: Some("GAP-001".to_string()),
+            ..line_record(coords)
+        }
+    }
+
+    fn make_boundary() -> MultiPolygon<f64> {
+        MultiPolygon::new(vec![Polygon::new(
+            LineString::from(vec![
+                (-2.2601, 53.4701),
+                (-2.2201, 53.4701),
+                (-2.2201, 53.4901),
+                (-2.2601, 53.4901),
+                (-2.2601, 53.4701),
+            ]),
+            vec![],
+        )])
+    }
+
+    fn ids(batch: &RecordBatch) -> HashSet<String> {
+        ids_in(batch, "hex_id")
+    }
+
+    fn ids_in(batch: &RecordBatch, column: &str) -> HashSet<String> {
+        batch
+            .column_by_name(column)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap()
+            .iter()
+            .map(|id| id.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_coverage_gaps_excludes_occupied_cells() {
+        let boundary = make_boundary();
+        let records = vec![make_record(vec![
+            vec![-2.2400, 53.4800],
+            vec![-2.2401, 53.4800],
+        ])];
+        let summary = to_hex_summary_for_multipolygon(&records, 10, &boundary).unwrap();
+        let occupied = ids(&summary);
+        assert!(!occupied.is_empty());
+
+        let gaps = coverage_gaps(&summary, &boundary, 10).unwrap();
+        let gap_ids = ids(&gaps);
+        let all: HashSet<String> = HexGrid::from_wgs84_multipolygon(&boundary, 10)
+            .unwrap()
+            .cells()
+            .iter()
+            .map(|c| c.id.clone())
+            .collect();
+
+        assert!(gap_ids.is_disjoint(&occupied));
+        assert_eq!(gap_ids, &all - &occupied);
+        assert!(gaps.column_by_name("geometry").is_some());
+    }
+
+    #[test]
+    fn test_coverage_gaps_rejects_zoom_mismatch() {
+        let records = vec![make_record(vec![
+            vec![-2.2400, 53.4800],
+            vec![-2.2401, 53.4800],
+        ])];
+        let summary = to_hex_summary_no_geom(&records, 12).unwrap();
+
+        let result = coverage_gaps(&summary, &make_boundary(), 10);
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_coverage_gaps_with_renamed_columns() {
+        let boundary = make_boundary();
+        let records = vec![make_record(vec![
+            vec![-2.2400, 53.4800],
+            vec![-2.2401, 53.4800],
+        ])];
+        let names = ColumnNames::default()
+            .with_hex_id("cell")
+            .with_geometry("geom");
+        let options = HexSummaryOptions::new().with_column_names(names.clone());
+        let summary = to_hex_summary_with_options(&records, 10, &boundary, &options).unwrap();
+
+        let gaps = coverage_gaps_with_columns(&summary, &boundary, 10, &names).unwrap();
+
+        let occupied = ids_in(&summary, "cell");
+        assert!(ids_in(&gaps, "cell").is_disjoint(&occupied));
+        assert!(gaps.column_by_name("geom").is_some());
+        assert!(coverage_gaps(&summary, &boundary, 10).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use geo_types::{LineString, MultiPolygon, Polygon};
+use n3gb_rs::HexCell;
+
+/// SRID for British National Grid (EPSG:27700), the CRS of hex cell polygons.
+pub const BNG_SRID: u32 = 27700;
+
+/// WKB geometry type codes.
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOLYGON: u32 = 6;
+
+/// EWKB flag marking that an SRID follows the geometry type.
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// Little-endian (NDR) byte order marker.
+const LITTLE_ENDIAN: u8 = 1;
+
+// =============================================================================
+// ToEwkb Trait - Encode geo_types as PostGIS Extended WKB
+// =============================================================================
+
+/// Trait for encoding geometries as EWKB (WKB with an embedded SRID).
+///
+/// Unlike the plain WKB written by `write_geoparquet`, EWKB carries the SRID
+/// so it can be loaded straight into a PostGIS geometry column without an
+/// `ST_SetSRID` step. Output is always little-endian.
+pub trait ToEwkb {
+    /// Encodes this geometry as EWKB tagged with `srid`.
+    fn to_ewkb(&self, srid: u32) -> Vec<u8>;
+}
+
+impl ToEwkb for Polygon<f64> {
+    fn to_ewkb(&self, srid: u32) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_header(&mut buf, WKB_POLYGON, Some(srid));
+        write_polygon_body(&mut buf, self);
+        buf
+    }
+}
+
+impl ToEwkb for MultiPolygon<f64> {
+    fn to_ewkb(&self, srid: u32) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_header(&mut buf, WKB_MULTIPOLYGON, Some(srid));
+        buf.extend_from_slice(&(self.0.len() as u32).to_le_bytes());
+        // Member polygons are plain WKB; only the outer geometry carries the SRID
+        for polygon in &self.0 {
+            write_header(&mut buf, WKB_POLYGON, None);
+            write_polygon_body(&mut buf, polygon);
+        }
+        buf
+    }
+}
+
+/// Hex cell polygons are in BNG, so callers should normally pass [`BNG_SRID`].
+impl ToEwkb for HexCell {
+    fn to_ewkb(&self, srid: u32) -> Vec<u8> {
+        self.to_polygon().to_ewkb(srid)
+    }
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+fn write_header(buf: &mut Vec<u8>, geometry_type: u32, srid: Option<u32>) {
+    buf.push(LITTLE_ENDIAN);
+    match srid {
+        Some(srid) => {
+            buf.extend_from_slice(&(geometry_type | EWKB_SRID_FLAG).to_le_bytes());
+            buf.extend_from_slice(&srid.to_le_bytes());
+        }
+        None => buf.extend_from_slice(&geometry_type.to_le_bytes()),
+    }
+}
+
+fn write_polygon_body(buf: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    let ring_count = 1 + polygon.interiors().len();
+    buf.extend_from_slice(&(ring_count as u32).to_le_bytes());
+    write_ring(buf, polygon.exterior());
+    for interior in polygon.interiors() {
+        write_ring(buf, interior);
+    }
+}
+
+fn write_ring(buf: &mut Vec<u8>, ring: &LineString<f64>) {
+    buf.extend_from_slice(&(ring.0.len() as u32).to_le_bytes());
+    for coord in &ring.0 {
+        buf.extend_from_slice(&coord.x.to_le_bytes());
+        buf.extend_from_slice(&coord.y.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo_types::Coord;
+
+    fn square() -> Polygon<f64> {
+        Polygon::new(
+            LineString::new(vec![
+                Coord { x: 0.0, y: 0.0 },
+                Coord { x: 1.0, y: 0.0 },
+                Coord { x: 1.0, y: 1.0 },
+                Coord { x: 0.0, y: 0.0 },
+            ]),
+            vec![],
+        )
+    }
+
+    fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_polygon_ewkb_has_srid() {
+        let ewkb = square().to_ewkb(BNG_SRID);
+
+        assert_eq!(ewkb[0], LITTLE_ENDIAN);
+        assert_eq!(read_u32(&ewkb, 1), WKB_POLYGON | EWKB_SRID_FLAG);
+        assert_eq!(read_u32(&ewkb, 5), 27700);
+        // One ring of four points
+        assert_eq!(read_u32(&ewkb, 9), 1);
+        assert_eq!(read_u32(&ewkb, 13), 4);
+        // header (1 + 4 + 4) + ring count (4) + point count (4) + 4 points * 16
+        assert_eq!(ewkb.len(), 9 + 4 + 4 + 4 * 16);
+    }
+
+    #[test]
+    fn test_multipolygon_ewkb_members_have_no_srid() {
+        let mp = MultiPolygon::new(vec![square(), square()]);
+        let ewkb = mp.to_ewkb(BNG_SRID);
+
+        assert_eq!(read_u32(&ewkb, 1), WKB_MULTIPOLYGON | EWKB_SRID_FLAG);
+        assert_eq!(read_u32(&ewkb, 5), 27700);
+        assert_eq!(read_u32(&ewkb, 9), 2);
+        // First member polygon header
+        assert_eq!(ewkb[13], LITTLE_ENDIAN);
+        assert_eq!(read_u32(&ewkb, 14), WKB_POLYGON);
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+use crate::client::PipelineData;
+
+use super::length::measured_length_m;
+use super::reproject::Crs;
+
+/// Keeps only records whose above-ground flag matches `above_ground`.
+///
+/// Records with an unknown above-ground status are excluded either way, so
+/// `filter_by_ground(r, true)` and `filter_by_ground(r, false)` together may
+/// not cover every input record.
+pub fn filter_by_ground<T: PipelineData>(records: &[T], above_ground: bool) -> Vec<&T> {
+    records
+        .iter()
+        .filter(|r| r.is_above_ground() == Some(above_ground))
+        .collect()
+}
+
+/// Keeps only records whose pipe type is one of `pipe_types`, e.g. `&["HP"]`.
+///
+/// Codes are compared case-insensitively, ignoring surrounding whitespace.
+/// Records with no pipe type are excluded.
+pub fn filter_by_pipe_type<'a, T: PipelineData>(
+    records: &'a [T],
+    pipe_types: &[&str],
+) -> Vec<&'a T> {
+    records
+        .iter()
+        .filter(|r| matches_any(r.pipe_type(), pipe_types))
+        .collect()
+}
+
+/// Keeps only records whose material code is one of `materials`, e.g.
+/// `&["ST", "CI"]`.
+///
+/// Codes are compared as in [`filter_by_pipe_type`]. Records with no
+/// material are excluded.
+pub fn filter_by_material<'a, T: PipelineData>(records: &'a [T], materials: &[&str]) -> Vec<&'a T> {
+    records
+        .iter()
+        .filter(|r| matches_any(r.material(), materials))
+        .collect()
+}
+
+/// Keeps only records whose pipe diameter is at least `min_diameter_mm`
+/// millimetres.
+///
+/// Diameters are normalised to millimetres from their recorded unit (see
+/// [`PipelineData::diameter_mm`]), so a 6 inch main passes a 150 mm
+/// threshold. Records with a missing diameter or unrecognised unit are
+/// excluded.
+pub fn filter_by_min_diameter<T: PipelineData>(records: &[T], min_diameter_mm: f64) -> Vec<&T> {
+    records
+        .iter()
+        .filter(|r| r.diameter_mm().is_some_and(|d| d >= min_diameter_mm))
+        .collect()
+}
+
+/// Returns true if `value` matches one of `codes`, ignoring case and
+/// surrounding whitespace.
+fn matches_any(value: Option<&str>, codes: &[&str]) -> bool {
+    value.is_some_and(|v| {
+        codes
+            .iter()
+            .any(|c| c.trim().eq_ignore_ascii_case(v.trim()))
+    })
+}
+
+/// Keeps only records whose pipe is at least `min_length_m` metres long.
+///
+/// Useful for dropping short service stubs that add noise to density maps.
+/// Lengths are measured in British National Grid, reprojecting from `crs` as
+/// needed. Records with no measurable line (missing, empty or non-linear
+/// geometry) are kept when `keep_unmeasurable` is set and dropped otherwise.
+pub fn filter_records_by_min_length<T: PipelineData>(
+    records: &[T],
+    min_length_m: f64,
+    crs: Crs,
+    keep_unmeasurable: bool,
+) -> Vec<&T> {
+    records
+        .iter()
+        .filter(|r| match measured_length_m(*r, crs) {
+            Some(length) => length >= min_length_m,
+            None => keep_unmeasurable,
+        })
+        .collect()
+}
+
+/// Removes records whose `asset_id` was already seen, keeping the first.
+///
+/// Fetching an area as several overlapping tiles and `extend`-ing the
+/// results returns pipes on the seams more than once, which inflates hex
+/// counts; run this on the combined records before aggregating. Records
+/// without an asset id are all kept; see [`dedup_by_geometry`] for those.
+pub fn dedup_by_asset_id<T: PipelineData>(records: &mut Vec<T>) {
+    let mut seen = HashSet::new();
+    records.retain(|r| match r.asset_id() {
+        Some(id) => seen.insert(id.to_string()),
+        None => true,
+    });
+}
+
+/// Removes records whose geometry exactly matches an earlier record's,
+/// keeping the first.
+///
+/// For sources without asset ids, where the same pipe fetched twice comes
+/// back with identical coordinates. Records without geometry are all kept.
+pub fn dedup_by_geometry<T: PipelineData>(records: &mut Vec<T>) {
+    let mut seen = HashSet::new();
+    records.retain(|r| match &r.geo_shape().geometry {
+        Some(geometry) => seen.insert(geometry.value.to_string()),
+        None => true,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::{empty_record, line_record};
+
+    fn make_record(ag_ind: Option<&str>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            ag_ind: ag_ind.map(String::from),
+            ..empty_record()
+        }
+    }
+
+    #[test]
+    fn test_filter_by_ground() {
+        let records = vec![
+            make_record(Some("Y")),
+            make_record(Some("N")),
+            make_record(Some("N")),
+            make_record(None),
+        ];
+
+        assert_eq!(filter_by_ground(&records, true).len(), 1);
+        assert_eq!(filter_by_ground(&records, false).len(), 2);
+    }
+
+    #[test]
+    fn test_filter_records_by_min_length() {
+        // Roughly 111 m and 11 m north-south lines
+        let main = line_record(vec![vec![-2.248, 53.480], vec![-2.248, 53.481]]);
+        let stub = line_record(vec![vec![-2.248, 53.480], vec![-2.248, 53.4801]]);
+        let empty = make_record(None);
+        let records = vec![main, stub, empty];
+
+        let kept = filter_records_by_min_length(&records, 50.0, Crs::Wgs84, false);
+        assert_eq!(kept.len(), 1);
+        assert!(std::ptr::eq(kept[0], &records[0]));
+
+        let kept = filter_records_by_min_length(&records, 50.0, Crs::Wgs84, true);
+        assert_eq!(kept.len(), 2);
+        assert!(std::ptr::eq(kept[1], &records[2]));
+    }
+
+    fn make_pipe(
+        pipe_type: &str,
+        material: &str,
+        diameter: f64,
+        unit: &str,
+    ) -> CadentPipelineRecord {
+        let mut record = make_record(None);
+        record.pipe_type = Some(pipe_type.to_string());
+        record.material = Some(material.to_string());
+        record.diameter = Some(diameter);
+        record.diam_unit = Some(unit.to_string());
+        record
+    }
+
+    #[test]
+    fn test_filter_by_pipe_type_and_material() {
+        let records = vec![
+            make_pipe("HP", "ST", 300.0, "mm"),
+            make_pipe("lp", "PE", 90.0, "mm"),
+            make_pipe("MP", "st ", 180.0, "mm"),
+            make_record(None),
+        ];
+
+        let high = filter_by_pipe_type(&records, &["HP", "LP"]);
+        assert_eq!(high.len(), 2);
+        assert!(std::ptr::eq(high[1], &records[1]));
+
+        let steel = filter_by_material(&records, &["ST"]);
+        assert_eq!(steel.len(), 2);
+        assert!(std::ptr::eq(steel[1], &records[2]));
+
+        assert!(filter_by_material(&records, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_filter_by_min_diameter_normalises_units() {
+        let records = vec![
+            make_pipe("MP", "ST", 6.0, "in"),
+            make_pipe("MP", "ST", 125.0, "mm"),
+            make_pipe("MP", "ST", 0.2, "m"),
+            make_pipe("MP", "ST", 500.0, "furlong"),
+            make_record(None),
+        ];
+
+        let kept = filter_by_min_diameter(&records, 150.0);
+        assert_eq!(kept.len(), 2);
+        assert!(std::ptr::eq(kept[0], &records[0]));
+        assert!(std::ptr::eq(kept[1], &records[2]));
+    }
+
+    #[test]
+    fn test_dedup_by_asset_id() {
+        let with_id = |id: Option<&str>| {
+            let mut record = make_record(None);
+            record.asset_id = id.map(String::from);
+            record
+        };
+        let mut records = vec![
+            with_id(Some("A")),
+            with_id(Some("B")),
+            with_id(Some("A")),
+            with_id(None),
+            with_id(None),
+        ];
+
+        dedup_by_asset_id(&mut records);
+
+        let ids: Vec<Option<&str>> = records.iter().map(|r| r.asset_id()).collect();
+        assert_eq!(ids, vec![Some("A"), Some("B"), None, None]);
+    }
+
+    #[test]
+    fn test_dedup_by_geometry() {
+        let line = vec![vec![-2.248, 53.480], vec![-2.248, 53.481]];
+        let mut records = vec![
+            line_record(line.clone()),
+            line_record(vec![vec![-2.248, 53.480], vec![-2.249, 53.481]]),
+            line_record(line),
+            make_record(None),
+            make_record(None),
+        ];
+
+        dedup_by_geometry(&mut records);
+
+        assert_eq!(records.len(), 4);
+        assert!(records[2].geo_shape.geometry.is_none());
+    }
+
+    #[test]
+    fn test_filter_records_by_min_length_bng() {
+        let records = vec![
+            line_record(vec![vec![383_000.0, 398_000.0], vec![383_100.0, 398_000.0]]),
+            line_record(vec![vec![383_000.0, 398_000.0], vec![383_005.0, 398_000.0]]),
+        ];
+
+        let kept = filter_records_by_min_length(&records, 50.0, Crs::Bng, false);
+        assert_eq!(kept.len(), 1);
+        assert!(std::ptr::eq(kept[0], &records[0]));
+    }
+}
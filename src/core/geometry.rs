@@ -1,18 +1,134 @@
-use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+use geo::Winding;
+use geo::orient::{Direction, Orient};
+use geo::winding_order::WindingOrder;
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
 use geojson::{Geometry as GeoJsonGeometry, Value as GeoJsonValue};
 
 use crate::error::InfraHexError;
 
+// =============================================================================
+// Ring Orientation
+// =============================================================================
+
+/// Winding order applied to polygon rings before output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RingOrientation {
+    /// Exterior rings counter-clockwise, holes clockwise: the GeoJSON
+    /// (RFC 7946) right-hand rule and the OGC convention.
+    #[default]
+    CounterClockwise,
+    /// Exterior rings clockwise, holes counter-clockwise, for consumers
+    /// expecting the reverse (e.g. ESRI shapefiles).
+    Clockwise,
+}
+
+/// Returns a copy of `polygon` with its rings wound according to
+/// `orientation`.
+///
+/// Zero-area rings have no winding and are left unchanged.
+pub fn normalise_winding(polygon: &Polygon<f64>, orientation: RingOrientation) -> Polygon<f64> {
+    polygon.orient(match orientation {
+        RingOrientation::CounterClockwise => Direction::Default,
+        RingOrientation::Clockwise => Direction::Reversed,
+    })
+}
+
+// =============================================================================
+// Z Coordinates
+// =============================================================================
+
+/// A LineString carrying the elevation (GeoJSON's optional third position
+/// element) of each vertex alongside it.
+///
+/// geo_types coordinates are 2D, so the plain `LineString`, `Point` and
+/// polygon conversions drop Z. Only `LineStringZ` and [`PointZ`] keep it;
+/// parse into these when a dataset's elevations matter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineStringZ {
+    pub line: LineString<f64>,
+    /// One entry per vertex of `line`; `None` where a position had no Z.
+    pub z: Vec<Option<f64>>,
+}
+
+impl LineStringZ {
+    /// Returns true if any vertex has an elevation.
+    pub fn has_z(&self) -> bool {
+        self.z.iter().any(Option::is_some)
+    }
+}
+
+/// A Point with its elevation, if the GeoJSON position had one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointZ {
+    pub point: Point<f64>,
+    pub z: Option<f64>,
+}
+
 // =============================================================================
 // ToGeoJson Trait - Convert geo_types to GeoJSON
 // =============================================================================
 
 /// Trait for converting geo_types geometries to GeoJSON.
+///
+/// Positions are written `[x, y]`, i.e. `[lon, lat]` for WGS84. Polygon
+/// rings are rewound to follow the RFC 7946 right-hand rule (exterior
+/// counter-clockwise, holes clockwise).
 pub trait ToGeoJson {
     /// Converts this geometry to a GeoJSON Geometry.
     fn to_geojson(&self) -> GeoJsonGeometry;
 }
 
+impl ToGeoJson for Point<f64> {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        GeoJsonGeometry::new(GeoJsonValue::Point(vec![self.x(), self.y()]))
+    }
+}
+
+impl ToGeoJson for MultiPoint<f64> {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        let positions = self.0.iter().map(|p| vec![p.x(), p.y()]).collect();
+        GeoJsonGeometry::new(GeoJsonValue::MultiPoint(positions))
+    }
+}
+
+impl ToGeoJson for LineString<f64> {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        GeoJsonGeometry::new(GeoJsonValue::LineString(linestring_to_coords(self)))
+    }
+}
+
+impl ToGeoJson for MultiLineString<f64> {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        let lines = self.0.iter().map(linestring_to_coords).collect();
+        GeoJsonGeometry::new(GeoJsonValue::MultiLineString(lines))
+    }
+}
+
+impl ToGeoJson for LineStringZ {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        let coords = self
+            .line
+            .coords()
+            .zip(self.z.iter().chain(std::iter::repeat(&None)))
+            .map(|(c, z)| position_z(c.x, c.y, *z))
+            .collect();
+        GeoJsonGeometry::new(GeoJsonValue::LineString(coords))
+    }
+}
+
+impl ToGeoJson for PointZ {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        GeoJsonGeometry::new(GeoJsonValue::Point(position_z(
+            self.point.x(),
+            self.point.y(),
+            self.z,
+        )))
+    }
+}
+
 impl ToGeoJson for Polygon<f64> {
     fn to_geojson(&self) -> GeoJsonGeometry {
         let rings = polygon_to_rings(self);
@@ -27,19 +143,50 @@ impl ToGeoJson for MultiPolygon<f64> {
     }
 }
 
+impl ToGeoJson for Geometry<f64> {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        match self {
+            Geometry::Point(g) => g.to_geojson(),
+            Geometry::Line(g) => LineString::from(vec![g.start, g.end]).to_geojson(),
+            Geometry::LineString(g) => g.to_geojson(),
+            Geometry::Polygon(g) => g.to_geojson(),
+            Geometry::MultiPoint(g) => g.to_geojson(),
+            Geometry::MultiLineString(g) => g.to_geojson(),
+            Geometry::MultiPolygon(g) => g.to_geojson(),
+            Geometry::GeometryCollection(g) => g.to_geojson(),
+            Geometry::Rect(g) => g.to_polygon().to_geojson(),
+            Geometry::Triangle(g) => g.to_polygon().to_geojson(),
+        }
+    }
+}
+
+impl ToGeoJson for GeometryCollection<f64> {
+    fn to_geojson(&self) -> GeoJsonGeometry {
+        let members = self.0.iter().map(ToGeoJson::to_geojson).collect();
+        GeoJsonGeometry::new(GeoJsonValue::GeometryCollection(members))
+    }
+}
+
+/// Builds a GeoJSON position, appending `z` when present.
+fn position_z(x: f64, y: f64, z: Option<f64>) -> Vec<f64> {
+    match z {
+        Some(z) => vec![x, y, z],
+        None => vec![x, y],
+    }
+}
+
+/// Helper to convert a line's coordinates to GeoJSON positions.
+fn linestring_to_coords(line: &LineString<f64>) -> Vec<Vec<f64>> {
+    line.coords().map(|c| vec![c.x, c.y]).collect()
+}
+
 /// Helper to convert a polygon's rings to GeoJSON coordinate format.
 fn polygon_to_rings(polygon: &Polygon<f64>) -> Vec<Vec<Vec<f64>>> {
-    let exterior: Vec<Vec<f64>> = polygon
-        .exterior()
-        .coords()
-        .map(|c| vec![c.x, c.y])
-        .collect();
-
-    let mut rings = vec![exterior];
+    let polygon = &normalise_winding(polygon, RingOrientation::CounterClockwise);
+    let mut rings = vec![linestring_to_coords(polygon.exterior())];
 
     for interior in polygon.interiors() {
-        let ring: Vec<Vec<f64>> = interior.coords().map(|c| vec![c.x, c.y]).collect();
-        rings.push(ring);
+        rings.push(linestring_to_coords(interior));
     }
 
     rings
@@ -50,9 +197,20 @@ fn polygon_to_rings(polygon: &Polygon<f64>) -> Vec<Vec<Vec<f64>>> {
 // =============================================================================
 
 /// Trait for parsing GeoJSON geometries into geo_types.
+///
+/// `from_geojson` repairs polygon rings: unclosed rings are closed and
+/// rings wound against the RFC 7946 right-hand rule are reversed.
+/// `from_geojson_validated` rejects such input instead.
 pub trait FromGeoJson: Sized {
     /// The GeoJSON value types this type can be parsed from.
     fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError>;
+
+    /// Like [`FromGeoJson::from_geojson`], but returns
+    /// `InfraHexError::Geometry` for unclosed or wrongly wound polygon rings
+    /// rather than repairing them.
+    fn from_geojson_validated(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        Self::from_geojson(geometry)
+    }
 }
 
 impl FromGeoJson for LineString<f64> {
@@ -75,44 +233,165 @@ impl FromGeoJson for LineString<f64> {
     }
 }
 
-impl FromGeoJson for Polygon<f64> {
+impl FromGeoJson for MultiLineString<f64> {
     fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
         match &geometry.value {
-            GeoJsonValue::Polygon(rings) => rings_to_polygon(rings),
+            GeoJsonValue::LineString(coords) => {
+                Ok(MultiLineString::new(vec![coords_to_linestring(coords)]))
+            }
+            GeoJsonValue::MultiLineString(lines) => Ok(MultiLineString::new(
+                lines
+                    .iter()
+                    .map(|line| coords_to_linestring(line))
+                    .collect(),
+            )),
             other => Err(InfraHexError::Geometry(format!(
-                "Expected Polygon, got {:?}",
+                "Expected LineString or MultiLineString, got {:?}",
                 other
             ))),
         }
     }
 }
 
-impl FromGeoJson for MultiPolygon<f64> {
+impl FromGeoJson for LineStringZ {
     fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
-        match &geometry.value {
-            GeoJsonValue::Polygon(rings) => {
-                let polygon = rings_to_polygon(rings)?;
-                Ok(MultiPolygon::new(vec![polygon]))
+        let positions: Vec<&Vec<f64>> = match &geometry.value {
+            GeoJsonValue::LineString(coords) => coords.iter().collect(),
+            // Flattened into one line, as for LineString
+            GeoJsonValue::MultiLineString(lines) => lines.iter().flatten().collect(),
+            other => {
+                return Err(InfraHexError::Geometry(format!(
+                    "Expected LineString or MultiLineString, got {:?}",
+                    other
+                )));
             }
-            GeoJsonValue::MultiPolygon(polygons) => {
-                let mut result = Vec::with_capacity(polygons.len());
-                for rings in polygons {
-                    result.push(rings_to_polygon(rings)?);
-                }
-                Ok(MultiPolygon::new(result))
+        };
+
+        let (coords, z) = positions
+            .into_iter()
+            .filter(|c| c.len() >= 2)
+            .map(|c| (Coord { x: c[0], y: c[1] }, c.get(2).copied()))
+            .unzip();
+        Ok(LineStringZ {
+            line: LineString::new(coords),
+            z,
+        })
+    }
+}
+
+impl FromGeoJson for PointZ {
+    fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        let point = Point::from_geojson(geometry)?;
+        let z = match &geometry.value {
+            GeoJsonValue::Point(position) => position.get(2).copied(),
+            _ => None,
+        };
+        Ok(PointZ { point, z })
+    }
+}
+
+impl FromGeoJson for Point<f64> {
+    fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        match &geometry.value {
+            GeoJsonValue::Point(position) => position_to_point(position),
+            other => Err(InfraHexError::Geometry(format!(
+                "Expected Point, got {:?}",
+                other
+            ))),
+        }
+    }
+}
+
+impl FromGeoJson for MultiPoint<f64> {
+    fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        match &geometry.value {
+            GeoJsonValue::Point(position) => {
+                Ok(MultiPoint::new(vec![position_to_point(position)?]))
             }
+            GeoJsonValue::MultiPoint(positions) => positions
+                .iter()
+                .map(|p| position_to_point(p))
+                .collect::<Result<Vec<_>, _>>()
+                .map(MultiPoint::new),
             other => Err(InfraHexError::Geometry(format!(
-                "Expected Polygon or MultiPolygon, got {:?}",
+                "Expected Point or MultiPoint, got {:?}",
                 other
             ))),
         }
     }
 }
 
+impl FromGeoJson for Polygon<f64> {
+    fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        polygon_from_geojson(geometry, false)
+    }
+
+    fn from_geojson_validated(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        polygon_from_geojson(geometry, true)
+    }
+}
+
+impl FromGeoJson for MultiPolygon<f64> {
+    fn from_geojson(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        multipolygon_from_geojson(geometry, false)
+    }
+
+    fn from_geojson_validated(geometry: &GeoJsonGeometry) -> Result<Self, InfraHexError> {
+        multipolygon_from_geojson(geometry, true)
+    }
+}
+
+fn polygon_from_geojson(
+    geometry: &GeoJsonGeometry,
+    strict: bool,
+) -> Result<Polygon<f64>, InfraHexError> {
+    match &geometry.value {
+        GeoJsonValue::Polygon(rings) => rings_to_polygon(rings, strict),
+        other => Err(InfraHexError::Geometry(format!(
+            "Expected Polygon, got {:?}",
+            other
+        ))),
+    }
+}
+
+fn multipolygon_from_geojson(
+    geometry: &GeoJsonGeometry,
+    strict: bool,
+) -> Result<MultiPolygon<f64>, InfraHexError> {
+    match &geometry.value {
+        GeoJsonValue::Polygon(rings) => {
+            let polygon = rings_to_polygon(rings, strict)?;
+            Ok(MultiPolygon::new(vec![polygon]))
+        }
+        GeoJsonValue::MultiPolygon(polygons) => {
+            let mut result = Vec::with_capacity(polygons.len());
+            for rings in polygons {
+                result.push(rings_to_polygon(rings, strict)?);
+            }
+            Ok(MultiPolygon::new(result))
+        }
+        other => Err(InfraHexError::Geometry(format!(
+            "Expected Polygon or MultiPolygon, got {:?}",
+            other
+        ))),
+    }
+}
+
 // =============================================================================
 // Helper Functions
 // =============================================================================
 
+/// Converts a GeoJSON position to a Point, ignoring any Z value.
+fn position_to_point(position: &[f64]) -> Result<Point<f64>, InfraHexError> {
+    match position {
+        [x, y, ..] => Ok(Point::new(*x, *y)),
+        _ => Err(InfraHexError::Geometry(format!(
+            "Point needs at least 2 coordinates, got {}",
+            position.len()
+        ))),
+    }
+}
+
 /// Converts GeoJSON coordinate array to a LineString.
 fn coords_to_linestring(coords: &[Vec<f64>]) -> LineString<f64> {
     let points: Vec<Coord<f64>> = coords
@@ -129,7 +408,11 @@ fn coords_to_linestring(coords: &[Vec<f64>]) -> LineString<f64> {
 }
 
 /// Converts GeoJSON polygon rings to a geo_types Polygon.
-fn rings_to_polygon(rings: &[Vec<Vec<f64>>]) -> Result<Polygon<f64>, InfraHexError> {
+///
+/// Each ring is checked for closure and winding (exterior counter-clockwise,
+/// holes clockwise). With `strict` a bad ring is an error; otherwise it is
+/// closed and rewound.
+fn rings_to_polygon(rings: &[Vec<Vec<f64>>], strict: bool) -> Result<Polygon<f64>, InfraHexError> {
     if rings.is_empty() {
         return Err(InfraHexError::Geometry("No rings in polygon".to_string()));
     }
@@ -140,16 +423,53 @@ fn rings_to_polygon(rings: &[Vec<Vec<f64>>]) -> Result<Polygon<f64>, InfraHexErr
             "No valid coordinates in exterior ring".to_string(),
         ));
     }
+    let exterior = validate_ring(exterior, 0, WindingOrder::CounterClockwise, strict)?;
 
-    let holes: Vec<LineString<f64>> = rings[1..]
-        .iter()
-        .map(|ring| coords_to_linestring(ring))
-        .filter(|ls| !ls.0.is_empty())
-        .collect();
+    let mut holes = Vec::with_capacity(rings.len() - 1);
+    for (i, ring) in rings.iter().enumerate().skip(1) {
+        let hole = coords_to_linestring(ring);
+        if !hole.0.is_empty() {
+            holes.push(validate_ring(hole, i, WindingOrder::Clockwise, strict)?);
+        }
+    }
 
     Ok(Polygon::new(exterior, holes))
 }
 
+/// Closes `ring` and winds it in `expected` order, or errors under `strict`.
+///
+/// Zero-area rings have no winding and are accepted as-is.
+fn validate_ring(
+    mut ring: LineString<f64>,
+    index: usize,
+    expected: WindingOrder,
+    strict: bool,
+) -> Result<LineString<f64>, InfraHexError> {
+    if !ring.is_closed() {
+        if strict {
+            return Err(InfraHexError::Geometry(format!(
+                "Ring {} is not closed: first and last coordinates differ",
+                index
+            )));
+        }
+        ring.close();
+    }
+
+    if let Some(order) = ring.winding_order()
+        && order != expected
+    {
+        if strict {
+            return Err(InfraHexError::Geometry(format!(
+                "Ring {} is wound {:?}, expected {:?}",
+                index, order, expected
+            )));
+        }
+        ring.0.reverse();
+    }
+
+    Ok(ring)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -304,6 +624,223 @@ mod tests {
         assert_eq!(parsed.interiors()[0].0.len(), 5);
     }
 
+    #[test]
+    fn test_normalise_winding() {
+        use geo::Winding;
+
+        // Clockwise exterior with a counter-clockwise hole
+        let poly = Polygon::new(
+            LineString::from(vec![
+                (0.0, 0.0),
+                (0.0, 10.0),
+                (10.0, 10.0),
+                (10.0, 0.0),
+                (0.0, 0.0),
+            ]),
+            vec![LineString::from(vec![
+                (2.0, 2.0),
+                (8.0, 2.0),
+                (8.0, 8.0),
+                (2.0, 8.0),
+                (2.0, 2.0),
+            ])],
+        );
+
+        let ccw = normalise_winding(&poly, RingOrientation::CounterClockwise);
+        assert!(ccw.exterior().is_ccw());
+        assert!(ccw.interiors()[0].is_cw());
+
+        let cw = normalise_winding(&poly, RingOrientation::Clockwise);
+        assert!(cw.exterior().is_cw());
+        assert!(cw.interiors()[0].is_ccw());
+    }
+
+    #[test]
+    fn test_to_geojson_follows_right_hand_rule() {
+        use geo::Winding;
+
+        let clockwise = Polygon::new(
+            LineString::from(vec![
+                (0.0, 0.0),
+                (0.0, 1.0),
+                (1.0, 1.0),
+                (1.0, 0.0),
+                (0.0, 0.0),
+            ]),
+            vec![],
+        );
+
+        let parsed = Polygon::from_geojson(&clockwise.to_geojson()).unwrap();
+        assert!(parsed.exterior().is_ccw());
+    }
+
+    #[test]
+    fn test_unclosed_ring_is_closed() {
+        let geom = GeoJsonGeometry::new(GeoJsonValue::Polygon(vec![vec![
+            vec![0.0, 0.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+            vec![0.0, 1.0],
+        ]]));
+
+        let poly = Polygon::from_geojson(&geom).unwrap();
+        assert!(poly.exterior().is_closed());
+        assert_eq!(poly.exterior().0.len(), 5);
+
+        match Polygon::from_geojson_validated(&geom) {
+            Err(InfraHexError::Geometry(message)) => assert!(message.contains("not closed")),
+            other => panic!("expected a geometry error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_reversed_hole_is_rewound() {
+        // Counter-clockwise exterior with a counter-clockwise (reversed) hole
+        let geom = GeoJsonGeometry::new(GeoJsonValue::Polygon(vec![
+            vec![
+                vec![0.0, 0.0],
+                vec![10.0, 0.0],
+                vec![10.0, 10.0],
+                vec![0.0, 10.0],
+                vec![0.0, 0.0],
+            ],
+            vec![
+                vec![2.0, 2.0],
+                vec![8.0, 2.0],
+                vec![8.0, 8.0],
+                vec![2.0, 8.0],
+                vec![2.0, 2.0],
+            ],
+        ]));
+
+        let poly = Polygon::from_geojson(&geom).unwrap();
+        assert!(poly.exterior().is_ccw());
+        assert!(poly.interiors()[0].is_cw());
+
+        assert!(matches!(
+            MultiPolygon::from_geojson_validated(&geom),
+            Err(InfraHexError::Geometry(_))
+        ));
+    }
+
+    #[test]
+    fn test_validated_accepts_well_formed_polygon() {
+        let poly = Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+            vec![],
+        );
+
+        let parsed = Polygon::from_geojson_validated(&poly.to_geojson()).unwrap();
+        assert_eq!(parsed, poly);
+    }
+
+    #[test]
+    fn test_point_and_multipoint_from_geojson() {
+        let point = GeoJsonGeometry::new(GeoJsonValue::Point(vec![-2.2484, 53.4804]));
+        assert_eq!(
+            Point::from_geojson(&point).unwrap(),
+            Point::new(-2.2484, 53.4804)
+        );
+        assert_eq!(MultiPoint::from_geojson(&point).unwrap().0.len(), 1);
+
+        let multi = GeoJsonGeometry::new(GeoJsonValue::MultiPoint(vec![
+            vec![0.0, 0.0],
+            vec![1.0, 1.0, 5.0],
+        ]));
+        let parsed = MultiPoint::from_geojson(&multi).unwrap();
+        assert_eq!(parsed.0[1], Point::new(1.0, 1.0));
+        assert!(Point::from_geojson(&multi).is_err());
+
+        let short = GeoJsonGeometry::new(GeoJsonValue::Point(vec![0.0]));
+        assert!(Point::from_geojson(&short).is_err());
+    }
+
+    #[test]
+    fn test_linestring_roundtrip() {
+        let line = LineString::from(vec![(-2.2484, 53.4804), (-2.2502, 53.4806)]);
+
+        let geom = line.to_geojson();
+        match &geom.value {
+            GeoJsonValue::LineString(coords) => assert_eq!(coords[0], vec![-2.2484, 53.4804]),
+            _ => panic!("Expected LineString"),
+        }
+        assert_eq!(LineString::from_geojson(&geom).unwrap(), line);
+    }
+
+    #[test]
+    fn test_multilinestring_roundtrip() {
+        let lines = MultiLineString::new(vec![
+            LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]),
+            LineString::from(vec![(2.0, 2.0), (3.0, 3.0), (4.0, 2.0)]),
+        ]);
+
+        let parsed = MultiLineString::from_geojson(&lines.to_geojson()).unwrap();
+        assert_eq!(parsed, lines);
+    }
+
+    #[test]
+    fn test_point_roundtrip() {
+        let point = Point::new(-2.2484, 53.4804);
+
+        let geom = point.to_geojson();
+        assert_eq!(geom.value, GeoJsonValue::Point(vec![-2.2484, 53.4804]));
+        assert_eq!(Point::from_geojson(&geom).unwrap(), point);
+
+        let points = MultiPoint::new(vec![point, Point::new(0.0, 1.0)]);
+        assert_eq!(
+            MultiPoint::from_geojson(&points.to_geojson()).unwrap(),
+            points
+        );
+    }
+
+    #[test]
+    fn test_geometry_collection_to_geojson() {
+        let line = LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]);
+        let point = Point::new(2.0, 3.0);
+        let collection = GeometryCollection::new_from(vec![
+            Geometry::LineString(line.clone()),
+            Geometry::Point(point),
+        ]);
+
+        match collection.to_geojson().value {
+            GeoJsonValue::GeometryCollection(members) => {
+                assert_eq!(members.len(), 2);
+                assert_eq!(LineString::from_geojson(&members[0]).unwrap(), line);
+                assert_eq!(Point::from_geojson(&members[1]).unwrap(), point);
+            }
+            _ => panic!("Expected GeometryCollection"),
+        }
+    }
+
+    #[test]
+    fn test_linestring_z_roundtrip() {
+        let geom = GeoJsonGeometry::new(GeoJsonValue::LineString(vec![
+            vec![-2.2484, 53.4804, -1.2],
+            vec![-2.2502, 53.4806],
+            vec![-2.2510, 53.4810, -0.8],
+        ]));
+
+        let parsed = LineStringZ::from_geojson(&geom).unwrap();
+        assert_eq!(parsed.line.0.len(), 3);
+        assert_eq!(parsed.z, vec![Some(-1.2), None, Some(-0.8)]);
+        assert!(parsed.has_z());
+        assert_eq!(parsed.to_geojson(), geom);
+
+        // The 2D conversion drops Z
+        let flat = LineString::from_geojson(&geom).unwrap();
+        assert_eq!(flat, parsed.line);
+    }
+
+    #[test]
+    fn test_point_z_roundtrip() {
+        let geom = GeoJsonGeometry::new(GeoJsonValue::Point(vec![-2.2484, 53.4804, 12.5]));
+
+        let parsed = PointZ::from_geojson(&geom).unwrap();
+        assert_eq!(parsed.point, Point::new(-2.2484, 53.4804));
+        assert_eq!(parsed.z, Some(12.5));
+        assert_eq!(parsed.to_geojson(), geom);
+    }
+
     #[test]
     fn test_rejects_point_geometry() {
         let geom = GeoJsonGeometry::new(GeoJsonValue::Point(vec![0.0, 0.0]));
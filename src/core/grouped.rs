@@ -0,0 +1,546 @@
+use arrow_array::{RecordBatch, StringArray, UInt32Array};
+use arrow_schema::{DataType, Field, Schema};
+use geoarrow_array::IntoArrow;
+use n3gb_rs::HexCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::client::PipelineData;
+use crate::error::InfraHexError;
+
+use super::arrow::{
+    HexSummaryOptions, apply_hex_id_filter, build_polygon_geometry, extract_cells_per_pipeline,
+};
+
+/// Attribute used to split hex counts into groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    PipeType,
+    Material,
+    Pressure,
+}
+
+impl GroupBy {
+    /// Name of the group column in the output batch.
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            GroupBy::PipeType => "pipe_type",
+            GroupBy::Material => "material",
+            GroupBy::Pressure => "pressure",
+        }
+    }
+
+    fn key<'a, T: PipelineData>(&self, record: &'a T) -> Option<&'a str> {
+        match self {
+            GroupBy::PipeType => record.pipe_type(),
+            GroupBy::Material => record.material(),
+            GroupBy::Pressure => record.pressure(),
+        }
+    }
+}
+
+/// Bucket for records missing the grouped attribute in wide summaries. A
+/// record whose attribute is literally this value is rejected rather than
+/// merged into the bucket.
+const UNKNOWN_GROUP: &str = "unknown";
+
+/// One (hex, group) row: hex id, group value (`None` when the attribute is
+/// missing) and the number of pipelines.
+type GroupedRow = (String, Option<String>, usize);
+
+/// Counts pipelines per (hex, group), counting each pipeline at most once per
+/// hex. Rows are sorted by hex id then group so a hex's rows are adjacent.
+fn aggregate_grouped_counts<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    group_by: GroupBy,
+    options: &HexSummaryOptions,
+) -> Result<(Vec<GroupedRow>, HashMap<String, HexCell>), InfraHexError> {
+    let mut cells_per_pipe = extract_cells_per_pipeline(records, zoom, &None, &options.hex_cells)?;
+    apply_hex_id_filter(&mut cells_per_pipe, options.hex_id_filter.as_ref());
+
+    let mut counts: HashMap<(String, Option<String>), usize> = HashMap::new();
+    let mut cells_map: HashMap<String, HexCell> = HashMap::new();
+
+    for (record, cells) in records.iter().zip(cells_per_pipe) {
+        let group = group_by.key(record).map(str::to_string);
+        let mut seen_in_pipe = HashSet::new();
+        for cell in cells {
+            if seen_in_pipe.insert(cell.id.clone()) {
+                *counts.entry((cell.id.clone(), group.clone())).or_insert(0) += 1;
+                cells_map.entry(cell.id.clone()).or_insert(cell);
+            }
+        }
+    }
+
+    let mut rows: Vec<GroupedRow> = counts
+        .into_iter()
+        .map(|((id, group), count)| (id, group, count))
+        .collect();
+    rows.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+    Ok((rows, cells_map))
+}
+
+fn to_hex_summary_grouped_impl<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    group_by: GroupBy,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    let (rows, cells_map) = aggregate_grouped_counts(records, zoom, group_by, options)?;
+
+    let hex_ids: StringArray = rows.iter().map(|(id, _, _)| Some(id.as_str())).collect();
+    let groups: StringArray = rows.iter().map(|(_, g, _)| g.as_deref()).collect();
+    let pipe_counts: UInt32Array = rows.iter().map(|(_, _, c)| Some(*c as u32)).collect();
+
+    let names = &options.column_names;
+    let mut fields = vec![
+        Field::new(&names.hex_id, DataType::Utf8, false),
+        Field::new(group_by.column_name(), DataType::Utf8, true),
+        Field::new(&names.pipe_count, DataType::UInt32, false),
+    ];
+
+    let mut columns: Vec<Arc<dyn arrow_array::Array>> =
+        vec![Arc::new(hex_ids), Arc::new(groups), Arc::new(pipe_counts)];
+
+    if options.include_geometry {
+        // Each group row repeats its hex's polygon
+        let cells: Vec<&HexCell> = rows
+            .iter()
+            .map(|(id, _, _)| cells_map.get(id).unwrap())
+            .collect();
+
+        let (geometry_array, geometry_field) = build_polygon_geometry(&cells, options)?;
+        fields.push(geometry_field);
+        columns.push(Arc::new(geometry_array.into_arrow()));
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Builds a per-(hex, group) summary: one row for each distinct value of the
+/// `group_by` attribute within each hex, with the number of pipelines.
+///
+/// Every row carries its hex's polygon, so the polygon is duplicated across
+/// the group rows of the same hex. This makes the output directly renderable
+/// per (hex, group) at the cost of a larger geometry column; use
+/// [`to_hex_summary_grouped_no_geom`] and join on `hex_id` if size matters.
+/// Records with a missing attribute are grouped under a null value.
+pub fn to_hex_summary_grouped<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    group_by: GroupBy,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_grouped_impl(records, zoom, group_by, &HexSummaryOptions::new())
+}
+
+pub fn to_hex_summary_grouped_no_geom<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    group_by: GroupBy,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_grouped_impl(
+        records,
+        zoom,
+        group_by,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
+}
+
+/// Builds a wide per-hex summary: `hex_id`, `pipe_count`, then one
+/// `count_<value>` UInt32 column per distinct `group_by` value (sorted by
+/// value), an optional `dominant_<attribute>` Utf8 column, then geometry if
+/// enabled.
+///
+/// Records missing the attribute are counted under `count_unknown`; a record
+/// whose attribute is literally `"unknown"` returns `InfraHexError::Config`
+/// so the two can't be silently merged. Each pipeline belongs to one group,
+/// so a hex's group counts sum to its `pipe_count`. Rows are sorted by
+/// `pipe_count` descending, then hex id.
+fn to_hex_summary_wide_impl<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+    group_by: GroupBy,
+    include_dominant: bool,
+    options: &HexSummaryOptions,
+) -> Result<RecordBatch, InfraHexError> {
+    let (rows, cells_map) = aggregate_grouped_counts(records, zoom, group_by, options)?;
+    if rows
+        .iter()
+        .any(|(_, g, _)| g.as_deref() == Some(UNKNOWN_GROUP))
+    {
+        return Err(InfraHexError::Config(format!(
+            "{} value {:?} clashes with the bucket for missing values",
+            group_by.column_name(),
+            UNKNOWN_GROUP
+        )));
+    }
+    let group_of = |group: &Option<String>| group.as_deref().unwrap_or(UNKNOWN_GROUP).to_string();
+
+    let groups: Vec<String> = rows
+        .iter()
+        .map(|(_, g, _)| group_of(g))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let group_index: HashMap<&str, usize> = groups
+        .iter()
+        .enumerate()
+        .map(|(i, g)| (g.as_str(), i))
+        .collect();
+
+    // Rows are sorted by hex id, so each hex's groups are adjacent
+    let mut per_hex: Vec<(&str, Vec<u32>)> = Vec::new();
+    for (id, group, count) in &rows {
+        if per_hex.last().is_none_or(|(last, _)| *last != id.as_str()) {
+            per_hex.push((id, vec![0; groups.len()]));
+        }
+        let (_, counts) = per_hex.last_mut().unwrap();
+        counts[group_index[group_of(group).as_str()]] += *count as u32;
+    }
+    per_hex.sort_by(|a, b| {
+        let total = |c: &[u32]| c.iter().sum::<u32>();
+        total(&b.1).cmp(&total(&a.1)).then_with(|| a.0.cmp(b.0))
+    });
+
+    let names = &options.column_names;
+    let mut fields = vec![
+        Field::new(&names.hex_id, DataType::Utf8, false),
+        Field::new(&names.pipe_count, DataType::UInt32, false),
+    ];
+    let mut columns: Vec<Arc<dyn arrow_array::Array>> = vec![
+        Arc::new(
+            per_hex
+                .iter()
+                .map(|(id, _)| Some(*id))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            per_hex
+                .iter()
+                .map(|(_, c)| Some(c.iter().sum::<u32>()))
+                .collect::<UInt32Array>(),
+        ),
+    ];
+
+    for (i, group) in groups.iter().enumerate() {
+        fields.push(Field::new(
+            format!("count_{}", group),
+            DataType::UInt32,
+            false,
+        ));
+        columns.push(Arc::new(
+            per_hex
+                .iter()
+                .map(|(_, c)| Some(c[i]))
+                .collect::<UInt32Array>(),
+        ));
+    }
+
+    if include_dominant {
+        // Ties go to the group that sorts first
+        let dominant: StringArray = per_hex
+            .iter()
+            .map(|(_, counts)| {
+                let max = counts.iter().max()?;
+                let i = counts.iter().position(|c| c == max)?;
+                Some(groups[i].as_str())
+            })
+            .collect();
+        fields.push(Field::new(
+            format!("dominant_{}", group_by.column_name()),
+            DataType::Utf8,
+            false,
+        ));
+        columns.push(Arc::new(dominant));
+    }
+
+    if options.include_geometry {
+        let cells: Vec<&HexCell> = per_hex
+            .iter()
+            .map(|(id, _)| cells_map.get(*id).unwrap())
+            .collect();
+
+        let (geometry_array, geometry_field) = build_polygon_geometry(&cells, options)?;
+        fields.push(geometry_field);
+        columns.push(Arc::new(geometry_array.into_arrow()));
+    }
+
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Builds a per-hex summary with the pipe count broken down by `pipe_type`.
+///
+/// Alongside `hex_id` and `pipe_count`, each distinct pipe type in `records`
+/// gets a `count_<type>` column (e.g. `count_LP`, `count_MP`), sorted by
+/// type, and records without a type are counted under `count_unknown`. The
+/// columns depend on the input, so summaries of different areas may not
+/// share a schema. Unlike [`to_hex_summary_grouped`] there is one row per
+/// hex, which suits styling a map by dominant pressure tier.
+pub fn to_hex_summary_by_type<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_wide_impl(
+        records,
+        zoom,
+        GroupBy::PipeType,
+        false,
+        &HexSummaryOptions::new(),
+    )
+}
+
+pub fn to_hex_summary_by_type_no_geom<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_wide_impl(
+        records,
+        zoom,
+        GroupBy::PipeType,
+        false,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
+}
+
+/// Builds a per-hex summary with the pipe count broken down by `material`.
+///
+/// Like [`to_hex_summary_by_type`], each distinct material gets a
+/// `count_<material>` column and records without one are counted under
+/// `count_unknown`. A `dominant_material` column names the most common
+/// material in each hex (`"unknown"` if untyped pipes are the majority);
+/// ties go to the material that sorts first, so output is deterministic.
+pub fn to_hex_summary_by_material<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_wide_impl(
+        records,
+        zoom,
+        GroupBy::Material,
+        true,
+        &HexSummaryOptions::new(),
+    )
+}
+
+pub fn to_hex_summary_by_material_no_geom<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<RecordBatch, InfraHexError> {
+    to_hex_summary_wide_impl(
+        records,
+        zoom,
+        GroupBy::Material,
+        true,
+        &HexSummaryOptions::new().with_geometry(false),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use arrow_array::Array;
+
+    fn make_record(material: Option<&str>, coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            material: material.map(str::to_string),
+            ..line_record(coords)
+        }
+    }
+
+    fn make_records() -> Vec<CadentPipelineRecord> {
+        let line = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        vec![
+            make_record(Some("PE"), line.clone()),
+            make_record(Some("PE"), line.clone()),
+            make_record(Some("ST"), line.clone()),
+            make_record(None, line),
+        ]
+    }
+
+    #[test]
+    fn test_grouped_counts_per_group() {
+        let batch = to_hex_summary_grouped_no_geom(&make_records(), 10, GroupBy::Material).unwrap();
+
+        let materials = batch
+            .column_by_name("material")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let counts = batch
+            .column_by_name("pipe_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap();
+
+        let mut totals: HashMap<Option<&str>, u32> = HashMap::new();
+        for i in 0..batch.num_rows() {
+            let key = (!materials.is_null(i)).then(|| materials.value(i));
+            *totals.entry(key).or_insert(0) += counts.value(i);
+        }
+
+        let hexes = batch.num_rows() as u32 / 3;
+        assert_eq!(totals[&Some("PE")], 2 * hexes);
+        assert_eq!(totals[&Some("ST")], hexes);
+        assert_eq!(totals[&None], hexes);
+    }
+
+    fn make_typed_record(pipe_type: Option<&str>, coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            pipe_type: pipe_type.map(str::to_string),
+            ..make_record(None, coords)
+        }
+    }
+
+    fn u32_column<'a>(batch: &'a RecordBatch, name: &str) -> &'a UInt32Array {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_by_type_counts_per_type() {
+        let line = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let records = vec![
+            make_typed_record(Some("LP"), line.clone()),
+            make_typed_record(Some("LP"), line.clone()),
+            make_typed_record(Some("MP"), line.clone()),
+            make_typed_record(None, line),
+        ];
+        let batch = to_hex_summary_by_type_no_geom(&records, 10).unwrap();
+
+        let schema = batch.schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "hex_id",
+                "pipe_count",
+                "count_LP",
+                "count_MP",
+                "count_unknown"
+            ]
+        );
+
+        let expected = to_hex_summary_grouped_no_geom(&records, 10, GroupBy::PipeType).unwrap();
+        assert_eq!(batch.num_rows() * 3, expected.num_rows());
+        for i in 0..batch.num_rows() {
+            assert_eq!(u32_column(&batch, "pipe_count").value(i), 4);
+            assert_eq!(u32_column(&batch, "count_LP").value(i), 2);
+            assert_eq!(u32_column(&batch, "count_MP").value(i), 1);
+            assert_eq!(u32_column(&batch, "count_unknown").value(i), 1);
+        }
+    }
+
+    #[test]
+    fn test_by_type_zero_fills_absent_types() {
+        let records = vec![
+            make_typed_record(
+                Some("LP"),
+                vec![vec![-2.2400, 53.4800], vec![-2.2401, 53.4800]],
+            ),
+            make_typed_record(
+                Some("HP"),
+                vec![vec![-2.1000, 53.4000], vec![-2.1001, 53.4000]],
+            ),
+        ];
+        let batch = to_hex_summary_by_type(&records, 12).unwrap();
+
+        assert!(batch.column_by_name("count_unknown").is_none());
+        assert_eq!(
+            batch.column_by_name("geometry").unwrap().len(),
+            batch.num_rows()
+        );
+        for i in 0..batch.num_rows() {
+            let lp = u32_column(&batch, "count_LP").value(i);
+            let hp = u32_column(&batch, "count_HP").value(i);
+            assert_eq!(lp + hp, u32_column(&batch, "pipe_count").value(i));
+            assert!(lp == 0 || hp == 0);
+        }
+    }
+
+    fn dominant(batch: &RecordBatch) -> Vec<&str> {
+        let column = batch
+            .column_by_name("dominant_material")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        column.iter().map(Option::unwrap).collect()
+    }
+
+    #[test]
+    fn test_by_material_counts_and_dominant() {
+        let batch = to_hex_summary_by_material_no_geom(&make_records(), 10).unwrap();
+
+        let schema = batch.schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "hex_id",
+                "pipe_count",
+                "count_PE",
+                "count_ST",
+                "count_unknown",
+                "dominant_material"
+            ]
+        );
+        for i in 0..batch.num_rows() {
+            assert_eq!(u32_column(&batch, "count_PE").value(i), 2);
+            assert_eq!(u32_column(&batch, "count_unknown").value(i), 1);
+        }
+        assert!(dominant(&batch).iter().all(|m| *m == "PE"));
+    }
+
+    #[test]
+    fn test_by_material_tie_picks_first_sorted() {
+        let line = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let records = vec![
+            make_record(Some("ST"), line.clone()),
+            make_record(Some("PE"), line.clone()),
+            make_record(Some("ST"), line.clone()),
+            make_record(Some("PE"), line),
+        ];
+        let batch = to_hex_summary_by_material(&records, 10).unwrap();
+
+        assert!(batch.num_rows() > 0);
+        for i in 0..batch.num_rows() {
+            assert_eq!(u32_column(&batch, "count_PE").value(i), 2);
+            assert_eq!(u32_column(&batch, "count_ST").value(i), 2);
+        }
+        assert!(dominant(&batch).iter().all(|m| *m == "PE"));
+    }
+
+    #[test]
+    fn test_wide_rejects_literal_unknown_value() {
+        let line = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let records = vec![
+            make_record(Some("unknown"), line.clone()),
+            make_record(None, line),
+        ];
+        assert!(matches!(
+            to_hex_summary_by_material_no_geom(&records, 10),
+            Err(InfraHexError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_grouped_geometry_repeats_per_row() {
+        let batch = to_hex_summary_grouped(&make_records(), 10, GroupBy::Material).unwrap();
+
+        let geometry = batch.column_by_name("geometry").unwrap();
+        assert_eq!(geometry.len(), batch.num_rows());
+        assert_eq!(geometry.null_count(), 0);
+    }
+}
@@ -0,0 +1,132 @@
+use arrow_array::UInt64Array;
+use h3o::{LatLng, Resolution};
+use n3gb_rs::HexCell;
+
+use crate::error::InfraHexError;
+
+use super::reproject::bng_to_wgs84;
+use super::zoom::hex_cell_dimensions;
+
+/// Average H3 hexagon area in square metres, indexed by resolution 0-15.
+const H3_AVERAGE_AREA_M2: [f64; 16] = [
+    4.357_449_416_078_383e12,
+    6.097_884_417_941_332e11,
+    8.680_178_039_899_72e10,
+    1.239_343_465_508_816e10,
+    1.770_347_654_491_307e9,
+    2.529_038_581_819_449e8,
+    3.612_906_216_441_245e7,
+    5.161_293_359_717_191e6,
+    7.373_275_975_944_177e5,
+    1.053_325_134_272_067e5,
+    1.504_750_190_766_435e4,
+    2.149_643_129_451_879e3,
+    3.070_918_756_316_06e2,
+    4.387_026_794_728_296e1,
+    6.267_181_135_324_313,
+    8.953_115_907_605_79e-1,
+];
+
+/// Picks the H3 resolution whose cells are closest in size to n3gb cells at
+/// `zoom`.
+///
+/// The n3gb cell area (see [`hex_cell_dimensions`]) is compared with H3's
+/// published average hexagon area at each resolution, and the resolution
+/// with the smallest area ratio either way is chosen. H3 areas step by
+/// roughly 7x per resolution, so the chosen H3 cells are within a factor of
+/// about 2.6 of the n3gb cells.
+///
+/// # Errors
+///
+/// Returns [`InfraHexError::HexGrid`] if `zoom` is not a valid n3gb zoom.
+pub fn h3_resolution_for_zoom(zoom: u8) -> Result<u8, InfraHexError> {
+    let area = hex_cell_dimensions(zoom)?.area_m2;
+    let distance = |h3_area: f64| (h3_area / area).ln().abs();
+
+    let resolution = (0..H3_AVERAGE_AREA_M2.len())
+        .min_by(|&a, &b| {
+            distance(H3_AVERAGE_AREA_M2[a]).total_cmp(&distance(H3_AVERAGE_AREA_M2[b]))
+        })
+        .unwrap_or(0);
+    Ok(resolution as u8)
+}
+
+/// Computes the H3 index of the cell containing each hex's centroid.
+///
+/// Centroids are reprojected from BNG to WGS84 and indexed at the resolution
+/// given by [`h3_resolution_for_zoom`] for the hex's own zoom. H3 and n3gb
+/// cells don't nest, so this is an approximation: an n3gb hex maps to the
+/// single H3 cell under its centre, even when it overlaps several.
+pub(super) fn h3_index_column(cells: &[&HexCell]) -> Result<UInt64Array, InfraHexError> {
+    let mut resolutions: Vec<Option<Resolution>> = vec![None; 16];
+
+    cells
+        .iter()
+        .map(|cell| {
+            let zoom = usize::from(cell.zoom_level);
+            let resolution = match resolutions.get(zoom).copied().flatten() {
+                Some(resolution) => resolution,
+                None => {
+                    let resolution = Resolution::try_from(h3_resolution_for_zoom(cell.zoom_level)?)
+                        .map_err(|e| InfraHexError::Config(e.to_string()))?;
+                    if let Some(slot) = resolutions.get_mut(zoom) {
+                        *slot = Some(resolution);
+                    }
+                    resolution
+                }
+            };
+
+            let centroid = bng_to_wgs84(cell.center.0);
+            let lat_lng = LatLng::new(centroid.y, centroid.x)
+                .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+            Ok(Some(u64::from(lat_lng.to_cell(resolution))))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::HexSummaryOptions;
+    use crate::core::to_hex_summary_with_options;
+    use arrow_array::Array;
+
+    fn make_record(coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            asset_id: Some("H3-001".to_string()),
+            ..line_record(coords)
+        }
+    }
+
+    #[test]
+    fn test_h3_resolution_increases_with_zoom() {
+        let resolutions: Vec<u8> = (0..=15)
+            .map(|zoom| h3_resolution_for_zoom(zoom).unwrap())
+            .collect();
+        assert!(resolutions.windows(2).all(|w| w[0] <= w[1]));
+        assert!(resolutions.iter().all(|&r| r <= 15));
+    }
+
+    #[test]
+    fn test_summary_h3_index_column() {
+        let records = vec![make_record(vec![
+            vec![-2.2400, 53.4800],
+            vec![-2.2300, 53.4850],
+        ])];
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_h3_index(true);
+        let batch = to_hex_summary_with_options(&records, 10, &(), &options).unwrap();
+
+        let h3 = batch
+            .column_by_name("h3_index")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt64Array>()
+            .unwrap();
+        assert_eq!(h3.len(), batch.num_rows());
+        assert_eq!(h3.null_count(), 0);
+    }
+}
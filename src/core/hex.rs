@@ -1,65 +1,677 @@
-use geo_types::LineString;
-use n3gb_rs::HexCell;
+use geo::{Buffer, Intersects};
+use geo_types::{Coord, LineString, MultiPoint};
+use geojson::{Geometry as GeoJsonGeometry, Value as GeoJsonValue};
+use n3gb_rs::{HexCell, HexGrid};
+use std::collections::HashSet;
 
 use crate::client::PipelineData;
 use crate::error::InfraHexError;
 
 use super::geometry::FromGeoJson;
+use super::reproject::Crs;
+
+/// How deeply nested `GeometryCollection`s are followed before giving up.
+const MAX_COLLECTION_DEPTH: usize = 8;
+
+/// Options controlling how a pipeline geometry is converted into hex cells.
+#[derive(Debug, Clone, Default)]
+pub struct HexCellOptions {
+    pub coordinate_precision: Option<f64>,
+    pub max_cells_per_pipe: Option<usize>,
+    pub sample_every_n: Option<usize>,
+    pub buffer_m: Option<f64>,
+    pub crs: Crs,
+}
+
+impl HexCellOptions {
+    /// Creates options matching the behaviour of [`get_hex_cells`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snaps every input coordinate to a grid of `precision` degrees (e.g.
+    /// `1e-7`) before hexing.
+    ///
+    /// Floating-point noise in the last digits can flip which cell a vertex
+    /// near a cell edge lands in; snapping first makes cell assignment
+    /// reproducible across platforms and data sources.
+    pub fn with_coordinate_precision(mut self, precision: f64) -> Self {
+        self.coordinate_precision = Some(precision);
+        self
+    }
+
+    /// Keeps only every `n`th vertex of each line (always keeping the first
+    /// and last) before hexing.
+    ///
+    /// Some lines pack thousands of vertices into a single cell; thinning
+    /// them is cruder than simplification but cheap and predictable. At
+    /// coarse zooms the cell set is usually unchanged, but at fine zooms a
+    /// large stride can cut corners and miss cells. A stride of 1 keeps every
+    /// vertex; 0 is rejected with `InfraHexError::Config`.
+    pub fn with_sample_every_n(mut self, n: usize) -> Self {
+        self.sample_every_n = Some(n);
+        self
+    }
+
+    /// Buffers each pipe by `buffer_m` metres in British National Grid and
+    /// hexes the resulting corridor instead of the bare line.
+    ///
+    /// Every cell the corridor overlaps is kept, giving a true metric
+    /// easement around the pipe (e.g. 10 m) rather than a grid-step
+    /// approximation. Ends and bends are rounded. A buffer of 0 leaves the
+    /// line's own cells; negative or non-finite buffers are rejected with
+    /// `InfraHexError::Config`.
+    pub fn with_buffer_m(mut self, buffer_m: f64) -> Self {
+        self.buffer_m = Some(buffer_m);
+        self
+    }
+
+    /// Sets the coordinate reference system of the input geometry.
+    ///
+    /// Defaults to [`Crs::Wgs84`], as served by Cadent. Use [`Crs::Bng`] for
+    /// NUAR-style sources that already supply British National Grid
+    /// eastings and northings; those are hexed directly with no
+    /// reprojection. [`HexCellOptions::with_coordinate_precision`] is in the
+    /// units of this CRS.
+    pub fn with_crs(mut self, crs: Crs) -> Self {
+        self.crs = crs;
+        self
+    }
+
+    /// Fails with `InfraHexError::Geometry` when a single pipe produces more
+    /// than `max` cells.
+    ///
+    /// A guard against pathological geometry: a malformed or extremely long
+    /// line at a high zoom can otherwise generate an enormous cell vector and
+    /// exhaust memory during aggregation.
+    pub fn with_max_cells_per_pipe(mut self, max: usize) -> Self {
+        self.max_cells_per_pipe = Some(max);
+        self
+    }
+}
 
 /// Extract hex cells from any pipeline record that implements PipelineData.
 /// This works with pipeline linestrings from different infrastructure clients.
-/// This currenty assumes the data will be in wgs84 for NUAR client needs to be BNG too
-/// TODO: Add flag for CRS system that tiggers correct method
-/// let cells = HexCell::from_line_string_bng(&line, zoom)?;
+/// Coordinates are assumed to be WGS84; use [`get_hex_cells_with_crs`] for
+/// data already in British National Grid.
+///
+/// Cells are always on the standard n3gb grid: n3gb-rs 0.1.6 fixes the
+/// grid's origin, orientation and cell sizes per zoom, and neither
+/// `HexCell` nor `HexGrid` takes grid parameters, so there is no custom
+/// grid to pass through here.
 pub fn get_hex_cells<T: PipelineData>(record: &T, zoom: u8) -> Result<Vec<HexCell>, InfraHexError> {
+    get_hex_cells_with_options(record, zoom, &HexCellOptions::default())
+}
+
+/// Extract hex cells from a pipeline record whose coordinates are in `crs`.
+///
+/// Shorthand for [`get_hex_cells_with_options`] with
+/// [`HexCellOptions::with_crs`].
+pub fn get_hex_cells_with_crs<T: PipelineData>(
+    record: &T,
+    zoom: u8,
+    crs: Crs,
+) -> Result<Vec<HexCell>, InfraHexError> {
+    get_hex_cells_with_options(record, zoom, &HexCellOptions::new().with_crs(crs))
+}
+
+/// Extract hex cells from a pipeline record, applying the given options.
+///
+/// Point and MultiPoint features (e.g. valves or meters) map to the cell
+/// containing each point. `GeometryCollection`s are unpacked (up to 8 levels
+/// of nesting): the cells of each member line or point are unioned, keeping
+/// the first occurrence of each cell.
+pub fn get_hex_cells_with_options<T: PipelineData>(
+    record: &T,
+    zoom: u8,
+    options: &HexCellOptions,
+) -> Result<Vec<HexCell>, InfraHexError> {
     let geometry = record
         .geo_shape()
         .geometry
         .as_ref()
         .ok_or_else(|| InfraHexError::Geometry("Feature has no geometry".to_string()))?;
 
-    let line = LineString::from_geojson(geometry)?;
-    let cells = HexCell::from_line_string_wgs84(&line, zoom)?;
+    let cells = cells_for_geometry(geometry, zoom, options, 0)?;
+
+    if let Some(max) = options.max_cells_per_pipe
+        && cells.len() > max
+    {
+        return Err(InfraHexError::Geometry(format!(
+            "Asset {} produced {} cells at zoom {}, exceeding the limit of {}",
+            record.asset_id().unwrap_or("<unknown>"),
+            cells.len(),
+            zoom,
+            max
+        )));
+    }
+
+    Ok(cells)
+}
+
+/// Extracts the hex cells within `buffer_m` metres of a pipeline record.
+///
+/// Shorthand for [`get_hex_cells_with_options`] with
+/// [`HexCellOptions::with_buffer_m`].
+pub fn get_hex_cells_buffered_m<T: PipelineData>(
+    record: &T,
+    zoom: u8,
+    buffer_m: f64,
+) -> Result<Vec<HexCell>, InfraHexError> {
+    get_hex_cells_with_options(record, zoom, &HexCellOptions::new().with_buffer_m(buffer_m))
+}
+
+/// Parses an n3gb hex id from an external source (e.g. an allowlist or a
+/// join key), reconstructing the cell, and its polygon, from the id alone.
+///
+/// Malformed ids return `InfraHexError::HexGrid`. When `expected_zoom` is
+/// given, a well-formed id at a different zoom returns
+/// `InfraHexError::Config`, so ids can be checked against the zoom of the
+/// data they'll be joined to before use.
+pub fn parse_hex_id(id: &str, expected_zoom: Option<u8>) -> Result<HexCell, InfraHexError> {
+    let cell = HexCell::from_hex_id(id)?;
+
+    if let Some(zoom) = expected_zoom
+        && cell.zoom_level != zoom
+    {
+        return Err(InfraHexError::Config(format!(
+            "Hex id {} is at zoom {}, expected zoom {}",
+            id, cell.zoom_level, zoom
+        )));
+    }
+
+    Ok(cell)
+}
+
+/// Hexes a single GeoJSON geometry, recursing into collections.
+fn cells_for_geometry(
+    geometry: &GeoJsonGeometry,
+    zoom: u8,
+    options: &HexCellOptions,
+    depth: usize,
+) -> Result<Vec<HexCell>, InfraHexError> {
+    match &geometry.value {
+        GeoJsonValue::GeometryCollection(members) => {
+            if depth >= MAX_COLLECTION_DEPTH {
+                return Err(InfraHexError::Geometry(format!(
+                    "GeometryCollection nested deeper than {} levels",
+                    MAX_COLLECTION_DEPTH
+                )));
+            }
+
+            let mut seen = HashSet::new();
+            let mut cells = Vec::new();
+            for member in members {
+                for cell in cells_for_geometry(member, zoom, options, depth + 1)? {
+                    if seen.insert(cell.id.clone()) {
+                        cells.push(cell);
+                    }
+                }
+            }
+            Ok(cells)
+        }
+        GeoJsonValue::Point(_) | GeoJsonValue::MultiPoint(_) => {
+            let points = MultiPoint::from_geojson(geometry)?;
+
+            let mut seen = HashSet::new();
+            let mut cells = Vec::new();
+            for point in points {
+                for cell in point_cells(point.0, zoom, options)? {
+                    if seen.insert(cell.id.clone()) {
+                        cells.push(cell);
+                    }
+                }
+            }
+            Ok(cells)
+        }
+        _ => {
+            let mut line = LineString::from_geojson(geometry)?;
+
+            if let Some(n) = options.sample_every_n {
+                line = sample_every_n(&line, n)?;
+            }
+
+            if let Some(precision) = options.coordinate_precision {
+                line = snap_to_grid(&line, precision)?;
+            }
+
+            let cells = match options.crs {
+                Crs::Wgs84 => HexCell::from_line_string_wgs84(&line, zoom)?,
+                Crs::Bng => HexCell::from_line_string_bng(&line, zoom)?,
+            };
+            match options.buffer_m {
+                Some(buffer_m) => buffered_cells(&line, options.crs, cells, zoom, buffer_m),
+                None => Ok(cells),
+            }
+        }
+    }
+}
+
+/// Hexes a single point: the cell containing it, plus any within the
+/// buffer distance.
+fn point_cells(
+    point: Coord<f64>,
+    zoom: u8,
+    options: &HexCellOptions,
+) -> Result<Vec<HexCell>, InfraHexError> {
+    let mut line = LineString::new(vec![point]);
+    if let Some(precision) = options.coordinate_precision {
+        line = snap_to_grid(&line, precision)?;
+    }
+    let point = line.0[0].into();
+    let cell = match options.crs {
+        Crs::Wgs84 => HexCell::from_wgs84(&point, zoom)?,
+        Crs::Bng => HexCell::from_bng(&point, zoom)?,
+    };
+    match options.buffer_m {
+        Some(buffer_m) => buffered_cells(&line, options.crs, vec![cell], zoom, buffer_m),
+        None => Ok(vec![cell]),
+    }
+}
+
+/// Keeps every `n`th vertex of `line` plus its last, so a line of two or
+/// more points never drops below two.
+fn sample_every_n(line: &LineString<f64>, n: usize) -> Result<LineString<f64>, InfraHexError> {
+    if n == 0 {
+        return Err(InfraHexError::Config(
+            "Vertex sampling stride must be at least 1".to_string(),
+        ));
+    }
+
+    let last = line.0.len().saturating_sub(1);
+    Ok(line
+        .0
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % n == 0 || *i == last)
+        .map(|(_, c)| *c)
+        .collect())
+}
+
+/// Adds to `cells` (the line's own cells) every cell overlapping `line`,
+/// given in `crs`, buffered by `buffer_m` metres in BNG.
+///
+/// `HexGrid` keeps cells whose centre lies inside a polygon, so candidates
+/// come from a corridor widened by one cell circumradius, which contains the
+/// centre of every cell touching the true corridor, and are then tested for
+/// overlap. geo's buffer unions the swept shape, so sharp bends that fold
+/// the offset curve back on itself still give valid polygons.
+fn buffered_cells(
+    line: &LineString<f64>,
+    crs: Crs,
+    mut cells: Vec<HexCell>,
+    zoom: u8,
+    buffer_m: f64,
+) -> Result<Vec<HexCell>, InfraHexError> {
+    if !buffer_m.is_finite() || buffer_m < 0.0 {
+        return Err(InfraHexError::Config(format!(
+            "Buffer distance must be zero or positive, got {}",
+            buffer_m
+        )));
+    }
+    let Some(sample) = cells.first() else {
+        return Ok(cells);
+    };
+    if buffer_m == 0.0 {
+        return Ok(cells);
+    }
+
+    let circumradius = sample
+        .to_polygon()
+        .exterior()
+        .coords()
+        .map(|c| (c.x - sample.center.x()).hypot(c.y - sample.center.y()))
+        .fold(0.0, f64::max);
+
+    let bng: LineString<f64> = line.coords().map(|c| crs.to_bng(*c)).collect();
+    let corridor = bng.buffer(buffer_m);
+    let search_area = bng.buffer(buffer_m + circumradius);
+
+    let mut seen: HashSet<String> = cells.iter().map(|c| c.id.clone()).collect();
+    for polygon in &search_area {
+        for cell in HexGrid::from_bng_polygon(polygon, zoom)?.cells() {
+            if !seen.contains(&cell.id) && cell.to_polygon().intersects(&corridor) {
+                seen.insert(cell.id.clone());
+                cells.push(cell.clone());
+            }
+        }
+    }
+
     Ok(cells)
 }
 
+/// Rounds each coordinate to the nearest multiple of `precision`.
+fn snap_to_grid(line: &LineString<f64>, precision: f64) -> Result<LineString<f64>, InfraHexError> {
+    if !precision.is_finite() || precision <= 0.0 {
+        return Err(InfraHexError::Config(format!(
+            "Coordinate precision must be positive, got {}",
+            precision
+        )));
+    }
+
+    let snap = |v: f64| (v / precision).round() * precision;
+    Ok(line
+        .coords()
+        .map(|c| Coord {
+            x: snap(c.x),
+            y: snap(c.y),
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::client::{CadentPipelineRecord, GeoPoint2d};
-    use geojson::{Feature, Geometry, Value};
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::reproject::wgs84_to_bng;
+    use geo_types::Point;
+    use geojson::{Geometry, Value};
 
     fn make_test_record() -> CadentPipelineRecord {
-        let geom = Geometry::new(Value::LineString(vec![
+        CadentPipelineRecord {
+            pipe_type: Some("MP".to_string()),
+            asset_id: Some("TEST-001".to_string()),
+            ..line_record(vec![
+                vec![-2.248423716278411, 53.4804537960769],
+                vec![-2.248817614533952, 53.480510340167925],
+                vec![-2.249255070278722, 53.480573578320396],
+                vec![-2.249632113002486, 53.48061535991179],
+                vec![-2.250244759514899, 53.48066909573824],
+            ])
+        }
+    }
+
+    fn make_record_with_coords(coords: Vec<Vec<f64>>) -> CadentPipelineRecord {
+        let mut record = make_test_record();
+        record.geo_shape.geometry = Some(Geometry::new(Value::LineString(coords)));
+        record
+    }
+
+    #[test]
+    fn test_get_hex_cells_with_crs_wgs84() {
+        let record = make_test_record();
+        let cells = get_hex_cells_with_crs(&record, 12, Crs::Wgs84).unwrap();
+
+        let ids: Vec<&str> = cells.iter().map(|c| c.id.as_str()).collect();
+        let default_cells = get_hex_cells(&record, 12).unwrap();
+        let default_ids: Vec<&str> = default_cells.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, default_ids);
+
+        let start =
+            HexCell::from_wgs84(&Point::new(-2.248423716278411, 53.4804537960769), 12).unwrap();
+        assert!(ids.contains(&start.id.as_str()));
+    }
+
+    #[test]
+    fn test_get_hex_cells_with_crs_bng() {
+        // A 120 m east-west run in central Manchester, in eastings/northings
+        let record =
+            make_record_with_coords(vec![vec![383_500.0, 398_200.0], vec![383_620.0, 398_200.0]]);
+        let cells = get_hex_cells_with_crs(&record, 12, Crs::Bng).unwrap();
+        assert!(!cells.is_empty());
+
+        let ids: HashSet<&str> = cells.iter().map(|c| c.id.as_str()).collect();
+        for (x, y) in [(383_500.0, 398_200.0), (383_620.0, 398_200.0)] {
+            let cell = HexCell::from_bng(&Point::new(x, y), 12).unwrap();
+            assert!(ids.contains(cell.id.as_str()), "missing {}", cell.id);
+        }
+
+        // Every cell lies along the line, not wherever the numbers would
+        // land if read as degrees
+        for cell in &cells {
+            assert!((cell.center.y() - 398_200.0).abs() < 500.0);
+        }
+    }
+
+    #[test]
+    fn test_coordinate_precision_makes_cells_reproducible() {
+        let a = make_record_with_coords(vec![
             vec![-2.248423716278411, 53.4804537960769],
-            vec![-2.248817614533952, 53.480510340167925],
-            vec![-2.249255070278722, 53.480573578320396],
-            vec![-2.249632113002486, 53.48061535991179],
             vec![-2.250244759514899, 53.48066909573824],
+        ]);
+        let b = make_record_with_coords(vec![
+            vec![-2.248423716278412, 53.4804537960768],
+            vec![-2.250244759514898, 53.48066909573825],
+        ]);
+        let options = HexCellOptions::new().with_coordinate_precision(1e-7);
+
+        let ids = |record: &CadentPipelineRecord| -> Vec<String> {
+            get_hex_cells_with_options(record, 12, &options)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect()
+        };
+
+        assert_eq!(ids(&a), ids(&b));
+    }
+
+    #[test]
+    fn test_buffer_picks_up_adjacent_cells() {
+        let record = make_record_with_coords(vec![vec![-2.2600, 53.4800], vec![-2.2200, 53.4800]]);
+        let zoom = 10;
+        let edge = crate::core::hex_cell_dimensions(zoom)
+            .unwrap()
+            .edge_length_m;
+        // Half the across-flats width of a hexagon
+        let half_width = edge * 3f64.sqrt() / 2.0;
+
+        let line_ids: HashSet<String> = get_hex_cells(&record, zoom)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        let buffered = get_hex_cells_buffered_m(&record, zoom, half_width).unwrap();
+        let buffered_ids: HashSet<String> = buffered.iter().map(|c| c.id.clone()).collect();
+
+        assert!(buffered_ids.is_superset(&line_ids));
+        assert!(buffered_ids.len() > line_ids.len());
+        assert_eq!(buffered_ids.len(), buffered.len());
+
+        // Every added cell lies within the buffer distance of the line
+        let bng: LineString<f64> = LineString::from(vec![(-2.2600, 53.4800), (-2.2200, 53.4800)])
+            .coords()
+            .map(|c| wgs84_to_bng(*c))
+            .collect();
+        let reach = bng.buffer(half_width * 1.001);
+        for cell in buffered.iter().filter(|c| !line_ids.contains(&c.id)) {
+            assert!(cell.to_polygon().intersects(&reach));
+        }
+    }
+
+    #[test]
+    fn test_buffer_handles_sharp_bends_and_bad_distances() {
+        // A hairpin whose offset curves overlap
+        let record = make_record_with_coords(vec![
+            vec![-2.2600, 53.4800],
+            vec![-2.2200, 53.4800],
+            vec![-2.2600, 53.4801],
+        ]);
+        let cells = get_hex_cells_buffered_m(&record, 10, 250.0).unwrap();
+        let ids: HashSet<&str> = cells.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids.len(), cells.len());
+
+        assert_eq!(
+            get_hex_cells_buffered_m(&record, 10, 0.0).unwrap().len(),
+            get_hex_cells(&record, 10).unwrap().len()
+        );
+        assert!(matches!(
+            get_hex_cells_buffered_m(&record, 10, -1.0),
+            Err(InfraHexError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_snap_to_grid_identical_coords() {
+        let a = LineString::from(vec![(-2.248423716278411, 53.4804537960769)]);
+        let b = LineString::from(vec![(-2.248423716278412, 53.4804537960768)]);
+
+        assert_eq!(
+            snap_to_grid(&a, 1e-7).unwrap(),
+            snap_to_grid(&b, 1e-7).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_snap_to_grid_rejects_invalid_precision() {
+        let line = LineString::from(vec![(0.0, 0.0)]);
+        assert!(snap_to_grid(&line, 0.0).is_err());
+        assert!(snap_to_grid(&line, f64::NAN).is_err());
+    }
+
+    fn make_record_with_geometry(value: Value) -> CadentPipelineRecord {
+        let mut record = make_test_record();
+        record.geo_shape.geometry = Some(Geometry::new(value));
+        record
+    }
+
+    fn cell_ids(record: &CadentPipelineRecord, zoom: u8) -> HashSet<String> {
+        get_hex_cells(record, zoom)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_geometry_collection_unions_member_cells() {
+        let first = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let second = vec![vec![-2.2000, 53.5000], vec![-2.2018, 53.5002]];
+
+        let collection = make_record_with_geometry(Value::GeometryCollection(vec![
+            Geometry::new(Value::LineString(first.clone())),
+            Geometry::new(Value::LineString(second.clone())),
         ]));
 
-        CadentPipelineRecord {
-            geo_point_2d: GeoPoint2d {
-                lon: -2.248,
-                lat: 53.480,
-            },
-            geo_shape: Feature {
-                geometry: Some(geom),
-                ..Default::default()
-            },
-            pipe_type: Some("MP".to_string()),
-            pressure: None,
-            material: None,
-            diameter: None,
-            diam_unit: None,
-            carr_mat: None,
-            carr_dia: None,
-            carr_di_un: None,
-            asset_id: Some("TEST-001".to_string()),
-            depth: None,
-            ag_ind: None,
-            inst_date: None,
+        let first_ids = cell_ids(&make_record_with_coords(first), 12);
+        let second_ids = cell_ids(&make_record_with_coords(second), 12);
+        let collection_ids = cell_ids(&collection, 12);
+
+        assert!(!first_ids.is_empty() && !second_ids.is_empty());
+        assert!(first_ids.is_subset(&collection_ids));
+        assert!(second_ids.is_subset(&collection_ids));
+        assert_eq!(collection_ids, &first_ids | &second_ids);
+    }
+
+    #[test]
+    fn test_nested_geometry_collection_with_point() {
+        let nested = make_record_with_geometry(Value::GeometryCollection(vec![Geometry::new(
+            Value::GeometryCollection(vec![Geometry::new(Value::Point(vec![-2.2484, 53.4804]))]),
+        )]));
+
+        assert_eq!(get_hex_cells(&nested, 12).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_point_maps_to_containing_cell() {
+        let record = make_record_with_geometry(Value::Point(vec![-2.2484, 53.4804]));
+        let expected = HexCell::from_wgs84(&Point::new(-2.2484, 53.4804), 12).unwrap();
+
+        let cells = get_hex_cells(&record, 12).unwrap();
+        assert_eq!(cells.len(), 1);
+        assert_eq!(cells[0].id, expected.id);
+        assert_eq!(get_hex_cells(&record, 12).unwrap()[0].id, expected.id);
+    }
+
+    #[test]
+    fn test_multipoint_dedups_shared_cells() {
+        let record = make_record_with_geometry(Value::MultiPoint(vec![
+            vec![-2.2484, 53.4804],
+            vec![-2.2484, 53.4804],
+            vec![-2.40, 53.40],
+        ]));
+
+        assert_eq!(get_hex_cells(&record, 12).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_geometry_collection_too_deep() {
+        let mut value = Value::LineString(vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]]);
+        for _ in 0..=MAX_COLLECTION_DEPTH {
+            value = Value::GeometryCollection(vec![Geometry::new(value)]);
         }
+
+        assert!(get_hex_cells(&make_record_with_geometry(value), 12).is_err());
+    }
+
+    #[test]
+    fn test_max_cells_per_pipe() {
+        // A ~15 km line at a high zoom
+        let record = make_record_with_coords(vec![vec![-2.40, 53.40], vec![-2.20, 53.50]]);
+        let uncapped = get_hex_cells(&record, 12).unwrap().len();
+        assert!(uncapped > 10);
+
+        let capped = HexCellOptions::new().with_max_cells_per_pipe(10);
+        let err = get_hex_cells_with_options(&record, 12, &capped).unwrap_err();
+        assert!(matches!(&err, InfraHexError::Geometry(msg) if msg.contains("TEST-001")));
+
+        let roomy = HexCellOptions::new().with_max_cells_per_pipe(uncapped);
+        assert_eq!(
+            get_hex_cells_with_options(&record, 12, &roomy)
+                .unwrap()
+                .len(),
+            uncapped
+        );
+    }
+
+    #[test]
+    fn test_sample_every_n_keeps_endpoints() {
+        let line: LineString<f64> = (0..10).map(|i| (i as f64, 0.0)).collect();
+
+        let xs = |l: LineString<f64>| l.0.iter().map(|c| c.x).collect::<Vec<_>>();
+        assert_eq!(
+            xs(sample_every_n(&line, 4).unwrap()),
+            vec![0.0, 4.0, 8.0, 9.0]
+        );
+        assert_eq!(xs(sample_every_n(&line, 100).unwrap()), vec![0.0, 9.0]);
+        assert_eq!(sample_every_n(&line, 1).unwrap(), line);
+        assert!(sample_every_n(&line, 0).is_err());
+    }
+
+    #[test]
+    fn test_sample_every_n_preserves_cells_at_coarse_zoom() {
+        // ~500 m of line with a vertex every metre or so
+        let dense: Vec<Vec<f64>> = (0..=500)
+            .map(|i| vec![-2.2500 + i as f64 * 0.000015, 53.4800])
+            .collect();
+        let record = make_record_with_coords(dense);
+
+        let ids = |options: &HexCellOptions| -> HashSet<String> {
+            get_hex_cells_with_options(&record, 6, options)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect()
+        };
+
+        let sampled = HexCellOptions::new().with_sample_every_n(50);
+        assert_eq!(ids(&HexCellOptions::new()), ids(&sampled));
+    }
+
+    #[test]
+    fn test_parse_hex_id_roundtrip() {
+        let generated = get_hex_cells(&make_test_record(), 12).unwrap();
+        let cell = &generated[0];
+
+        let parsed = parse_hex_id(&cell.id, Some(12)).unwrap();
+        assert_eq!(parsed.id, cell.id);
+        assert_eq!(parsed.zoom_level, 12);
+        assert_eq!(parsed.to_polygon(), cell.to_polygon());
+    }
+
+    #[test]
+    fn test_parse_hex_id_rejects_invalid() {
+        assert!(matches!(
+            parse_hex_id("not-a-hex-id", None),
+            Err(InfraHexError::HexGrid(_))
+        ));
+
+        let cell = &get_hex_cells(&make_test_record(), 12).unwrap()[0];
+        assert!(matches!(
+            parse_hex_id(&cell.id, Some(10)),
+            Err(InfraHexError::Config(_))
+        ));
     }
 
     #[test]
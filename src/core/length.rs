@@ -0,0 +1,214 @@
+use geo_types::Coord;
+use geojson::Value as GeoJsonValue;
+use n3gb_rs::HexCell;
+use std::collections::HashMap;
+
+use crate::client::PipelineData;
+
+use super::reproject::Crs;
+
+/// Returns the planar length of a record's pipe geometry in metres.
+///
+/// The WGS84 geometry is reprojected to British National Grid and measured as
+/// straight segments. Parts of a MultiLineString are measured separately, so
+/// gaps between parts don't count. Missing, empty or non-linear geometry, and
+/// single-vertex lines, have a length of 0.0.
+pub fn pipe_length_m<T: PipelineData>(record: &T) -> f64 {
+    measured_length_m(record, Crs::Wgs84).unwrap_or(0.0)
+}
+
+/// Returns the BNG length of a record's pipe geometry in metres, or `None`
+/// if it has no measurable line: missing or non-linear geometry, or no part
+/// with at least two vertices.
+pub(crate) fn measured_length_m<T: PipelineData>(record: &T, crs: Crs) -> Option<f64> {
+    let geometry = record.geo_shape().geometry.as_ref()?;
+
+    match &geometry.value {
+        GeoJsonValue::LineString(coords) => bng_length(coords, crs),
+        GeoJsonValue::MultiLineString(lines) => lines
+            .iter()
+            .filter_map(|l| bng_length(l, crs))
+            .reduce(|a, b| a + b),
+        _ => None,
+    }
+}
+
+/// Splits a record's BNG line length between the hex cells it was assigned.
+///
+/// Each segment is clipped to each cell's hexagon and the clipped lengths
+/// are summed per cell id, so a pipe's lengths across all of its cells add
+/// up to its [`pipe_length_m`] (less any part lying in cells not passed in,
+/// e.g. ones dropped by a boundary filter). Points and other non-linear
+/// geometry contribute nothing.
+pub(crate) fn length_per_cell<T: PipelineData>(
+    record: &T,
+    cells: &[HexCell],
+    crs: Crs,
+) -> HashMap<String, f64> {
+    let mut lengths = HashMap::new();
+    let Some(geometry) = record.geo_shape().geometry.as_ref() else {
+        return lengths;
+    };
+    let parts: Vec<Vec<Coord<f64>>> = match &geometry.value {
+        GeoJsonValue::LineString(coords) => vec![bng_points(coords, crs)],
+        GeoJsonValue::MultiLineString(lines) => lines.iter().map(|l| bng_points(l, crs)).collect(),
+        _ => return lengths,
+    };
+
+    for cell in cells {
+        if lengths.contains_key(&cell.id) {
+            continue;
+        }
+        let ring: Vec<Coord<f64>> = cell.to_polygon().exterior().0.clone();
+        let length = parts
+            .iter()
+            .flat_map(|p| p.windows(2))
+            .map(|w| clipped_length(w[0], w[1], &ring))
+            .sum();
+        lengths.insert(cell.id.clone(), length);
+    }
+
+    lengths
+}
+
+/// Length of the segment `a`–`b` lying inside the convex closed `ring`,
+/// found by Cyrus–Beck clipping against each edge.
+fn clipped_length(a: Coord<f64>, b: Coord<f64>, ring: &[Coord<f64>]) -> f64 {
+    let cross = |u: Coord<f64>, v: Coord<f64>| u.x * v.y - u.y * v.x;
+    // Inside is to the left of each edge for counter-clockwise rings
+    let orientation = ring
+        .windows(2)
+        .map(|w| cross(w[0], w[1]))
+        .sum::<f64>()
+        .signum();
+
+    let d = b - a;
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+    for w in ring.windows(2) {
+        let edge = w[1] - w[0];
+        let num = orientation * cross(edge, a - w[0]);
+        let den = orientation * cross(edge, d);
+        if den == 0.0 {
+            if num < 0.0 {
+                return 0.0;
+            }
+            continue;
+        }
+        let t = -num / den;
+        if den > 0.0 {
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+        if t0 >= t1 {
+            return 0.0;
+        }
+    }
+
+    (t1 - t0) * d.x.hypot(d.y)
+}
+
+/// Reprojects the valid vertices of a GeoJSON coordinate sequence from `crs`
+/// to BNG.
+fn bng_points(coords: &[Vec<f64>], crs: Crs) -> Vec<Coord<f64>> {
+    coords
+        .iter()
+        .filter(|c| c.len() >= 2)
+        .map(|c| crs.to_bng(Coord { x: c[0], y: c[1] }))
+        .collect()
+}
+
+/// Sums the BNG segment lengths of a GeoJSON coordinate sequence in `crs`.
+/// Returns `None` if it has fewer than two valid vertices.
+fn bng_length(coords: &[Vec<f64>], crs: Crs) -> Option<f64> {
+    let points = bng_points(coords, crs);
+
+    if points.len() < 2 {
+        return None;
+    }
+
+    Some(
+        points
+            .windows(2)
+            .map(|w| (w[1].x - w[0].x).hypot(w[1].y - w[0].y))
+            .sum(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::empty_record;
+    use geojson::{Feature, Geometry};
+
+    fn make_record(value: Option<GeoJsonValue>) -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            geo_shape: Feature {
+                geometry: value.map(Geometry::new),
+                ..Default::default()
+            },
+            ..empty_record()
+        }
+    }
+
+    #[test]
+    fn test_pipe_length_m_north_south_line() {
+        // 0.001 degrees of latitude is roughly 111 metres
+        let record = make_record(Some(GeoJsonValue::LineString(vec![
+            vec![-2.248, 53.480],
+            vec![-2.248, 53.481],
+        ])));
+
+        let length = pipe_length_m(&record);
+        assert!((length - 111.0).abs() < 2.0, "length {}", length);
+    }
+
+    #[test]
+    fn test_pipe_length_m_multilinestring_ignores_gaps() {
+        let part_a = vec![vec![-2.248, 53.480], vec![-2.248, 53.481]];
+        let part_b = vec![vec![-2.240, 53.480], vec![-2.240, 53.481]];
+        let single = pipe_length_m(&make_record(Some(GeoJsonValue::LineString(part_a.clone()))));
+        let multi = pipe_length_m(&make_record(Some(GeoJsonValue::MultiLineString(vec![
+            part_a, part_b,
+        ]))));
+
+        assert!((multi - 2.0 * single).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_clipped_length_either_winding() {
+        let square = |ring: [(f64, f64); 5]| -> Vec<Coord<f64>> {
+            ring.iter().map(|&(x, y)| Coord { x, y }).collect()
+        };
+        let ccw = square([
+            (0.0, 0.0),
+            (10.0, 0.0),
+            (10.0, 10.0),
+            (0.0, 10.0),
+            (0.0, 0.0),
+        ]);
+        let cw: Vec<Coord<f64>> = ccw.iter().rev().copied().collect();
+
+        for ring in [ccw, cw] {
+            let length = |a: (f64, f64), b: (f64, f64)| {
+                clipped_length(Coord { x: a.0, y: a.1 }, Coord { x: b.0, y: b.1 }, &ring)
+            };
+            assert!((length((-5.0, 5.0), (15.0, 5.0)) - 10.0).abs() < 1e-9);
+            assert!((length((5.0, 5.0), (15.0, 5.0)) - 5.0).abs() < 1e-9);
+            assert!((length((2.0, 2.0), (5.0, 6.0)) - 5.0).abs() < 1e-9);
+            assert_eq!(length((-5.0, 20.0), (15.0, 20.0)), 0.0);
+        }
+    }
+
+    #[test]
+    fn test_pipe_length_m_degenerate() {
+        assert_eq!(pipe_length_m(&make_record(None)), 0.0);
+        assert_eq!(
+            pipe_length_m(&make_record(Some(GeoJsonValue::LineString(vec![vec![
+                -2.248, 53.480
+            ]])))),
+            0.0
+        );
+    }
+}
@@ -0,0 +1,590 @@
+use arrow_array::{Array, Float64Array, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use arrow_schema::{DataType, Field, SchemaRef};
+use arrow_select::concat::concat_batches;
+use arrow_select::take::take;
+use n3gb_rs::HexCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+use crate::error::InfraHexError;
+
+use super::arrow::ColumnNames;
+use super::grouped::GroupBy;
+
+/// Derived columns that can't be recomputed from merged rows alone.
+const UNMERGEABLE_COLUMNS: [&str; 2] = ["count_normalised", "on_boundary"];
+
+/// Prefixes of the per-group count and dominant group columns added by the
+/// wide summaries (`to_hex_summary_by_type` and friends).
+const GROUP_COUNT_PREFIX: &str = "count_";
+const DOMINANT_PREFIX: &str = "dominant_";
+
+/// Merges hex summaries into one, summing `pipe_count` (and
+/// `total_length_m`, when present) per `hex_id`.
+///
+/// Use this to combine summaries of adjacent bboxes without re-running the
+/// pipeline over the merged area: cells on the seams appear once, with their
+/// counts added. Every input must share the same columns (names, types and
+/// CRS metadata), so summaries with and without geometry can't be mixed.
+///
+/// Grouped summaries keep one row per hex and group, as rows are keyed on
+/// `hex_id` plus any `pipe_type`, `material` or `pressure` column. Wide
+/// summaries have their `count_<group>` columns summed and their
+/// `dominant_<group>` column recomputed from the sums, and
+/// `share_of_region` is recomputed against the merged total. Other
+/// columns, including geometry, keep the value from the first input row
+/// seen for each key, giving a single polygon per hex.
+///
+/// Ungrouped rows are sorted by count descending, then hex id; grouped rows
+/// by hex id, then group. Returns `InfraHexError::Config` for an empty
+/// input, incompatible schemas, summaries computed at different zoom
+/// levels, a summed count that overflows `u32`, or summaries carrying
+/// `count_normalised` or `on_boundary`, which depend on the inputs' own
+/// extent and can't be merged.
+pub fn merge_hex_summaries(summaries: &[RecordBatch]) -> Result<RecordBatch, InfraHexError> {
+    merge_hex_summaries_with_columns(summaries, &ColumnNames::default())
+}
+
+/// Like [`merge_hex_summaries`], for summaries whose hex id and pipe count
+/// columns are named by `names`.
+pub fn merge_hex_summaries_with_columns(
+    summaries: &[RecordBatch],
+    names: &ColumnNames,
+) -> Result<RecordBatch, InfraHexError> {
+    let first = summaries
+        .first()
+        .ok_or_else(|| InfraHexError::Config("No summaries to merge".to_string()))?;
+    let schema = first.schema();
+
+    for (i, summary) in summaries.iter().enumerate() {
+        check_compatible(&schema, &summary.schema(), names)
+            .map_err(|e| InfraHexError::Config(format!("Summary {}: {}", i, e)))?;
+    }
+    if let Some(name) = UNMERGEABLE_COLUMNS
+        .iter()
+        .find(|name| schema.index_of(name).is_ok())
+    {
+        return Err(InfraHexError::Config(format!(
+            "Cannot merge summaries with a {} column; merge without it and recompute",
+            name
+        )));
+    }
+
+    let combined =
+        concat_batches(&schema, summaries).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+    let hex_index = schema.index_of(&names.hex_id).unwrap();
+    let hex_ids = string_column(&combined, hex_index);
+    check_single_zoom(hex_ids)?;
+
+    let rules: Vec<Rule> = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(i, field)| Rule::for_column(i, field, hex_index, names))
+        .collect();
+    let columns_with =
+        |rule: Rule| -> Vec<usize> { (0..rules.len()).filter(|&i| rules[i] == rule).collect() };
+    let group_columns: Vec<&StringArray> = columns_with(Rule::Key)
+        .into_iter()
+        .filter(|&i| i != hex_index)
+        .map(|i| string_column(&combined, i))
+        .collect();
+    let count_columns: Vec<(usize, &UInt32Array)> = columns_with(Rule::SumCount)
+        .into_iter()
+        .map(|i| (i, combined.column(i).as_any().downcast_ref().unwrap()))
+        .collect();
+    let length_columns: Vec<(usize, &Float64Array)> = columns_with(Rule::SumLength)
+        .into_iter()
+        .map(|i| (i, combined.column(i).as_any().downcast_ref().unwrap()))
+        .collect();
+    let key_of = |row: usize| {
+        let groups: Vec<Option<&str>> = group_columns
+            .iter()
+            .map(|g| g.is_valid(row).then(|| g.value(row)))
+            .collect();
+        (hex_ids.value(row), groups)
+    };
+
+    // Merged rows in order of first appearance, with their first input row
+    // and running sums (indexed like `count_columns` / `length_columns`)
+    let mut merged_rows: HashMap<(&str, Vec<Option<&str>>), usize> = HashMap::new();
+    let mut first_rows: Vec<usize> = Vec::new();
+    let mut counts: Vec<Vec<u32>> = vec![Vec::new(); count_columns.len()];
+    let mut lengths: Vec<Vec<f64>> = vec![Vec::new(); length_columns.len()];
+
+    for row in 0..combined.num_rows() {
+        let merged = match merged_rows.entry(key_of(row)) {
+            Entry::Occupied(entry) => *entry.get(),
+            Entry::Vacant(entry) => {
+                first_rows.push(row);
+                counts.iter_mut().for_each(|c| c.push(0));
+                lengths.iter_mut().for_each(|l| l.push(0.0));
+                *entry.insert(first_rows.len() - 1)
+            }
+        };
+
+        for ((i, column), sums) in count_columns.iter().zip(&mut counts) {
+            sums[merged] = sums[merged].checked_add(column.value(row)).ok_or_else(|| {
+                InfraHexError::Config(format!(
+                    "Merged {} of {} overflows u32",
+                    schema.field(*i).name(),
+                    hex_ids.value(row)
+                ))
+            })?;
+        }
+        for ((_, column), sums) in length_columns.iter().zip(&mut lengths) {
+            sums[merged] += column.value(row);
+        }
+    }
+
+    let sums_of = |index: usize| {
+        let position = count_columns.iter().position(|(i, _)| *i == index);
+        &counts[position.unwrap()]
+    };
+    let count_index = schema.index_of(&names.pipe_count).unwrap();
+    let pipe_counts = sums_of(count_index);
+
+    let mut order: Vec<usize> = (0..first_rows.len()).collect();
+    if group_columns.is_empty() {
+        order.sort_by(|&a, &b| {
+            let id = |m: usize| hex_ids.value(first_rows[m]);
+            pipe_counts[b]
+                .cmp(&pipe_counts[a])
+                .then_with(|| id(a).cmp(id(b)))
+        });
+    } else {
+        order.sort_by_key(|&m| key_of(first_rows[m]));
+    }
+
+    // Per-group counts of wide summaries, in schema (i.e. group) order
+    let group_counts: Vec<(&str, &Vec<u32>)> = count_columns
+        .iter()
+        .filter(|(i, _)| *i != count_index)
+        .filter_map(|(i, _)| {
+            let group = schema.field(*i).name().strip_prefix(GROUP_COUNT_PREFIX)?;
+            Some((group, sums_of(*i)))
+        })
+        .collect();
+    let total: u64 = pipe_counts.iter().map(|&c| u64::from(c)).sum();
+    let ordered_rows: UInt64Array = order.iter().map(|&m| Some(first_rows[m] as u64)).collect();
+
+    let columns = rules
+        .iter()
+        .enumerate()
+        .map(|(i, rule)| -> Result<Arc<dyn Array>, InfraHexError> {
+            Ok(match rule {
+                Rule::Key | Rule::First => take(combined.column(i), &ordered_rows, None)
+                    .map_err(|e| InfraHexError::Geometry(e.to_string()))?,
+                Rule::SumCount => {
+                    let sums = sums_of(i);
+                    Arc::new(
+                        order
+                            .iter()
+                            .map(|&m| Some(sums[m]))
+                            .collect::<UInt32Array>(),
+                    )
+                }
+                Rule::SumLength => {
+                    let position = length_columns.iter().position(|(l, _)| *l == i);
+                    let sums = &lengths[position.unwrap()];
+                    Arc::new(
+                        order
+                            .iter()
+                            .map(|&m| Some(sums[m]))
+                            .collect::<Float64Array>(),
+                    )
+                }
+                Rule::Share => Arc::new(
+                    order
+                        .iter()
+                        .map(|&m| Some(f64::from(pipe_counts[m]) / total as f64))
+                        .collect::<Float64Array>(),
+                ),
+                Rule::Dominant => {
+                    if group_counts.is_empty() {
+                        return Err(InfraHexError::Config(format!(
+                            "Cannot recompute {} without {}<group> columns",
+                            schema.field(i).name(),
+                            GROUP_COUNT_PREFIX
+                        )));
+                    }
+                    // Ties go to the group that sorts first, as when built
+                    Arc::new(
+                        order
+                            .iter()
+                            .map(|&m| {
+                                let max = group_counts.iter().map(|(_, c)| c[m]).max()?;
+                                group_counts
+                                    .iter()
+                                    .find(|(_, c)| c[m] == max)
+                                    .map(|(group, _)| *group)
+                            })
+                            .collect::<StringArray>(),
+                    )
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    RecordBatch::try_new(schema, columns).map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// How a summary column is combined across the rows merged into one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule {
+    /// Part of the merge key: `hex_id` or a grouped summary's group.
+    Key,
+    /// `pipe_count` or a per-group count, summed.
+    SumCount,
+    /// `total_length_m`, summed.
+    SumLength,
+    /// `share_of_region`, recomputed from the merged counts.
+    Share,
+    /// `dominant_<group>`, recomputed from the merged per-group counts.
+    Dominant,
+    /// Constant per key (geometry, area, ...), taken from the first row.
+    First,
+}
+
+impl Rule {
+    fn for_column(index: usize, field: &Field, hex_index: usize, names: &ColumnNames) -> Self {
+        let name = field.name().as_str();
+        let is_group = [GroupBy::PipeType, GroupBy::Material, GroupBy::Pressure]
+            .iter()
+            .any(|g| g.column_name() == name);
+
+        match field.data_type() {
+            _ if index == hex_index => Rule::Key,
+            DataType::Utf8 if is_group => Rule::Key,
+            DataType::Utf8 if name.starts_with(DOMINANT_PREFIX) => Rule::Dominant,
+            DataType::UInt32
+                if name == names.pipe_count || name.starts_with(GROUP_COUNT_PREFIX) =>
+            {
+                Rule::SumCount
+            }
+            DataType::Float64 if name == "total_length_m" => Rule::SumLength,
+            DataType::Float64 if name == "share_of_region" => Rule::Share,
+            _ => Rule::First,
+        }
+    }
+}
+
+/// Column `index` of `batch` as strings; the caller has checked its type.
+fn string_column(batch: &RecordBatch, index: usize) -> &StringArray {
+    batch
+        .column(index)
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .unwrap()
+}
+
+/// Checks that `other` has the same columns as `expected`, and that both
+/// have the hex id and pipe count columns of a hex summary, as named by
+/// `names`.
+pub(crate) fn check_compatible(
+    expected: &SchemaRef,
+    other: &SchemaRef,
+    names: &ColumnNames,
+) -> Result<(), String> {
+    let has = |name: &str, data_type: &DataType| {
+        expected
+            .field_with_name(name)
+            .is_ok_and(|f| f.data_type() == data_type)
+    };
+    if !has(&names.hex_id, &DataType::Utf8) || !has(&names.pipe_count, &DataType::UInt32) {
+        return Err(format!(
+            "not a hex summary: needs Utf8 {} and UInt32 {} columns",
+            names.hex_id, names.pipe_count
+        ));
+    }
+
+    if expected.fields() != other.fields() {
+        let names = |s: &SchemaRef| {
+            s.fields()
+                .iter()
+                .map(|f| f.name().as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        return Err(format!(
+            "incompatible schema: expected [{}], got [{}]",
+            names(expected),
+            names(other)
+        ));
+    }
+
+    Ok(())
+}
+
+/// Errors if the hex ids span more than one zoom level.
+fn check_single_zoom(hex_ids: &StringArray) -> Result<(), InfraHexError> {
+    let mut zoom = None;
+    for id in hex_ids.iter().flatten() {
+        let cell_zoom = HexCell::from_hex_id(id)?.zoom_level;
+        match zoom {
+            None => zoom = Some(cell_zoom),
+            Some(z) if z != cell_zoom => {
+                return Err(InfraHexError::Config(format!(
+                    "Cannot merge summaries at different zoom levels ({} and {})",
+                    z, cell_zoom
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::{
+        CountNormalisation, HexSummaryOptions, to_hex_summary, to_hex_summary_by_material_no_geom,
+        to_hex_summary_grouped_no_geom, to_hex_summary_no_geom, to_hex_summary_with_options,
+    };
+
+    fn total_count(batch: &RecordBatch) -> u32 {
+        batch
+            .column_by_name("pipe_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<UInt32Array>()
+            .unwrap()
+            .iter()
+            .flatten()
+            .sum()
+    }
+
+    fn total_length(batch: &RecordBatch) -> f64 {
+        batch
+            .column_by_name("total_length_m")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap()
+            .iter()
+            .flatten()
+            .sum()
+    }
+
+    #[test]
+    fn test_merge_sums_shared_cells() {
+        let line_a = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let line_b = vec![vec![-2.2484, 53.4804], vec![-2.2484, 53.4814]];
+        let summary_a = to_hex_summary(&[line_record(line_a.clone())], 10).unwrap();
+        let summary_b = to_hex_summary(&[line_record(line_b.clone())], 10).unwrap();
+        let both = to_hex_summary(&[line_record(line_a), line_record(line_b)], 10).unwrap();
+
+        let merged = merge_hex_summaries(&[summary_a.clone(), summary_b]).unwrap();
+
+        assert_eq!(merged.schema(), summary_a.schema());
+        assert_eq!(merged.num_rows(), both.num_rows());
+        assert_eq!(merged.column_by_name("geometry").unwrap().null_count(), 0);
+        assert_eq!(total_count(&merged), total_count(&both));
+        assert!((total_length(&merged) - total_length(&both)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_merge_rejects_mismatched_columns() {
+        let records = vec![line_record(vec![
+            vec![-2.2484, 53.4804],
+            vec![-2.2502, 53.4806],
+        ])];
+        let with_geom = to_hex_summary(&records, 10).unwrap();
+        let without = to_hex_summary_no_geom(&records, 10).unwrap();
+
+        let result = merge_hex_summaries(&[with_geom, without]);
+        match result {
+            Err(InfraHexError::Config(message)) => {
+                assert!(message.contains("Summary 1"), "{}", message);
+                assert!(message.contains("incompatible schema"), "{}", message);
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_rejects_mixed_zoom() {
+        let records = vec![line_record(vec![
+            vec![-2.2484, 53.4804],
+            vec![-2.2502, 53.4806],
+        ])];
+        let result = merge_hex_summaries(&[
+            to_hex_summary_no_geom(&records, 10).unwrap(),
+            to_hex_summary_no_geom(&records, 11).unwrap(),
+        ]);
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_merge_with_renamed_columns() {
+        let records = vec![line_record(vec![
+            vec![-2.2484, 53.4804],
+            vec![-2.2502, 53.4806],
+        ])];
+        let names = ColumnNames::default()
+            .with_hex_id("cell")
+            .with_pipe_count("pipes");
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_column_names(names.clone());
+        let summary = to_hex_summary_with_options(&records, 10, &(), &options).unwrap();
+
+        assert!(merge_hex_summaries(&[summary.clone(), summary.clone()]).is_err());
+
+        let merged =
+            merge_hex_summaries_with_columns(&[summary.clone(), summary.clone()], &names).unwrap();
+        assert_eq!(merged.num_rows(), summary.num_rows());
+        let pipes = |batch: &RecordBatch| -> u32 {
+            batch
+                .column_by_name("pipes")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<UInt32Array>()
+                .unwrap()
+                .iter()
+                .flatten()
+                .sum()
+        };
+        assert_eq!(pipes(&merged), 2 * pipes(&summary));
+    }
+
+    #[test]
+    fn test_merge_grouped_keys_on_hex_and_group() {
+        let line = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let typed = |pipe_type: &str| CadentPipelineRecord {
+            pipe_type: Some(pipe_type.to_string()),
+            ..line_record(line.clone())
+        };
+        let summary_a =
+            to_hex_summary_grouped_no_geom(&[typed("LP"), typed("MP")], 10, GroupBy::PipeType)
+                .unwrap();
+        let summary_b =
+            to_hex_summary_grouped_no_geom(&[typed("LP")], 10, GroupBy::PipeType).unwrap();
+        let both = to_hex_summary_grouped_no_geom(
+            &[typed("LP"), typed("MP"), typed("LP")],
+            10,
+            GroupBy::PipeType,
+        )
+        .unwrap();
+
+        let merged = merge_hex_summaries(&[summary_a, summary_b]).unwrap();
+
+        assert_eq!(merged, both);
+    }
+
+    #[test]
+    fn test_merge_wide_recomputes_dominant() {
+        let line_a = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let line_b = vec![vec![-2.2484, 53.4804], vec![-2.2484, 53.4814]];
+        let made_of = |coords: &Vec<Vec<f64>>, material: &str| CadentPipelineRecord {
+            material: Some(material.to_string()),
+            ..line_record(coords.clone())
+        };
+        let first = || vec![made_of(&line_a, "PE"), made_of(&line_b, "ST")];
+        let second = || {
+            vec![
+                made_of(&line_a, "ST"),
+                made_of(&line_a, "ST"),
+                made_of(&line_b, "PE"),
+            ]
+        };
+        let all: Vec<_> = first().into_iter().chain(second()).collect();
+
+        let merged = merge_hex_summaries(&[
+            to_hex_summary_by_material_no_geom(&first(), 10).unwrap(),
+            to_hex_summary_by_material_no_geom(&second(), 10).unwrap(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            merged,
+            to_hex_summary_by_material_no_geom(&all, 10).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_merge_recomputes_share_of_region() {
+        let line_a = vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]];
+        let line_b = vec![vec![-2.2484, 53.4804], vec![-2.2484, 53.4814]];
+        let options = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_total_length(false)
+            .with_share_of_region(true);
+        let summarise = |records: &[CadentPipelineRecord]| {
+            to_hex_summary_with_options(records, 10, &(), &options).unwrap()
+        };
+
+        let merged = merge_hex_summaries(&[
+            summarise(&[line_record(line_a.clone())]),
+            summarise(&[line_record(line_b.clone())]),
+        ])
+        .unwrap();
+
+        // `to_hex_summary` breaks count ties in hash order, so compare per hex
+        let shares = |batch: &RecordBatch| -> HashMap<String, f64> {
+            let ids = string_column(batch, 0);
+            let shares = batch
+                .column_by_name("share_of_region")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+            (0..batch.num_rows())
+                .map(|i| (ids.value(i).to_string(), shares.value(i)))
+                .collect()
+        };
+        let both = summarise(&[line_record(line_a), line_record(line_b)]);
+        assert_eq!(merged.num_rows(), both.num_rows());
+        assert_eq!(shares(&merged), shares(&both));
+        assert!((shares(&merged).values().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge_rejects_unmergeable_columns() {
+        let records = vec![line_record(vec![
+            vec![-2.2484, 53.4804],
+            vec![-2.2502, 53.4806],
+        ])];
+        let normalised = HexSummaryOptions::new()
+            .with_geometry(false)
+            .with_count_normalisation(CountNormalisation::Linear);
+        let summary = to_hex_summary_with_options(&records, 10, &(), &normalised).unwrap();
+
+        match merge_hex_summaries(&[summary.clone(), summary]) {
+            Err(InfraHexError::Config(message)) => {
+                assert!(message.contains("count_normalised"), "{}", message)
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_rejects_count_overflow() {
+        let records = vec![line_record(vec![
+            vec![-2.2484, 53.4804],
+            vec![-2.2484, 53.4805],
+        ])];
+        let summary = to_hex_summary_no_geom(&records, 10).unwrap();
+        let index = summary.schema().index_of("pipe_count").unwrap();
+        let mut columns = summary.columns().to_vec();
+        columns[index] = Arc::new(UInt32Array::from(vec![u32::MAX; summary.num_rows()]));
+        let saturated = RecordBatch::try_new(summary.schema(), columns).unwrap();
+
+        match merge_hex_summaries(&[saturated, summary]) {
+            Err(InfraHexError::Config(message)) => {
+                assert!(message.contains("overflows"), "{}", message)
+            }
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_empty_input() {
+        assert!(merge_hex_summaries(&[]).is_err());
+    }
+}
@@ -1,14 +1,86 @@
+mod aggregator;
+mod annotate;
 mod arrow;
+mod batch;
+mod coverage;
+mod ewkb;
+mod filter;
 mod geometry;
+mod grouped;
+#[cfg(feature = "h3")]
+mod h3;
 mod hex;
+mod length;
+mod merge;
+mod mvt;
 mod parquet;
+mod pipeline;
+mod reproject;
+mod schema;
+mod sink;
+mod wkt;
+mod zoom;
 
+pub use aggregator::HexAggregator;
+pub use annotate::{annotate_hexes_with_areas, annotate_hexes_with_areas_with_columns};
 pub use arrow::{
-    to_hex_summary, to_hex_summary_for_multipolygon, to_hex_summary_for_multipolygon_no_geom,
-    to_hex_summary_for_polygon, to_hex_summary_for_polygon_no_geom, to_hex_summary_no_geom,
-    to_record_batch, to_record_batch_for_multipolygon, to_record_batch_for_multipolygon_no_geom,
-    to_record_batch_for_polygon, to_record_batch_for_polygon_no_geom, to_record_batch_no_geom,
-};
-pub use geometry::{FromGeoJson, ToGeoJson};
-pub use hex::get_hex_cells;
-pub use parquet::write_geoparquet;
+    BoundaryFilter, ColumnNames, ComputeProgress, CountNormalisation, HexIdFilter,
+    HexSummaryOptions, PROGRESS_CHUNK_SIZE, hex_summary_schema, to_hex_summary,
+    to_hex_summary_buffered_m, to_hex_summary_for_multipolygon,
+    to_hex_summary_for_multipolygon_no_geom, to_hex_summary_for_polygon,
+    to_hex_summary_for_polygon_no_geom, to_hex_summary_no_geom, to_hex_summary_with_options,
+    to_hex_summary_with_progress, to_record_batch, to_record_batch_for_multipolygon,
+    to_record_batch_for_multipolygon_no_geom, to_record_batch_for_polygon,
+    to_record_batch_for_polygon_no_geom, to_record_batch_no_geom, to_record_batch_with_options,
+    to_record_batches, to_record_hex_pairs, to_record_hex_pairs_no_geom,
+    to_record_hex_pairs_with_options,
+};
+pub use batch::{estimate_batch_memory, geometry_column_index, would_fit};
+pub use coverage::{coverage_gaps, coverage_gaps_with_columns};
+pub use ewkb::{BNG_SRID, ToEwkb};
+pub use filter::{
+    dedup_by_asset_id, dedup_by_geometry, filter_by_ground, filter_by_material,
+    filter_by_min_diameter, filter_by_pipe_type, filter_records_by_min_length,
+};
+pub use geometry::{
+    FromGeoJson, LineStringZ, PointZ, RingOrientation, ToGeoJson, normalise_winding,
+};
+pub use grouped::{
+    GroupBy, to_hex_summary_by_material, to_hex_summary_by_material_no_geom,
+    to_hex_summary_by_type, to_hex_summary_by_type_no_geom, to_hex_summary_grouped,
+    to_hex_summary_grouped_no_geom,
+};
+#[cfg(feature = "h3")]
+pub use h3::h3_resolution_for_zoom;
+pub use hex::{
+    HexCellOptions, get_hex_cells, get_hex_cells_buffered_m, get_hex_cells_with_crs,
+    get_hex_cells_with_options, parse_hex_id,
+};
+pub use length::pipe_length_m;
+pub use merge::{merge_hex_summaries, merge_hex_summaries_with_columns};
+pub use mvt::{
+    MVT_LAYER_NAME, encode_mvt, encode_mvt_with_columns, write_mvt, write_mvt_with_columns,
+};
+pub use parquet::{
+    GeoParquetBatchWriter, GeoParquetOptions, ParquetCompression, SCHEMA_VERSION,
+    SCHEMA_VERSION_KEY, read_and_merge_geoparquet, read_geoparquet, read_geoparquet_batches,
+    read_geoparquet_lenient, write_geoparquet, write_geoparquet_batches, write_geoparquet_parallel,
+    write_geoparquet_parallel_to_writer, write_geoparquet_parallel_to_writer_with_options,
+    write_geoparquet_parallel_with_options, write_geoparquet_to_bytes, write_geoparquet_to_writer,
+    write_geoparquet_with_options,
+};
+pub use pipeline::{StreamFetchReport, stream_fetch_to_parquet};
+pub use reproject::{
+    Crs, bng_to_wgs84, multipolygon_bng_to_wgs84, multipolygon_wgs84_to_bng, polygon_bng_to_wgs84,
+    polygon_wgs84_to_bng, wgs84_to_bng,
+};
+pub use schema::schema_to_json;
+pub use sink::{
+    CsvSink, GeoJsonSink, OutputFormat, ParquetSink, SummarySink, format_from_extension,
+    hex_summary_to_geojson, hex_summary_to_geojson_wgs84,
+    hex_summary_to_geojson_wgs84_with_columns, hex_summary_to_geojson_with_columns,
+    write_hex_summary_csv, write_summary_stdout, write_summary_stdout_with_columns,
+    write_summary_to_writer, write_summary_to_writer_with_columns,
+};
+pub use wkt::{ToWkt, hexcell_to_wkt, hexcells_to_wkt_lines};
+pub use zoom::{CellDimensions, estimate_cell_count, hex_cell_dimensions, suggest_zoom};
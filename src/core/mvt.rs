@@ -0,0 +1,478 @@
+use arrow_array::{Array, RecordBatch, UInt32Array};
+use geo_types::Coord;
+use n3gb_rs::HexCell;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::error::InfraHexError;
+
+use super::arrow::ColumnNames;
+use super::reproject::polygon_bng_to_wgs84;
+
+/// Name of the single layer written by [`encode_mvt`].
+pub const MVT_LAYER_NAME: &str = "hexes";
+
+/// Tile coordinate extent, the MVT default.
+const EXTENT: u32 = 4096;
+
+/// Tile-unit buffer kept around the tile when clipping, so polygon edges
+/// don't show seams where neighbouring tiles meet.
+const CLIP_BUFFER: f64 = 64.0;
+
+/// MVT geometry type code for polygons.
+const GEOM_POLYGON: u64 = 3;
+
+/// MVT geometry command ids.
+const CMD_MOVE_TO: u32 = 1;
+const CMD_LINE_TO: u32 = 2;
+const CMD_CLOSE_PATH: u32 = 7;
+
+/// Protobuf wire types.
+const WIRE_VARINT: u8 = 0;
+const WIRE_LEN: u8 = 2;
+
+// =============================================================================
+// Public API
+// =============================================================================
+
+/// Encodes a hex summary as a single Mapbox Vector Tile (`z`/`x`/`y`, XYZ
+/// scheme) and writes it to `path`.
+///
+/// See [`encode_mvt`].
+pub fn write_mvt(
+    batch: &RecordBatch,
+    z: u8,
+    x: u32,
+    y: u32,
+    path: impl AsRef<Path>,
+) -> Result<(), InfraHexError> {
+    write_mvt_with_columns(batch, z, x, y, &ColumnNames::default(), path)
+}
+
+/// Like [`write_mvt`], for a summary with the column names in `names`.
+///
+/// See [`encode_mvt_with_columns`].
+pub fn write_mvt_with_columns(
+    batch: &RecordBatch,
+    z: u8,
+    x: u32,
+    y: u32,
+    names: &ColumnNames,
+    path: impl AsRef<Path>,
+) -> Result<(), InfraHexError> {
+    let tile = encode_mvt_with_columns(batch, z, x, y, names)?;
+    std::fs::write(path, tile).map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Encodes a hex summary as a single Mapbox Vector Tile (`z`/`x`/`y`, XYZ
+/// scheme), ready to serve to MapLibre or Mapbox GL.
+///
+/// Each row's cell is rebuilt from its `hex_id`, reprojected from BNG to Web
+/// Mercator and clipped to the tile; cells outside the tile are skipped. The
+/// tile holds one layer, [`MVT_LAYER_NAME`], whose features carry `hex_id`
+/// and (when present) `pipe_count` attributes. Output is uncompressed; gzip
+/// it if the server expects compressed tiles.
+///
+/// Returns `InfraHexError::Config` if the tile coordinates are out of range
+/// for `z` or the batch has no `hex_id` column.
+pub fn encode_mvt(batch: &RecordBatch, z: u8, x: u32, y: u32) -> Result<Vec<u8>, InfraHexError> {
+    encode_mvt_with_columns(batch, z, x, y, &ColumnNames::default())
+}
+
+/// Like [`encode_mvt`], for a summary whose hex id and pipe count columns
+/// are named by `names`. The feature attributes take the same names.
+pub fn encode_mvt_with_columns(
+    batch: &RecordBatch,
+    z: u8,
+    x: u32,
+    y: u32,
+    names: &ColumnNames,
+) -> Result<Vec<u8>, InfraHexError> {
+    if z > 30 || u64::from(x) >= 1 << z || u64::from(y) >= 1 << z {
+        return Err(InfraHexError::Config(format!(
+            "Tile {}/{}/{} is out of range",
+            z, x, y
+        )));
+    }
+
+    let hex_ids = names.hex_ids(batch)?;
+    let pipe_counts = batch
+        .column_by_name(&names.pipe_count)
+        .and_then(|c| c.as_any().downcast_ref::<UInt32Array>());
+
+    let mut layer = LayerBuilder::default();
+    for row in 0..batch.num_rows() {
+        if hex_ids.is_null(row) {
+            continue;
+        }
+        let hex_id = hex_ids.value(row);
+        let cell = HexCell::from_hex_id(hex_id)?;
+        let polygon = polygon_bng_to_wgs84(&cell.to_polygon());
+
+        let ring: Vec<(f64, f64)> = polygon
+            .exterior()
+            .coords()
+            .map(|c| tile_coord(*c, z, x, y))
+            .collect();
+        let Some(geometry) = encode_polygon(&clip_ring(&ring)) else {
+            continue;
+        };
+
+        let mut tags = vec![0, layer.value(Value::String(hex_id.to_string()))];
+        if let Some(counts) = pipe_counts.filter(|c| !c.is_null(row)) {
+            tags.extend([1, layer.value(Value::Uint(u64::from(counts.value(row))))]);
+        }
+        layer.features.push(encode_feature(&tags, &geometry));
+    }
+
+    let mut keys = vec![names.hex_id.as_str()];
+    if pipe_counts.is_some() {
+        keys.push(names.pipe_count.as_str());
+    }
+
+    let mut tile = Vec::new();
+    write_bytes(&mut tile, 3, &layer.encode(&keys));
+    Ok(tile)
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+/// A layer attribute value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Value {
+    String(String),
+    Uint(u64),
+}
+
+/// Accumulates a layer's features and de-duplicated attribute values.
+#[derive(Default)]
+struct LayerBuilder {
+    features: Vec<Vec<u8>>,
+    values: Vec<Value>,
+    value_index: HashMap<Value, u32>,
+}
+
+impl LayerBuilder {
+    /// Returns the index of `value` in the layer's value table, adding it if new.
+    fn value(&mut self, value: Value) -> u32 {
+        if let Some(&index) = self.value_index.get(&value) {
+            return index;
+        }
+        let index = self.values.len() as u32;
+        self.values.push(value.clone());
+        self.value_index.insert(value, index);
+        index
+    }
+
+    fn encode(&self, keys: &[&str]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_varint_field(&mut buf, 15, 2);
+        write_bytes(&mut buf, 1, MVT_LAYER_NAME.as_bytes());
+        for feature in &self.features {
+            write_bytes(&mut buf, 2, feature);
+        }
+        for key in keys {
+            write_bytes(&mut buf, 3, key.as_bytes());
+        }
+        for value in &self.values {
+            let mut encoded = Vec::new();
+            match value {
+                Value::String(s) => write_bytes(&mut encoded, 1, s.as_bytes()),
+                Value::Uint(n) => write_varint_field(&mut encoded, 5, *n),
+            }
+            write_bytes(&mut buf, 4, &encoded);
+        }
+        write_varint_field(&mut buf, 5, u64::from(EXTENT));
+        buf
+    }
+}
+
+/// Projects a WGS84 coordinate to Web Mercator, in units of tile `z`/`x`/`y`
+/// (0..EXTENT across the tile, y pointing down).
+fn tile_coord(coord: Coord<f64>, z: u8, x: u32, y: u32) -> (f64, f64) {
+    let n = f64::from(1u32 << z.min(31)) * f64::from(EXTENT);
+    let lat = coord.y.to_radians();
+    let world_x = (coord.x + 180.0) / 360.0 * n;
+    let world_y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * n;
+    (
+        world_x - f64::from(x) * f64::from(EXTENT),
+        world_y - f64::from(y) * f64::from(EXTENT),
+    )
+}
+
+/// Clips a closed ring to the buffered tile square (Sutherland–Hodgman).
+///
+/// Exact for convex rings such as hex cells. The result is open (the first
+/// vertex is not repeated) and empty if the ring lies outside the tile.
+fn clip_ring(ring: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let (min, max) = (-CLIP_BUFFER, f64::from(EXTENT) + CLIP_BUFFER);
+    let mut points: Vec<(f64, f64)> = match ring.split_last() {
+        Some((last, rest)) if ring.first() == Some(last) => rest.to_vec(),
+        _ => ring.to_vec(),
+    };
+
+    // Each edge: is a point inside, and where a segment crosses the edge
+    type Inside = fn((f64, f64), f64) -> bool;
+    let edges: [(Inside, f64, bool); 4] = [
+        (|p, v| p.0 >= v, min, true),
+        (|p, v| p.0 <= v, max, true),
+        (|p, v| p.1 >= v, min, false),
+        (|p, v| p.1 <= v, max, false),
+    ];
+
+    for (inside, bound, vertical) in edges {
+        let input = std::mem::take(&mut points);
+        let Some(&last) = input.last() else {
+            break;
+        };
+        let intersect = |a: (f64, f64), b: (f64, f64)| {
+            if vertical {
+                let t = (bound - a.0) / (b.0 - a.0);
+                (bound, a.1 + t * (b.1 - a.1))
+            } else {
+                let t = (bound - a.1) / (b.1 - a.1);
+                (a.0 + t * (b.0 - a.0), bound)
+            }
+        };
+
+        let mut prev = last;
+        for &point in &input {
+            match (inside(point, bound), inside(prev, bound)) {
+                (true, true) => points.push(point),
+                (true, false) => points.extend([intersect(prev, point), point]),
+                (false, true) => points.push(intersect(prev, point)),
+                (false, false) => {}
+            }
+            prev = point;
+        }
+    }
+
+    points
+}
+
+/// Encodes an open ring as MVT polygon commands, rounding to integer tile
+/// coordinates and winding it as an exterior ring. Returns `None` if fewer
+/// than three distinct vertices survive rounding.
+fn encode_polygon(ring: &[(f64, f64)]) -> Option<Vec<u32>> {
+    let mut points: Vec<(i64, i64)> = Vec::with_capacity(ring.len());
+    for &(x, y) in ring {
+        let point = (x.round() as i64, y.round() as i64);
+        if points.last() != Some(&point) {
+            points.push(point);
+        }
+    }
+    while points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return None;
+    }
+
+    // Exterior rings have positive area with y pointing down
+    let area: i64 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    if area == 0 {
+        return None;
+    }
+    if area < 0 {
+        points.reverse();
+    }
+
+    let mut commands = vec![command(CMD_MOVE_TO, 1)];
+    let mut cursor = (0, 0);
+    for (i, &point) in points.iter().enumerate() {
+        if i == 1 {
+            commands.push(command(CMD_LINE_TO, points.len() as u32 - 1));
+        }
+        commands.push(zigzag(point.0 - cursor.0));
+        commands.push(zigzag(point.1 - cursor.1));
+        cursor = point;
+    }
+    commands.push(command(CMD_CLOSE_PATH, 1));
+    Some(commands)
+}
+
+fn encode_feature(tags: &[u32], geometry: &[u32]) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write_packed(&mut buf, 2, tags);
+    write_varint_field(&mut buf, 3, GEOM_POLYGON);
+    write_packed(&mut buf, 4, geometry);
+    buf
+}
+
+fn command(id: u32, count: u32) -> u32 {
+    (id & 0x7) | (count << 3)
+}
+
+fn zigzag(n: i64) -> u32 {
+    ((n << 1) ^ (n >> 63)) as u32
+}
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn write_tag(buf: &mut Vec<u8>, field: u32, wire_type: u8) {
+    write_varint(buf, (u64::from(field) << 3) | u64::from(wire_type));
+}
+
+fn write_varint_field(buf: &mut Vec<u8>, field: u32, value: u64) {
+    write_tag(buf, field, WIRE_VARINT);
+    write_varint(buf, value);
+}
+
+fn write_bytes(buf: &mut Vec<u8>, field: u32, bytes: &[u8]) {
+    write_tag(buf, field, WIRE_LEN);
+    write_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+fn write_packed(buf: &mut Vec<u8>, field: u32, values: &[u32]) {
+    let mut packed = Vec::new();
+    for &value in values {
+        write_varint(&mut packed, u64::from(value));
+    }
+    write_bytes(buf, field, &packed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::StringArray;
+    use arrow_schema::{DataType, Field, Schema};
+    use std::sync::Arc;
+
+    fn make_summary(hex_id: &str) -> RecordBatch {
+        make_summary_with_columns(hex_id, &ColumnNames::default())
+    }
+
+    fn make_summary_with_columns(hex_id: &str, names: &ColumnNames) -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new(&names.hex_id, DataType::Utf8, false),
+            Field::new(&names.pipe_count, DataType::UInt32, false),
+        ]);
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(StringArray::from(vec![hex_id])),
+                Arc::new(UInt32Array::from(vec![7])),
+            ],
+        )
+        .unwrap()
+    }
+
+    /// XYZ tile containing a WGS84 coordinate at zoom `z`.
+    fn tile_for(coord: Coord<f64>, z: u8) -> (u32, u32) {
+        let (px, py) = tile_coord(coord, z, 0, 0);
+        (
+            (px / f64::from(EXTENT)) as u32,
+            (py / f64::from(EXTENT)) as u32,
+        )
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn test_encode_mvt_includes_hex_in_its_tile() {
+        let cell = HexCell::from_wgs84(&geo_types::Point::new(-2.2484, 53.4804), 10).unwrap();
+        let summary = make_summary(&cell.id);
+        let exterior = polygon_bng_to_wgs84(&cell.to_polygon())
+            .exterior()
+            .0
+            .clone();
+        let (x, y) = tile_for(exterior[0], 14);
+
+        let tile = encode_mvt(&summary, 14, x, y).unwrap();
+        assert!(contains(&tile, MVT_LAYER_NAME.as_bytes()));
+        assert!(contains(&tile, cell.id.as_bytes()));
+        assert!(contains(&tile, b"pipe_count"));
+
+        // A tile on the other side of the world holds no features
+        let far = encode_mvt(&summary, 14, 0, 0).unwrap();
+        assert!(!contains(&far, cell.id.as_bytes()));
+    }
+
+    #[test]
+    fn test_encode_mvt_with_renamed_columns() {
+        let cell = HexCell::from_wgs84(&geo_types::Point::new(-2.2484, 53.4804), 10).unwrap();
+        let names = ColumnNames::default()
+            .with_hex_id("cell")
+            .with_pipe_count("pipes");
+        let summary = make_summary_with_columns(&cell.id, &names);
+        let exterior = polygon_bng_to_wgs84(&cell.to_polygon())
+            .exterior()
+            .0
+            .clone();
+        let (x, y) = tile_for(exterior[0], 14);
+
+        assert!(encode_mvt(&summary, 14, x, y).is_err());
+
+        let tile = encode_mvt_with_columns(&summary, 14, x, y, &names).unwrap();
+        assert!(contains(&tile, cell.id.as_bytes()));
+        assert!(contains(&tile, b"pipes"));
+    }
+
+    #[test]
+    fn test_encode_mvt_rejects_out_of_range_tile() {
+        let summary = make_summary("unused");
+        assert!(matches!(
+            encode_mvt(&summary, 2, 4, 0),
+            Err(InfraHexError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_clip_ring_to_tile() {
+        let inside = [(10.0, 10.0), (20.0, 10.0), (20.0, 20.0), (10.0, 10.0)];
+        assert_eq!(clip_ring(&inside).len(), 3);
+
+        let outside = [(-500.0, 0.0), (-400.0, 0.0), (-400.0, 100.0), (-500.0, 0.0)];
+        assert!(clip_ring(&outside).is_empty());
+
+        // A square straddling the left edge is cut at the buffer
+        let straddling = [
+            (-200.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 100.0),
+            (-200.0, 100.0),
+            (-200.0, 0.0),
+        ];
+        let clipped = clip_ring(&straddling);
+        assert!(clipped.iter().all(|p| p.0 >= -CLIP_BUFFER));
+        assert_eq!(clipped.len(), 4);
+    }
+
+    #[test]
+    fn test_encode_polygon_commands() {
+        // Counter-clockwise with y down, so it gets reversed to an exterior
+        let ring = [(0.0, 0.0), (0.0, 10.0), (10.0, 10.0), (10.0, 0.0)];
+        let commands = encode_polygon(&ring).unwrap();
+
+        assert_eq!(commands[0], command(CMD_MOVE_TO, 1));
+        assert_eq!(commands[3], command(CMD_LINE_TO, 3));
+        assert_eq!(*commands.last().unwrap(), command(CMD_CLOSE_PATH, 1));
+        assert_eq!(commands.len(), 1 + 2 + 1 + 3 * 2 + 1);
+
+        assert!(encode_polygon(&[(0.0, 0.0), (0.2, 0.2), (0.4, 0.1)]).is_none());
+    }
+
+    #[test]
+    fn test_zigzag() {
+        assert_eq!(zigzag(0), 0);
+        assert_eq!(zigzag(-1), 1);
+        assert_eq!(zigzag(1), 2);
+        assert_eq!(zigzag(-2), 3);
+    }
+}
@@ -1,44 +1,1120 @@
 use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, SchemaRef};
+use geoarrow_schema::CoordType;
+use geoparquet::reader::{GeoParquetReaderBuilder, GeoParquetRecordBatchReader};
 use geoparquet::writer::{
     GeoParquetRecordBatchEncoder, GeoParquetWriterEncoding, GeoParquetWriterOptionsBuilder,
 };
 use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReader,
+    ParquetRecordBatchReaderBuilder,
+};
+use parquet::basic::{Compression, GzipLevel, ZstdLevel};
+use parquet::file::metadata::KeyValue;
+use parquet::file::properties::WriterProperties;
+use rayon::prelude::*;
+use serde_json::Value;
 use std::fs::File;
+use std::io::Write;
 use std::path::Path;
 
 use crate::error::InfraHexError;
 
-/// Write a RecordBatch to GeoParquet with EPSG:27700 CRS
+use super::arrow::ColumnNames;
+use super::merge::{check_compatible, merge_hex_summaries};
+
+/// Version of the output schema written by this crate.
+///
+/// Bump this whenever a change to the `to_*` outputs would make older readers
+/// misinterpret columns. It is stored in the Parquet key-value metadata under
+/// [`SCHEMA_VERSION_KEY`] and checked by [`read_geoparquet`].
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Parquet key-value metadata key holding [`SCHEMA_VERSION`].
+pub const SCHEMA_VERSION_KEY: &str = "infra_hex_rs:schema_version";
+
+/// Compression codec for GeoParquet output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetCompression {
+    Uncompressed,
+    Snappy,
+    /// Gzip at a level from 0 to 10.
+    Gzip(u32),
+    /// Zstandard at a level from 1 to 22.
+    Zstd(i32),
+}
+
+impl Default for ParquetCompression {
+    /// Zstd level 3: a good size/speed tradeoff for hex grids.
+    fn default() -> Self {
+        ParquetCompression::Zstd(3)
+    }
+}
+
+/// Options controlling how GeoParquet files are written.
+///
+/// Defaults to Zstd level 3 compression, dictionary encoding on, and the
+/// Parquet writer's default row group size.
+#[derive(Debug, Clone)]
+pub struct GeoParquetOptions {
+    pub compression: ParquetCompression,
+    pub row_group_size: Option<usize>,
+    pub dictionary_enabled: bool,
+}
+
+impl Default for GeoParquetOptions {
+    fn default() -> Self {
+        Self {
+            compression: ParquetCompression::default(),
+            row_group_size: None,
+            dictionary_enabled: true,
+        }
+    }
+}
+
+impl GeoParquetOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the compression codec.
+    pub fn with_compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Caps the number of rows per row group.
+    pub fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = Some(row_group_size);
+        self
+    }
+
+    /// Enables or disables dictionary encoding. Dictionaries shrink
+    /// repetitive columns such as `material`, but cost space on unique
+    /// ones such as `hex_id`.
+    pub fn with_dictionary(mut self, enabled: bool) -> Self {
+        self.dictionary_enabled = enabled;
+        self
+    }
+
+    /// Builds the Parquet writer properties for these options.
+    ///
+    /// Returns `InfraHexError::Config` for an out-of-range compression level
+    /// or a zero row group size.
+    fn writer_properties(&self) -> Result<WriterProperties, InfraHexError> {
+        let level_error = |e: parquet::errors::ParquetError| InfraHexError::Config(e.to_string());
+        let compression = match self.compression {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip(level) => {
+                Compression::GZIP(GzipLevel::try_new(level).map_err(level_error)?)
+            }
+            ParquetCompression::Zstd(level) => {
+                Compression::ZSTD(ZstdLevel::try_new(level).map_err(level_error)?)
+            }
+        };
+
+        let mut builder = WriterProperties::builder()
+            .set_compression(compression)
+            .set_dictionary_enabled(self.dictionary_enabled);
+        if let Some(size) = self.row_group_size {
+            if size == 0 {
+                return Err(InfraHexError::Config(
+                    "row_group_size must be greater than zero".to_string(),
+                ));
+            }
+            builder = builder.set_max_row_group_size(size);
+        }
+
+        Ok(builder.build())
+    }
+}
+
+/// Write a RecordBatch to GeoParquet with EPSG:27700 CRS, using the default
+/// [`GeoParquetOptions`] (Zstd level 3).
 pub fn write_geoparquet(batch: &RecordBatch, path: impl AsRef<Path>) -> Result<(), InfraHexError> {
-    let schema = batch.schema();
+    write_geoparquet_with_options(batch, path, &GeoParquetOptions::default())
+}
+
+/// Like [`write_geoparquet`], with explicit compression, row group size and
+/// dictionary encoding.
+pub fn write_geoparquet_with_options(
+    batch: &RecordBatch,
+    path: impl AsRef<Path>,
+    options: &GeoParquetOptions,
+) -> Result<(), InfraHexError> {
+    let file = File::create(path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+    let mut writer = GeoParquetBatchWriter::new(file).with_options(options.clone())?;
+    writer.write(batch)?;
+    writer.finish()
+}
+
+/// Write a RecordBatch as GeoParquet to any writer (a `Cursor`, socket, upload
+/// buffer, etc.) rather than a file path.
+pub fn write_geoparquet_to_writer<W: Write + Send>(
+    batch: &RecordBatch,
+    writer: W,
+) -> Result<(), InfraHexError> {
+    let mut writer = GeoParquetBatchWriter::new(writer);
+    writer.write(batch)?;
+    writer.finish()
+}
+
+/// Writes a sequence of RecordBatches to one GeoParquet file, one row group
+/// per batch.
+///
+/// Batches are encoded and written as the iterator yields them, so only one
+/// is held in memory at a time; pair with [`to_record_batches`] for areas
+/// too large to build as a single batch. All batches must share a schema.
+/// The GeoParquet metadata (bounding box and geometry types) covers every
+/// batch written. Returns `InfraHexError::Config` if the iterator is empty
+/// or a batch's schema differs from the first.
+///
+/// [`to_record_batches`]: crate::core::to_record_batches
+pub fn write_geoparquet_batches(
+    batches: impl IntoIterator<Item = RecordBatch>,
+    path: impl AsRef<Path>,
+) -> Result<(), InfraHexError> {
+    let file = File::create(path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+    let mut writer = GeoParquetBatchWriter::new(file);
+    for batch in batches {
+        writer.write(&batch)?;
+    }
+    writer.finish()
+}
+
+/// Incremental GeoParquet writer: call [`write`](Self::write) once per
+/// batch, then [`finish`](Self::finish) to write the footer.
+///
+/// The fallible counterpart to [`write_geoparquet_batches`], for batch
+/// sources that can fail part way (such as [`to_record_batches`]). The
+/// schema is taken from the first batch written, and each batch is flushed
+/// as its own row group. One geometry encoder sees every batch, so the
+/// bounding box and geometry types in the `geo` metadata cover them all.
+///
+/// [`to_record_batches`]: crate::core::to_record_batches
+pub struct GeoParquetBatchWriter<W: Write + Send> {
+    sink: Option<W>,
+    options: GeoParquetOptions,
+    state: Option<BatchWriterState<W>>,
+}
+
+struct BatchWriterState<W: Write + Send> {
+    schema: SchemaRef,
+    encoder: GeoParquetRecordBatchEncoder,
+    writer: ArrowWriter<W>,
+}
+
+impl<W: Write + Send> GeoParquetBatchWriter<W> {
+    /// Creates a writer over `sink` using the default [`GeoParquetOptions`].
+    /// Nothing is written until the first batch.
+    pub fn new(sink: W) -> Self {
+        Self {
+            sink: Some(sink),
+            options: GeoParquetOptions::default(),
+            state: None,
+        }
+    }
+
+    /// Sets the options used to write the file.
+    ///
+    /// Returns `InfraHexError::Config` once a batch has been written, since
+    /// the file's properties are fixed by then.
+    pub fn with_options(mut self, options: GeoParquetOptions) -> Result<Self, InfraHexError> {
+        if self.sink.is_none() {
+            return Err(InfraHexError::Config(
+                "GeoParquet options must be set before the first batch is written".to_string(),
+            ));
+        }
+        self.options = options;
+        Ok(self)
+    }
+
+    /// Encodes `batch` and writes it as a row group.
+    ///
+    /// Returns `InfraHexError::Config` if its schema differs from the first
+    /// batch written.
+    pub fn write(&mut self, batch: &RecordBatch) -> Result<(), InfraHexError> {
+        if self.state.is_none() {
+            let schema = batch.schema();
+            let options = GeoParquetWriterOptionsBuilder::default()
+                .set_encoding(GeoParquetWriterEncoding::WKB)
+                .build();
+            let encoder = GeoParquetRecordBatchEncoder::try_new(&schema, &options)
+                .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+            let properties = self.options.writer_properties()?;
+            let sink = self.sink.take().ok_or_else(|| {
+                InfraHexError::Config("GeoParquet writer already failed".to_string())
+            })?;
+            let writer = ArrowWriter::try_new(sink, encoder.target_schema(), Some(properties))
+                .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+            self.state = Some(BatchWriterState {
+                schema,
+                encoder,
+                writer,
+            });
+        }
+        let state = self.state.as_mut().unwrap();
+
+        if batch.schema() != state.schema {
+            return Err(InfraHexError::Config(
+                "batch schema differs from the first batch written".to_string(),
+            ));
+        }
+
+        let encoded_batch = state
+            .encoder
+            .encode_record_batch(batch)
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        state
+            .writer
+            .write(&encoded_batch)
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        state
+            .writer
+            .flush()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))
+    }
 
-    let options = GeoParquetWriterOptionsBuilder::default()
+    /// Writes the GeoParquet and schema version metadata and the file footer.
+    ///
+    /// Returns `InfraHexError::Config` if no batch was written, since the
+    /// file's schema comes from the first batch.
+    pub fn finish(self) -> Result<(), InfraHexError> {
+        let BatchWriterState {
+            encoder,
+            mut writer,
+            ..
+        } = self
+            .state
+            .ok_or_else(|| InfraHexError::Config("No batches to write".to_string()))?;
+
+        let kv_metadata = encoder
+            .into_keyvalue()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+        writer.append_key_value_metadata(kv_metadata);
+        writer.append_key_value_metadata(KeyValue::new(
+            SCHEMA_VERSION_KEY.to_string(),
+            SCHEMA_VERSION.to_string(),
+        ));
+        writer
+            .finish()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Like [`write_geoparquet`], but WKB-encodes the batch in parallel.
+///
+/// The batch is sliced into row groups of `row_group_size` rows, each slice
+/// is encoded on the rayon thread pool, and the encoded slices are then
+/// written in order, one row group each. This pays off for large summaries
+/// on multi-core machines; the file reads back the same as one written by
+/// [`write_geoparquet`], with the per-slice GeoParquet metadata (bounding
+/// box and geometry types) combined to cover the whole batch.
+///
+/// # Errors
+///
+/// Returns `InfraHexError::Config` if `row_group_size` is zero.
+pub fn write_geoparquet_parallel(
+    batch: &RecordBatch,
+    path: impl AsRef<Path>,
+    row_group_size: usize,
+) -> Result<(), InfraHexError> {
+    write_geoparquet_parallel_with_options(
+        batch,
+        path,
+        row_group_size,
+        &GeoParquetOptions::default(),
+    )
+}
+
+/// Like [`write_geoparquet_parallel`], with explicit compression and
+/// dictionary encoding. `row_group_size` takes precedence over the row
+/// group size in `options`.
+pub fn write_geoparquet_parallel_with_options(
+    batch: &RecordBatch,
+    path: impl AsRef<Path>,
+    row_group_size: usize,
+    options: &GeoParquetOptions,
+) -> Result<(), InfraHexError> {
+    let file = File::create(path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+    write_geoparquet_parallel_to_writer_with_options(batch, file, row_group_size, options)
+}
+
+/// Like [`write_geoparquet_to_writer`], encoding row groups in parallel as
+/// described by [`write_geoparquet_parallel`].
+pub fn write_geoparquet_parallel_to_writer<W: Write + Send>(
+    batch: &RecordBatch,
+    writer: W,
+    row_group_size: usize,
+) -> Result<(), InfraHexError> {
+    write_geoparquet_parallel_to_writer_with_options(
+        batch,
+        writer,
+        row_group_size,
+        &GeoParquetOptions::default(),
+    )
+}
+
+/// Like [`write_geoparquet_parallel_to_writer`], with explicit compression
+/// and dictionary encoding as for [`write_geoparquet_parallel_with_options`].
+pub fn write_geoparquet_parallel_to_writer_with_options<W: Write + Send>(
+    batch: &RecordBatch,
+    writer: W,
+    row_group_size: usize,
+    options: &GeoParquetOptions,
+) -> Result<(), InfraHexError> {
+    if row_group_size == 0 {
+        return Err(InfraHexError::Config(
+            "row_group_size must be greater than zero".to_string(),
+        ));
+    }
+    let options = options.clone().with_row_group_size(row_group_size);
+
+    // Nothing to parallelise
+    if batch.num_rows() <= row_group_size {
+        let mut writer = GeoParquetBatchWriter::new(writer).with_options(options)?;
+        writer.write(batch)?;
+        return writer.finish();
+    }
+
+    let schema = batch.schema();
+    let encoder_options = GeoParquetWriterOptionsBuilder::default()
         .set_encoding(GeoParquetWriterEncoding::WKB)
         .build();
 
-    let mut encoder = GeoParquetRecordBatchEncoder::try_new(&schema, &options)
-        .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+    let slices: Vec<RecordBatch> = (0..batch.num_rows())
+        .step_by(row_group_size)
+        .map(|offset| batch.slice(offset, row_group_size.min(batch.num_rows() - offset)))
+        .collect();
 
-    let file = File::create(path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
-    let mut writer = ArrowWriter::try_new(file, encoder.target_schema(), None)
+    // Each slice gets its own encoder, so each reports the bounding box and
+    // geometry types of its own rows only
+    let encoded = slices
+        .par_iter()
+        .map(|slice| {
+            let mut encoder = GeoParquetRecordBatchEncoder::try_new(&schema, &encoder_options)?;
+            let encoded = encoder.encode_record_batch(slice)?;
+            Ok((encoded, encoder.into_keyvalue()?))
+        })
+        .collect::<Result<Vec<_>, arrow_schema::ArrowError>>()
         .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
 
-    let encoded_batch = encoder
-        .encode_record_batch(batch)
+    let target_schema = encoded[0].0.schema();
+    let properties = options.writer_properties()?;
+    let mut writer = ArrowWriter::try_new(writer, target_schema, Some(properties))
         .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
 
+    let mut geo_metadata = Vec::with_capacity(encoded.len());
+    for (encoded_batch, kv) in encoded {
+        writer
+            .write(&encoded_batch)
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        writer
+            .flush()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        geo_metadata.push(kv);
+    }
+
+    writer.append_key_value_metadata(merge_geo_metadata(geo_metadata)?);
+    writer.append_key_value_metadata(KeyValue::new(
+        SCHEMA_VERSION_KEY.to_string(),
+        SCHEMA_VERSION.to_string(),
+    ));
     writer
-        .write(&encoded_batch)
+        .finish()
         .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
 
-    let kv_metadata = encoder
-        .into_keyvalue()
+    Ok(())
+}
+
+/// Combines the `geo` metadata written by several encoders into one entry
+/// covering all of their rows.
+///
+/// Each column's `bbox` becomes the union of the input boxes and its
+/// `geometry_types` the union of the input types; everything else is taken
+/// from the first entry.
+fn merge_geo_metadata(entries: Vec<KeyValue>) -> Result<KeyValue, InfraHexError> {
+    let mut entries = entries.into_iter();
+    let first = entries
+        .next()
+        .ok_or_else(|| InfraHexError::Config("no GeoParquet metadata to merge".to_string()))?;
+    let Some(first_value) = first.value.as_deref() else {
+        return Ok(first);
+    };
+
+    let mut merged: Value = serde_json::from_str(first_value)?;
+    for entry in entries {
+        let Some(value) = entry.value.as_deref() else {
+            continue;
+        };
+        let other: Value = serde_json::from_str(value)?;
+        let Some(columns) = other.get("columns").and_then(Value::as_object) else {
+            continue;
+        };
+
+        for (name, column) in columns {
+            let Some(target) = merged
+                .get_mut("columns")
+                .and_then(|c| c.get_mut(name))
+                .and_then(Value::as_object_mut)
+            else {
+                continue;
+            };
+
+            if let (Some(Value::Array(into)), Some(Value::Array(from))) =
+                (target.get_mut("bbox"), column.get("bbox"))
+                && into.len() == from.len()
+            {
+                let half = into.len() / 2;
+                for (i, (a, b)) in into.iter_mut().zip(from).enumerate() {
+                    if let (Some(x), Some(y)) = (a.as_f64(), b.as_f64()) {
+                        let bound = if i < half { x.min(y) } else { x.max(y) };
+                        *a = Value::from(bound);
+                    }
+                }
+            }
+
+            if let (Some(Value::Array(into)), Some(Value::Array(from))) = (
+                target.get_mut("geometry_types"),
+                column.get("geometry_types"),
+            ) {
+                for geometry_type in from {
+                    if !into.contains(geometry_type) {
+                        into.push(geometry_type.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(KeyValue::new(first.key, merged.to_string()))
+}
+
+/// Write a RecordBatch as GeoParquet into an in-memory byte buffer.
+pub fn write_geoparquet_to_bytes(batch: &RecordBatch) -> Result<Vec<u8>, InfraHexError> {
+    let mut buf = Vec::new();
+    write_geoparquet_to_writer(batch, &mut buf)?;
+    Ok(buf)
+}
+
+/// Read a GeoParquet file written by [`write_geoparquet`] back into RecordBatches.
+///
+/// WKB geometry columns are decoded back to native geoarrow arrays carrying
+/// the file's CRS metadata, so the batches match what was written and can
+/// be merged or re-aggregated directly. Files without GeoParquet metadata
+/// are read as plain Parquet.
+///
+/// Fails with `InfraHexError::Config` if the file was written with a different
+/// [`SCHEMA_VERSION`], rather than risk misinterpreting its columns. Files
+/// without a version (written before versioning was added) are accepted. Use
+/// [`read_geoparquet_lenient`] to skip the check.
+pub fn read_geoparquet(path: impl AsRef<Path>) -> Result<Vec<RecordBatch>, InfraHexError> {
+    read_geoparquet_impl(path.as_ref(), false)
+}
+
+/// Like [`read_geoparquet`], but reads files whatever their schema version.
+pub fn read_geoparquet_lenient(path: impl AsRef<Path>) -> Result<Vec<RecordBatch>, InfraHexError> {
+    read_geoparquet_impl(path.as_ref(), true)
+}
+
+fn read_geoparquet_impl(path: &Path, lenient: bool) -> Result<Vec<RecordBatch>, InfraHexError> {
+    let file = File::open(path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+    let builder = ParquetRecordBatchReaderBuilder::try_new(file)
         .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
 
-    writer.append_key_value_metadata(kv_metadata);
-    writer
-        .finish()
+    if !lenient {
+        check_schema_version(builder.metadata().file_metadata().key_value_metadata())?;
+    }
+
+    let target_schema = geoarrow_target_schema(&builder)?;
+    let reader = builder
+        .build()
         .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
 
-    Ok(())
+    decode_geometry(reader, target_schema)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Returns the schema a file's batches decode to, with WKB geometry columns
+/// parsed to native geoarrow types, or `None` if it has no GeoParquet
+/// metadata.
+fn geoarrow_target_schema(
+    builder: &ParquetRecordBatchReaderBuilder<File>,
+) -> Result<Option<SchemaRef>, InfraHexError> {
+    let Some(geo_metadata) = builder.geoparquet_metadata() else {
+        return Ok(None);
+    };
+    let geo_metadata = geo_metadata.map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+    builder
+        .geoarrow_schema(&geo_metadata, true, CoordType::default())
+        .map(Some)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Wraps `reader` to decode geometry into `target_schema`, or passes batches
+/// through unchanged when there is no target.
+fn decode_geometry(
+    reader: ParquetRecordBatchReader,
+    target_schema: Option<SchemaRef>,
+) -> Result<Box<dyn Iterator<Item = Result<RecordBatch, ArrowError>>>, InfraHexError> {
+    Ok(match target_schema {
+        Some(schema) => Box::new(
+            GeoParquetRecordBatchReader::try_new(reader, schema)
+                .map_err(|e| InfraHexError::Geometry(e.to_string()))?,
+        ),
+        None => Box::new(reader),
+    })
+}
+
+/// Reads a GeoParquet file lazily, yielding one RecordBatch per row group.
+///
+/// The streaming counterpart to [`read_geoparquet`]: only the row group being
+/// decoded is held in memory, so files larger than memory can be processed
+/// batch by batch, e.g. folded into a running merge. Batches come back in the
+/// same form as [`read_geoparquet`] returns them, with geometry decoded. The
+/// file's footer is read and its schema version checked up front; errors
+/// reading an individual row group are yielded by the iterator.
+pub fn read_geoparquet_batches(
+    path: impl AsRef<Path>,
+) -> Result<impl Iterator<Item = Result<RecordBatch, InfraHexError>>, InfraHexError> {
+    let file = File::open(path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+    let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::new())
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+    check_schema_version(metadata.metadata().file_metadata().key_value_metadata())?;
+
+    let target_schema = {
+        let file = file
+            .try_clone()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        geoarrow_target_schema(&ParquetRecordBatchReaderBuilder::new_with_metadata(
+            file,
+            metadata.clone(),
+        ))?
+    };
+    let num_row_groups = metadata.metadata().num_row_groups();
+
+    Ok((0..num_row_groups).map(move |i| {
+        let rows = metadata.metadata().row_group(i).num_rows().max(1) as usize;
+        let file = file
+            .try_clone()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+        // Size the batch to the row group so each group comes back whole
+        let reader = ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata.clone())
+            .with_row_groups(vec![i])
+            .with_batch_size(rows)
+            .build()
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+        match decode_geometry(reader, target_schema.clone())?.next() {
+            Some(batch) => batch.map_err(|e| InfraHexError::Geometry(e.to_string())),
+            None => Ok(RecordBatch::new_empty(
+                target_schema
+                    .clone()
+                    .unwrap_or_else(|| metadata.schema().clone()),
+            )),
+        }
+    }))
+}
+
+/// Reads several hex summary files written by [`write_geoparquet`] and merges
+/// them into one summary, summing `pipe_count` per cell.
+///
+/// Handy for rolling daily snapshots up into a single file. Each file is read
+/// with [`read_geoparquet`], so schema versions are checked. Fails with
+/// `InfraHexError::Config`, naming the offending file, if its columns differ
+/// from the first file's, and if the files were computed at different zoom
+/// levels.
+pub fn read_and_merge_geoparquet(paths: &[impl AsRef<Path>]) -> Result<RecordBatch, InfraHexError> {
+    let mut batches: Vec<RecordBatch> = Vec::new();
+
+    for path in paths {
+        let path = path.as_ref();
+        for batch in read_geoparquet(path)? {
+            if let Some(first) = batches.first() {
+                check_compatible(&first.schema(), &batch.schema(), &ColumnNames::default())
+                    .map_err(|e| InfraHexError::Config(format!("{}: {}", path.display(), e)))?;
+            }
+            batches.push(batch);
+        }
+    }
+
+    merge_hex_summaries(&batches)
+}
+
+/// Checks the schema version stored in a file's key-value metadata against
+/// [`SCHEMA_VERSION`].
+fn check_schema_version(kv_metadata: Option<&Vec<KeyValue>>) -> Result<(), InfraHexError> {
+    let file_version = kv_metadata
+        .into_iter()
+        .flatten()
+        .find(|kv| kv.key == SCHEMA_VERSION_KEY)
+        .and_then(|kv| kv.value.as_deref());
+
+    match file_version {
+        None => Ok(()),
+        Some(v) if v.trim().parse::<u32>() == Ok(SCHEMA_VERSION) => Ok(()),
+        Some(v) => Err(InfraHexError::Config(format!(
+            "schema version mismatch: file={}, supported={}",
+            v, SCHEMA_VERSION
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::to_hex_summary;
+    use arrow_array::StringArray;
+    use geojson::{Geometry, Value};
+    use n3gb_rs::HexCell;
+
+    fn make_test_record() -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            pipe_type: Some("MP".to_string()),
+            asset_id: Some("TEST-001".to_string()),
+            ..line_record(vec![
+                vec![-2.248423716278411, 53.4804537960769],
+                vec![-2.250244759514899, 53.48066909573824],
+            ])
+        }
+    }
+
+    #[test]
+    fn test_write_geoparquet_to_bytes_roundtrip() {
+        let summary = to_hex_summary(&[make_test_record()], 12).unwrap();
+
+        let bytes = write_geoparquet_to_bytes(&summary).unwrap();
+        assert_eq!(&bytes[..4], b"PAR1");
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(bytes))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.collect::<Result<_, _>>().unwrap();
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+
+        assert_eq!(rows, summary.num_rows());
+        assert!(batches[0].schema().field_with_name("hex_id").is_ok());
+    }
+
+    fn version_metadata(version: &str) -> Vec<KeyValue> {
+        vec![
+            KeyValue::new("geo".to_string(), "{}".to_string()),
+            KeyValue::new(SCHEMA_VERSION_KEY.to_string(), version.to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_check_schema_version_matches() {
+        let kv = version_metadata(&SCHEMA_VERSION.to_string());
+        assert!(check_schema_version(Some(&kv)).is_ok());
+    }
+
+    #[test]
+    fn test_check_schema_version_missing_is_accepted() {
+        assert!(check_schema_version(None).is_ok());
+        assert!(check_schema_version(Some(&vec![])).is_ok());
+    }
+
+    #[test]
+    fn test_check_schema_version_mismatch() {
+        let kv = version_metadata("999");
+        match check_schema_version(Some(&kv)) {
+            Err(InfraHexError::Config(msg)) => {
+                assert_eq!(
+                    msg,
+                    format!(
+                        "schema version mismatch: file=999, supported={}",
+                        SCHEMA_VERSION
+                    )
+                );
+            }
+            other => panic!("expected Config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_geoparquet_roundtrip_decodes_geometry() {
+        let summary = to_hex_summary(&[make_test_record()], 12).unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "infra-hex-rs-{}-roundtrip.parquet",
+            std::process::id()
+        ));
+        write_geoparquet(&summary, &path).unwrap();
+
+        let batches = read_geoparquet(&path);
+        std::fs::remove_file(&path).unwrap();
+        let batches = batches.unwrap();
+
+        let schema = batches[0].schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["hex_id", "pipe_count", "total_length_m", "geometry"]
+        );
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).sum::<usize>(),
+            summary.num_rows()
+        );
+
+        // Geometry comes back as a geoarrow polygon column, not raw WKB
+        let geometry = schema.field_with_name("geometry").unwrap();
+        let metadata = geometry.metadata();
+        assert_eq!(
+            metadata.get("ARROW:extension:name").map(String::as_str),
+            Some("geoarrow.polygon")
+        );
+        assert!(metadata["ARROW:extension:metadata"].contains("27700"));
+    }
+
+    #[test]
+    fn test_read_and_merge_geoparquet() {
+        let summary = to_hex_summary(&[make_test_record()], 12).unwrap();
+        let dir = std::env::temp_dir();
+        let paths: Vec<_> = ["a", "b"]
+            .iter()
+            .map(|name| {
+                dir.join(format!(
+                    "infra-hex-rs-{}-merge-{}.parquet",
+                    std::process::id(),
+                    name
+                ))
+            })
+            .collect();
+        for path in &paths {
+            write_geoparquet(&summary, path).unwrap();
+        }
+
+        let merged = read_and_merge_geoparquet(&paths);
+        for path in &paths {
+            std::fs::remove_file(path).unwrap();
+        }
+        let merged = merged.unwrap();
+
+        // Same cells in both files, so each count doubles
+        assert_eq!(merged.num_rows(), summary.num_rows());
+        let counts = merged
+            .column_by_name("pipe_count")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow_array::UInt32Array>()
+            .unwrap();
+        assert!(counts.iter().all(|c| c == Some(2)));
+    }
+
+    #[test]
+    fn test_read_geoparquet_batches_per_row_group() {
+        use parquet::file::properties::WriterProperties;
+
+        let records: Vec<_> = (0..4)
+            .map(|i| {
+                let mut record = make_test_record();
+                let offset = i as f64 * 0.01;
+                record.geo_shape.geometry = Some(Geometry::new(Value::LineString(vec![
+                    vec![-2.2484 + offset, 53.4804],
+                    vec![-2.2502 + offset, 53.4806],
+                ])));
+                record
+            })
+            .collect();
+        let options = crate::core::HexSummaryOptions::new().with_geometry(false);
+        let summary =
+            crate::core::to_hex_summary_with_options(&records, 12, &(), &options).unwrap();
+        assert!(summary.num_rows() > 2);
+
+        let path = std::env::temp_dir().join(format!(
+            "infra-hex-rs-{}-batches.parquet",
+            std::process::id()
+        ));
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), summary.schema(), Some(props))
+                .unwrap();
+        writer.write(&summary).unwrap();
+        writer.finish().unwrap();
+
+        let batches: Result<Vec<_>, _> = read_geoparquet_batches(&path).unwrap().collect();
+        std::fs::remove_file(&path).unwrap();
+        let batches = batches.unwrap();
+
+        assert!(batches.len() > 1);
+        let combined = arrow_select::concat::concat_batches(&summary.schema(), &batches).unwrap();
+        assert_eq!(combined, summary);
+    }
+
+    #[test]
+    fn test_read_geoparquet_batches_decodes_geometry_per_row_group() {
+        use geo_types::Geometry as GeoGeometry;
+
+        let summary = make_large_summary(12);
+        assert!(summary.num_rows() > 4);
+        let path = std::env::temp_dir().join(format!(
+            "infra-hex-rs-{}-geometry-batches.parquet",
+            std::process::id()
+        ));
+        let options = GeoParquetOptions::new().with_row_group_size(2);
+        write_geoparquet_with_options(&summary, &path, &options).unwrap();
+
+        let batches: Result<Vec<_>, _> = read_geoparquet_batches(&path).unwrap().collect();
+        std::fs::remove_file(&path).unwrap();
+        let batches = batches.unwrap();
+
+        assert!(batches.len() > 1);
+        let mut rows = 0;
+        for batch in &batches {
+            assert!(batch.num_rows() <= 2);
+            let schema = batch.schema();
+            let (index, field) = schema.column_with_name("geometry").unwrap();
+            assert_eq!(
+                field
+                    .metadata()
+                    .get("ARROW:extension:name")
+                    .map(String::as_str),
+                Some("geoarrow.polygon")
+            );
+
+            let ids = batch
+                .column_by_name("hex_id")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .unwrap();
+            let geometries =
+                crate::core::sink::decode_polygons(batch.column(index).as_ref(), field).unwrap();
+            for (i, geometry) in geometries.into_iter().enumerate() {
+                let expected = HexCell::from_hex_id(ids.value(i)).unwrap().to_polygon();
+                assert_eq!(geometry, Some(GeoGeometry::Polygon(expected)));
+            }
+            rows += batch.num_rows();
+        }
+        assert_eq!(rows, summary.num_rows());
+    }
+
+    /// A summary over a long line, large enough to span several row groups.
+    fn make_large_summary(zoom: u8) -> RecordBatch {
+        let mut record = make_test_record();
+        record.geo_shape.geometry = Some(Geometry::new(Value::LineString(
+            (0..200)
+                .map(|i| vec![-2.40 + i as f64 * 0.002, 53.48 + (i % 2) as f64 * 0.001])
+                .collect(),
+        )));
+        to_hex_summary(&[record], zoom).unwrap()
+    }
+
+    fn read_bytes(bytes: Vec<u8>) -> Vec<RecordBatch> {
+        ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(bytes))
+            .unwrap()
+            .build()
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_write_geoparquet_parallel_matches_serial() {
+        let summary = make_large_summary(12);
+        assert!(summary.num_rows() > 8);
+
+        let serial = write_geoparquet_to_bytes(&summary).unwrap();
+        let mut parallel = Vec::new();
+        write_geoparquet_parallel_to_writer(&summary, &mut parallel, 4).unwrap();
+
+        let serial = read_bytes(serial);
+        let parallel = read_bytes(parallel);
+        let schema = serial[0].schema();
+        assert_eq!(
+            arrow_select::concat::concat_batches(&schema, &parallel).unwrap(),
+            arrow_select::concat::concat_batches(&schema, &serial).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_geoparquet_parallel_rejects_zero_row_groups() {
+        let summary = make_large_summary(10);
+        let result = write_geoparquet_parallel_to_writer(&summary, Vec::new(), 0);
+        assert!(matches!(result, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_merge_geo_metadata() {
+        let entry = |bbox: &str, types: &str| {
+            KeyValue::new(
+                "geo".to_string(),
+                format!(
+                    r#"{{"version":"1.1.0","primary_column":"geometry","columns":{{"geometry":{{"encoding":"WKB","geometry_types":{},"bbox":{}}}}}}}"#,
+                    types, bbox
+                ),
+            )
+        };
+
+        let merged = merge_geo_metadata(vec![
+            entry("[0.0,1.0,2.0,3.0]", r#"["Polygon"]"#),
+            entry("[-1.0,2.0,1.0,5.0]", r#"["MultiPolygon"]"#),
+        ])
+        .unwrap();
+
+        let value: serde_json::Value =
+            serde_json::from_str(merged.value.as_deref().unwrap()).unwrap();
+        let column = &value["columns"]["geometry"];
+        assert_eq!(column["bbox"], serde_json::json!([-1.0, 1.0, 2.0, 5.0]));
+        assert_eq!(
+            column["geometry_types"],
+            serde_json::json!(["Polygon", "MultiPolygon"])
+        );
+        assert_eq!(value["primary_column"], "geometry");
+    }
+
+    /// Times the serial and parallel writers on a large summary. Run with
+    /// `cargo test --release -- --ignored --nocapture bench_write`.
+    #[test]
+    #[ignore = "benchmark"]
+    fn bench_write_geoparquet_parallel() {
+        let summary = make_large_summary(15);
+        println!("{} rows", summary.num_rows());
+
+        let start = std::time::Instant::now();
+        let serial = write_geoparquet_to_bytes(&summary).unwrap();
+        println!("serial:   {:?}", start.elapsed());
+
+        let start = std::time::Instant::now();
+        let mut parallel = Vec::new();
+        write_geoparquet_parallel_to_writer(&summary, &mut parallel, 1024).unwrap();
+        println!("parallel: {:?}", start.elapsed());
+
+        let rows = |batches: Vec<RecordBatch>| batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        assert_eq!(rows(read_bytes(parallel)), rows(read_bytes(serial)));
+    }
+
+    #[test]
+    fn test_batch_writer_matches_single_write() {
+        let summary = make_large_summary(12);
+        let half = summary.num_rows() / 2;
+
+        let mut buf = Vec::new();
+        let mut writer = GeoParquetBatchWriter::new(&mut buf);
+        writer.write(&summary.slice(0, half)).unwrap();
+        writer
+            .write(&summary.slice(half, summary.num_rows() - half))
+            .unwrap();
+        writer.finish().unwrap();
+
+        let single = read_bytes(write_geoparquet_to_bytes(&summary).unwrap());
+        let batched = read_bytes(buf);
+        let schema = single[0].schema();
+        assert_eq!(
+            arrow_select::concat::concat_batches(&schema, &batched).unwrap(),
+            arrow_select::concat::concat_batches(&schema, &single).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_batch_writer_rejects_schema_change_and_empty_input() {
+        let summary = make_large_summary(10);
+        let no_geom = summary.project(&[0, 1]).unwrap();
+
+        let mut writer = GeoParquetBatchWriter::new(Vec::new());
+        writer.write(&summary).unwrap();
+        assert!(matches!(
+            writer.write(&no_geom),
+            Err(InfraHexError::Config(_))
+        ));
+
+        let empty = GeoParquetBatchWriter::new(Vec::new()).finish();
+        assert!(matches!(empty, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_batch_writer_rejects_options_after_first_write() {
+        let summary = make_large_summary(10);
+        let mut writer = GeoParquetBatchWriter::new(Vec::new())
+            .with_options(GeoParquetOptions::new().with_dictionary(false))
+            .unwrap();
+        writer.write(&summary).unwrap();
+
+        let late = writer.with_options(GeoParquetOptions::new());
+        assert!(matches!(late, Err(InfraHexError::Config(_))));
+    }
+
+    #[test]
+    fn test_write_geoparquet_parallel_with_options_compresses() {
+        let summary = make_large_summary(12);
+        let write = |compression| {
+            let mut buf = Vec::new();
+            let options = GeoParquetOptions::new().with_compression(compression);
+            write_geoparquet_parallel_to_writer_with_options(&summary, &mut buf, 4, &options)
+                .unwrap();
+            buf
+        };
+
+        let plain = write(ParquetCompression::Uncompressed);
+        let zstd = write(ParquetCompression::Zstd(19));
+        assert!(
+            zstd.len() < plain.len(),
+            "{} >= {}",
+            zstd.len(),
+            plain.len()
+        );
+        assert_eq!(read_bytes(zstd), read_bytes(plain));
+    }
+
+    #[test]
+    fn test_writer_properties_validation() {
+        assert!(GeoParquetOptions::default().writer_properties().is_ok());
+        assert!(
+            GeoParquetOptions::new()
+                .with_compression(ParquetCompression::Gzip(6))
+                .with_row_group_size(1024)
+                .with_dictionary(false)
+                .writer_properties()
+                .is_ok()
+        );
+
+        for options in [
+            GeoParquetOptions::new().with_row_group_size(0),
+            GeoParquetOptions::new().with_compression(ParquetCompression::Zstd(99)),
+        ] {
+            assert!(matches!(
+                options.writer_properties(),
+                Err(InfraHexError::Config(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_write_geoparquet_with_options_compresses() {
+        let summary = make_large_summary(12);
+        let dir = std::env::temp_dir();
+        let path = |name: &str| dir.join(format!("infra-hex-rs-{}-{}", std::process::id(), name));
+
+        let plain = path("plain.parquet");
+        let zstd = path("zstd.parquet");
+        let options = GeoParquetOptions::new().with_compression(ParquetCompression::Uncompressed);
+        write_geoparquet_with_options(&summary, &plain, &options).unwrap();
+        write_geoparquet(&summary, &zstd).unwrap();
+
+        let size = |p: &std::path::PathBuf| std::fs::metadata(p).unwrap().len();
+        assert!(size(&zstd) < size(&plain));
+        std::fs::remove_file(plain).unwrap();
+        std::fs::remove_file(zstd).unwrap();
+    }
+
+    #[test]
+    fn test_read_and_merge_geoparquet_missing_file() {
+        let result = read_and_merge_geoparquet(&["/nonexistent/infra-hex-rs/a.parquet"]);
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,83 @@
+use futures::StreamExt;
+use std::path::Path;
+
+use crate::client::{BBox, CadentClient, FetchProgress, page_count};
+use crate::error::InfraHexError;
+
+use super::aggregator::HexAggregator;
+use super::parquet::write_geoparquet;
+
+/// Outcome of [`stream_fetch_to_parquet`].
+#[derive(Debug)]
+pub struct StreamFetchReport {
+    /// Records the count probe reported for the bounding box.
+    pub expected_records: usize,
+    /// Records actually fetched and aggregated.
+    pub records_fetched: usize,
+    /// Rows (distinct hexes) written to the output file.
+    pub hex_count: usize,
+    /// Errors from pages that failed to fetch. Their records are missing from
+    /// the output, which is otherwise complete.
+    pub errors: Vec<InfraHexError>,
+}
+
+impl StreamFetchReport {
+    /// True if every page was fetched successfully.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Fetches every pipeline in `bbox`, aggregates it into hexes at `zoom` and
+/// writes the summary to GeoParquet at `path`, without holding all records
+/// in memory.
+///
+/// Pages are streamed from the API a batch at a time and folded into a
+/// [`HexAggregator`] as they arrive, so peak memory is bounded by one batch
+/// of pages plus the per-hex counts. This is the path to use for country-scale runs
+/// that don't fit in RAM. `on_progress` is called after each page.
+///
+/// A failed page doesn't abort the run: its error is collected in
+/// [`StreamFetchReport::errors`] and the summary is written from the pages
+/// that succeeded. Errors from the count probe, hexing or writing the file
+/// are returned as `Err`.
+pub async fn stream_fetch_to_parquet(
+    client: &CadentClient,
+    bbox: &BBox,
+    zoom: u8,
+    path: impl AsRef<Path>,
+    mut on_progress: impl FnMut(FetchProgress),
+) -> Result<StreamFetchReport, InfraHexError> {
+    let expected_records = client.count_by_bbox(bbox).await?;
+    let total_pages = page_count(expected_records, &client.pagination);
+
+    let mut aggregator = HexAggregator::new(zoom);
+    let mut errors = Vec::new();
+    let mut completed_pages = 0;
+
+    let mut pages = Box::pin(client.stream_pages_by_bbox(bbox, expected_records));
+    while let Some(page) = pages.next().await {
+        match page {
+            Ok(records) => aggregator.add(&records)?,
+            Err(e) => errors.push(e),
+        }
+
+        completed_pages += 1;
+        on_progress(FetchProgress {
+            completed_pages,
+            total_pages,
+            records_so_far: aggregator.records_seen(),
+        });
+    }
+
+    let records_fetched = aggregator.records_seen();
+    let summary = aggregator.finish()?;
+    write_geoparquet(&summary, path)?;
+
+    Ok(StreamFetchReport {
+        expected_records,
+        records_fetched,
+        hex_count: summary.num_rows(),
+        errors,
+    })
+}
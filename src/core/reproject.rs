@@ -0,0 +1,387 @@
+//! Conversions between WGS84 (EPSG:4326) and British National Grid
+//! (EPSG:27700).
+//!
+//! Pure Rust, with no PROJ dependency: a 7-parameter Helmert datum shift
+//! between the WGS84 and Airy 1830 ellipsoids, combined with the OS
+//! Transverse Mercator projection. Without the OSTN15 grid correction the
+//! result is within about 10 metres of the definitive transform across
+//! Great Britain (a few metres in England, more in the Scottish Highlands),
+//! which is far below the size of any hex cell; it is not suitable for
+//! survey work.
+
+use geo_types::{Coord, LineString, MultiPolygon, Polygon};
+
+// =============================================================================
+// Ellipsoid and Projection Constants
+// =============================================================================
+
+/// WGS84 ellipsoid semi-major and semi-minor axes (metres).
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_B: f64 = 6_356_752.314_245;
+
+/// Airy 1830 ellipsoid semi-major and semi-minor axes (metres), used by OSGB36.
+const AIRY_A: f64 = 6_377_563.396;
+const AIRY_B: f64 = 6_356_256.909;
+
+/// National Grid Transverse Mercator projection parameters.
+const F0: f64 = 0.999_601_271_7;
+const LAT0: f64 = 49.0;
+const LON0: f64 = -2.0;
+const E0: f64 = 400_000.0;
+const N0: f64 = -100_000.0;
+
+/// Helmert parameters for WGS84 -> OSGB36 (translation in metres, scale in
+/// ppm, rotations in arc seconds).
+const TX: f64 = -446.448;
+const TY: f64 = 125.157;
+const TZ: f64 = -542.060;
+const S_PPM: f64 = 20.4894;
+const RX_SEC: f64 = -0.1502;
+const RY_SEC: f64 = -0.2470;
+const RZ_SEC: f64 = -0.8421;
+
+// =============================================================================
+// Coordinate Reference Systems
+// =============================================================================
+
+/// Coordinate reference system of input pipeline geometry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Crs {
+    /// WGS84 longitude/latitude in degrees (EPSG:4326), as served by Cadent.
+    #[default]
+    Wgs84,
+    /// British National Grid eastings/northings in metres (EPSG:27700).
+    Bng,
+}
+
+impl Crs {
+    /// Converts a coordinate in this CRS to British National Grid.
+    pub fn to_bng(self, coord: Coord<f64>) -> Coord<f64> {
+        match self {
+            Crs::Wgs84 => wgs84_to_bng(coord),
+            Crs::Bng => coord,
+        }
+    }
+}
+
+// =============================================================================
+// Public Conversions
+// =============================================================================
+
+/// Converts a WGS84 coordinate (`x` = lon, `y` = lat, degrees) to British
+/// National Grid eastings/northings (metres).
+///
+/// Uses a 7-parameter Helmert datum shift followed by the OS Transverse
+/// Mercator projection. This is accurate to within a few metres across Great
+/// Britain (no OSTN15 grid correction is applied).
+pub fn wgs84_to_bng(coord: Coord<f64>) -> Coord<f64> {
+    let (x, y, z) = geodetic_to_cartesian(coord.y, coord.x, WGS84_A, WGS84_B);
+    let (x, y, z) = helmert(x, y, z, 1.0);
+    let (lat, lon) = cartesian_to_geodetic(x, y, z, AIRY_A, AIRY_B);
+    let (e, n) = project(lat, lon);
+    Coord { x: e, y: n }
+}
+
+/// Converts British National Grid eastings/northings (metres) to a WGS84
+/// coordinate (`x` = lon, `y` = lat, degrees).
+///
+/// The inverse of [`wgs84_to_bng`], with the same few-metre accuracy.
+pub fn bng_to_wgs84(coord: Coord<f64>) -> Coord<f64> {
+    let (lat, lon) = unproject(coord.x, coord.y);
+    let (x, y, z) = geodetic_to_cartesian(lat, lon, AIRY_A, AIRY_B);
+    let (x, y, z) = helmert(x, y, z, -1.0);
+    let (lat, lon) = cartesian_to_geodetic(x, y, z, WGS84_A, WGS84_B);
+    Coord { x: lon, y: lat }
+}
+
+/// Converts every vertex of a BNG polygon (e.g. from `HexCell::to_polygon`)
+/// to WGS84.
+pub fn polygon_bng_to_wgs84(polygon: &Polygon<f64>) -> Polygon<f64> {
+    map_polygon(polygon, bng_to_wgs84)
+}
+
+/// Converts every vertex of a WGS84 polygon to BNG.
+pub fn polygon_wgs84_to_bng(polygon: &Polygon<f64>) -> Polygon<f64> {
+    map_polygon(polygon, wgs84_to_bng)
+}
+
+/// Converts every vertex of a BNG multipolygon to WGS84.
+pub fn multipolygon_bng_to_wgs84(multipolygon: &MultiPolygon<f64>) -> MultiPolygon<f64> {
+    multipolygon.iter().map(polygon_bng_to_wgs84).collect()
+}
+
+/// Converts every vertex of a WGS84 multipolygon to BNG.
+pub fn multipolygon_wgs84_to_bng(multipolygon: &MultiPolygon<f64>) -> MultiPolygon<f64> {
+    multipolygon.iter().map(polygon_wgs84_to_bng).collect()
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+/// Applies `convert` to every vertex of `polygon`, keeping its ring structure.
+fn map_polygon(polygon: &Polygon<f64>, convert: fn(Coord<f64>) -> Coord<f64>) -> Polygon<f64> {
+    let ring =
+        |ls: &LineString<f64>| -> LineString<f64> { ls.coords().map(|c| convert(*c)).collect() };
+    Polygon::new(
+        ring(polygon.exterior()),
+        polygon.interiors().iter().map(ring).collect(),
+    )
+}
+
+/// Converts latitude/longitude (degrees, zero height) to geocentric cartesian
+/// coordinates on the given ellipsoid.
+fn geodetic_to_cartesian(lat: f64, lon: f64, a: f64, b: f64) -> (f64, f64, f64) {
+    let (phi, lambda) = (lat.to_radians(), lon.to_radians());
+    let e2 = 1.0 - (b * b) / (a * a);
+    let nu = a / (1.0 - e2 * phi.sin().powi(2)).sqrt();
+    (
+        nu * phi.cos() * lambda.cos(),
+        nu * phi.cos() * lambda.sin(),
+        (1.0 - e2) * nu * phi.sin(),
+    )
+}
+
+/// Converts geocentric cartesian coordinates to latitude/longitude (degrees).
+fn cartesian_to_geodetic(x: f64, y: f64, z: f64, a: f64, b: f64) -> (f64, f64) {
+    let e2 = 1.0 - (b * b) / (a * a);
+    let p = (x * x + y * y).sqrt();
+    let mut phi = z.atan2(p * (1.0 - e2));
+
+    // Converges to sub-millimetre precision in a handful of iterations
+    for _ in 0..10 {
+        let nu = a / (1.0 - e2 * phi.sin().powi(2)).sqrt();
+        let next = (z + e2 * nu * phi.sin()).atan2(p);
+        if (next - phi).abs() < 1e-12 {
+            phi = next;
+            break;
+        }
+        phi = next;
+    }
+
+    (phi.to_degrees(), y.atan2(x).to_degrees())
+}
+
+/// Applies the WGS84 -> OSGB36 Helmert transform (`direction` = 1.0), or its
+/// approximate inverse (`direction` = -1.0).
+fn helmert(x: f64, y: f64, z: f64, direction: f64) -> (f64, f64, f64) {
+    let sec_to_rad = std::f64::consts::PI / (180.0 * 3600.0);
+    let (tx, ty, tz) = (TX * direction, TY * direction, TZ * direction);
+    let s = 1.0 + S_PPM * direction * 1e-6;
+    let rx = RX_SEC * direction * sec_to_rad;
+    let ry = RY_SEC * direction * sec_to_rad;
+    let rz = RZ_SEC * direction * sec_to_rad;
+
+    (
+        tx + s * x - rz * y + ry * z,
+        ty + rz * x + s * y - rx * z,
+        tz - ry * x + rx * y + s * z,
+    )
+}
+
+/// Meridional arc length from the true origin to latitude `phi` (radians).
+fn meridional_arc(phi: f64) -> f64 {
+    let n = (AIRY_A - AIRY_B) / (AIRY_A + AIRY_B);
+    let (n2, n3) = (n * n, n * n * n);
+    let phi0 = LAT0.to_radians();
+    let (dp, sp) = (phi - phi0, phi + phi0);
+
+    AIRY_B
+        * F0
+        * ((1.0 + n + 1.25 * n2 + 1.25 * n3) * dp
+            - (3.0 * n + 3.0 * n2 + 2.625 * n3) * dp.sin() * sp.cos()
+            + (1.875 * n2 + 1.875 * n3) * (2.0 * dp).sin() * (2.0 * sp).cos()
+            - (35.0 / 24.0) * n3 * (3.0 * dp).sin() * (3.0 * sp).cos())
+}
+
+/// Projects OSGB36 latitude/longitude (degrees) to eastings/northings.
+fn project(lat: f64, lon: f64) -> (f64, f64) {
+    let (phi, lambda) = (lat.to_radians(), lon.to_radians());
+    let e2 = 1.0 - (AIRY_B * AIRY_B) / (AIRY_A * AIRY_A);
+    let (sin_phi, cos_phi, tan_phi) = (phi.sin(), phi.cos(), phi.tan());
+
+    let nu = AIRY_A * F0 / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+    let rho = AIRY_A * F0 * (1.0 - e2) / (1.0 - e2 * sin_phi * sin_phi).powf(1.5);
+    let eta2 = nu / rho - 1.0;
+
+    let m = meridional_arc(phi);
+    let tan2 = tan_phi * tan_phi;
+    let tan4 = tan2 * tan2;
+
+    let i = m + N0;
+    let ii = nu / 2.0 * sin_phi * cos_phi;
+    let iii = nu / 24.0 * sin_phi * cos_phi.powi(3) * (5.0 - tan2 + 9.0 * eta2);
+    let iiia = nu / 720.0 * sin_phi * cos_phi.powi(5) * (61.0 - 58.0 * tan2 + tan4);
+    let iv = nu * cos_phi;
+    let v = nu / 6.0 * cos_phi.powi(3) * (nu / rho - tan2);
+    let vi = nu / 120.0
+        * cos_phi.powi(5)
+        * (5.0 - 18.0 * tan2 + tan4 + 14.0 * eta2 - 58.0 * tan2 * eta2);
+
+    let dl = lambda - LON0.to_radians();
+    let northing = i + ii * dl.powi(2) + iii * dl.powi(4) + iiia * dl.powi(6);
+    let easting = E0 + iv * dl + v * dl.powi(3) + vi * dl.powi(5);
+
+    (easting, northing)
+}
+
+/// Inverse projection from eastings/northings to OSGB36 latitude/longitude.
+fn unproject(easting: f64, northing: f64) -> (f64, f64) {
+    let e2 = 1.0 - (AIRY_B * AIRY_B) / (AIRY_A * AIRY_A);
+
+    // Iterate for the footpoint latitude. Converges in a few iterations for
+    // finite input; the cap stops NaN or infinite northings spinning forever
+    let mut phi = LAT0.to_radians();
+    let mut m = 0.0;
+    for _ in 0..20 {
+        phi += (northing - N0 - m) / (AIRY_A * F0);
+        m = meridional_arc(phi);
+        if (northing - N0 - m).abs() < 1e-5 {
+            break;
+        }
+    }
+
+    let (sin_phi, cos_phi, tan_phi) = (phi.sin(), phi.cos(), phi.tan());
+    let nu = AIRY_A * F0 / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+    let rho = AIRY_A * F0 * (1.0 - e2) / (1.0 - e2 * sin_phi * sin_phi).powf(1.5);
+    let eta2 = nu / rho - 1.0;
+
+    let tan2 = tan_phi * tan_phi;
+    let tan4 = tan2 * tan2;
+    let tan6 = tan4 * tan2;
+    let sec_phi = 1.0 / cos_phi;
+
+    let vii = tan_phi / (2.0 * rho * nu);
+    let viii = tan_phi / (24.0 * rho * nu.powi(3)) * (5.0 + 3.0 * tan2 + eta2 - 9.0 * tan2 * eta2);
+    let ix = tan_phi / (720.0 * rho * nu.powi(5)) * (61.0 + 90.0 * tan2 + 45.0 * tan4);
+    let x = sec_phi / nu;
+    let xi = sec_phi / (6.0 * nu.powi(3)) * (nu / rho + 2.0 * tan2);
+    let xii = sec_phi / (120.0 * nu.powi(5)) * (5.0 + 28.0 * tan2 + 24.0 * tan4);
+    let xiia =
+        sec_phi / (5040.0 * nu.powi(7)) * (61.0 + 662.0 * tan2 + 1320.0 * tan4 + 720.0 * tan6);
+
+    let de = easting - E0;
+    let lat = phi - vii * de.powi(2) + viii * de.powi(4) - ix * de.powi(6);
+    let lon = LON0.to_radians() + x * de - xi * de.powi(3) + xii * de.powi(5) - xiia * de.powi(7);
+
+    (lat.to_degrees(), lon.to_degrees())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Caister Water Tower, the worked example from the OS guide to coordinate
+    // systems in Great Britain.
+    const CAISTER_E: f64 = 651_409.903;
+    const CAISTER_N: f64 = 313_177.270;
+
+    #[test]
+    fn test_projection_matches_os_worked_example() {
+        // OSGB36 latitude/longitude of the tower
+        let (e, n) = project(52.657_570_306, 1.717_921_583);
+        assert!((e - CAISTER_E).abs() < 0.01, "easting {}", e);
+        assert!((n - CAISTER_N).abs() < 0.01, "northing {}", n);
+
+        let (lat, lon) = unproject(CAISTER_E, CAISTER_N);
+        assert!((lat - 52.657_570_306).abs() < 1e-7);
+        assert!((lon - 1.717_921_583).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_wgs84_to_bng_reference_point() {
+        // ETRS89 (~WGS84) position of the tower: 52°39'28.8282"N 1°42'57.8663"E
+        let wgs84 = Coord {
+            x: 1.0 + 42.0 / 60.0 + 57.8663 / 3600.0,
+            y: 52.0 + 39.0 / 60.0 + 28.8282 / 3600.0,
+        };
+        let bng = wgs84_to_bng(wgs84);
+
+        // Helmert-only accuracy is a few metres
+        assert!((bng.x - CAISTER_E).abs() < 5.0, "easting {}", bng.x);
+        assert!((bng.y - CAISTER_N).abs() < 5.0, "northing {}", bng.y);
+    }
+
+    #[test]
+    fn test_landmark_reference_points() {
+        // Published WGS84 positions and National Grid references of
+        // landmarks, rounded to about a metre; a wrong datum or projection
+        // parameter would be off by tens to hundreds of metres
+        let landmarks = [
+            // Snowdon summit, SH 60986 54375
+            (-4.076_11, 53.068_50, 260_986.0, 354_375.0),
+            // Ben Nevis summit, NN 16667 71288
+            (-5.003_52, 56.796_89, 216_667.0, 771_288.0),
+        ];
+
+        for (lon, lat, e, n) in landmarks {
+            let bng = wgs84_to_bng(Coord { x: lon, y: lat });
+            assert!((bng.x - e).abs() < 10.0, "easting {} vs {}", bng.x, e);
+            assert!((bng.y - n).abs() < 10.0, "northing {} vs {}", bng.y, n);
+
+            let wgs84 = bng_to_wgs84(Coord { x: e, y: n });
+            assert!((wgs84.x - lon).abs() < 5e-4, "lon {} vs {}", wgs84.x, lon);
+            assert!((wgs84.y - lat).abs() < 5e-4, "lat {} vs {}", wgs84.y, lat);
+        }
+    }
+
+    #[test]
+    fn test_polygon_helpers_roundtrip() {
+        let square = Polygon::new(
+            LineString::from(vec![
+                (383_000.0, 398_000.0),
+                (383_100.0, 398_000.0),
+                (383_100.0, 398_100.0),
+                (383_000.0, 398_000.0),
+            ]),
+            vec![LineString::from(vec![
+                (383_040.0, 398_020.0),
+                (383_060.0, 398_020.0),
+                (383_060.0, 398_040.0),
+                (383_040.0, 398_020.0),
+            ])],
+        );
+        let multi = MultiPolygon::new(vec![square.clone(), square.clone()]);
+
+        let wgs84 = multipolygon_bng_to_wgs84(&multi);
+        assert_eq!(wgs84.0.len(), 2);
+        assert_eq!(wgs84.0[0].interiors().len(), 1);
+        assert!(wgs84.0[0].exterior().coords().all(|c| c.x.abs() < 180.0));
+
+        let back = multipolygon_wgs84_to_bng(&wgs84);
+        for (a, b) in back.0[0]
+            .exterior()
+            .coords()
+            .chain(back.0[0].interiors()[0].coords())
+            .zip(
+                square
+                    .exterior()
+                    .coords()
+                    .chain(square.interiors()[0].coords()),
+            )
+        {
+            // The inverse Helmert shift is approximate, to a few millimetres
+            assert!((a.x - b.x).abs() < 0.01 && (a.y - b.y).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_bng_to_wgs84_roundtrip() {
+        let manchester = Coord {
+            x: -2.2426,
+            y: 53.4808,
+        };
+        let back = bng_to_wgs84(wgs84_to_bng(manchester));
+        assert!((back.x - manchester.x).abs() < 1e-6);
+        assert!((back.y - manchester.y).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bng_to_wgs84_non_finite_terminates() {
+        let out = bng_to_wgs84(Coord {
+            x: f64::NAN,
+            y: f64::NAN,
+        });
+        assert!(!out.x.is_finite() || !out.y.is_finite());
+    }
+}
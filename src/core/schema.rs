@@ -0,0 +1,100 @@
+use arrow_schema::{Field, Schema};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// Serialises an Arrow schema to pretty-printed JSON.
+///
+/// Each field is written with its `name`, `data_type` (Arrow's display form,
+/// e.g. `Utf8` or `UInt32`), `nullable` flag and `metadata`, which carries
+/// the GeoArrow extension name and CRS of geometry columns. Schema-level
+/// metadata is included too. Pair with [`hex_summary_schema`] to publish the
+/// output schema for downstream validation.
+///
+/// [`hex_summary_schema`]: crate::core::hex_summary_schema
+pub fn schema_to_json(schema: &Schema) -> String {
+    let fields: Vec<Value> = schema.fields().iter().map(|f| field_to_json(f)).collect();
+    let value = json!({
+        "fields": fields,
+        "metadata": metadata_to_json(schema.metadata()),
+    });
+
+    // Serialising a `Value` can't fail
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+fn field_to_json(field: &Field) -> Value {
+    json!({
+        "name": field.name(),
+        "data_type": field.data_type().to_string(),
+        "nullable": field.is_nullable(),
+        "metadata": metadata_to_json(field.metadata()),
+    })
+}
+
+/// Writes metadata with sorted keys so the output is stable.
+fn metadata_to_json(metadata: &HashMap<String, String>) -> Value {
+    let mut entries: Vec<_> = metadata.iter().collect();
+    entries.sort();
+    Value::Object(
+        entries
+            .into_iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::{
+        CountNormalisation, HexSummaryOptions, hex_summary_schema, to_hex_summary_with_options,
+    };
+
+    fn make_record() -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            asset_id: Some("SCHEMA-001".to_string()),
+            ..line_record(vec![vec![-2.2400, 53.4800], vec![-2.2300, 53.4850]])
+        }
+    }
+
+    #[test]
+    fn test_hex_summary_schema_matches_batch() {
+        let records = vec![make_record()];
+        let option_sets = [
+            HexSummaryOptions::new(),
+            HexSummaryOptions::new().with_geometry(false),
+            HexSummaryOptions::new()
+                .with_reproject_to_wgs84(true)
+                .with_cell_area(true)
+                .with_on_boundary(true)
+                .with_share_of_region(true)
+                .with_count_normalisation(CountNormalisation::Linear),
+        ];
+
+        for options in option_sets {
+            let batch = to_hex_summary_with_options(&records, 10, &(), &options).unwrap();
+            assert_eq!(batch.schema().as_ref(), &hex_summary_schema(&options));
+        }
+    }
+
+    #[test]
+    fn test_schema_to_json() {
+        let schema = hex_summary_schema(&HexSummaryOptions::new());
+        let json: Value = serde_json::from_str(&schema_to_json(&schema)).unwrap();
+
+        let fields = json["fields"].as_array().unwrap();
+        assert_eq!(fields.len(), schema.fields().len());
+        assert_eq!(fields[0]["name"], "hex_id");
+        assert_eq!(fields[0]["data_type"], "Utf8");
+        assert_eq!(fields[1]["data_type"], "UInt32");
+        assert_eq!(fields[1]["nullable"], false);
+        assert!(
+            fields[3]["metadata"]
+                .as_object()
+                .unwrap()
+                .contains_key("ARROW:extension:name")
+        );
+    }
+}
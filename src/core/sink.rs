@@ -0,0 +1,895 @@
+use arrow_array::{
+    Array, ArrayRef, BooleanArray, Float64Array, Int64Array, ListArray, RecordBatch,
+    RecordBatchOptions, StringArray, UInt32Array, UInt64Array,
+};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_csv::WriterBuilder;
+use arrow_schema::{DataType, Field, Schema};
+use geo::MapCoords;
+use geo_traits::to_geo::{ToGeoMultiPolygon, ToGeoPolygon};
+use geo_types::Geometry;
+use geoarrow_array::GeoArrowArrayAccessor;
+use geoarrow_array::array::from_arrow_array;
+use geoarrow_array::cast::AsGeoArrowArray;
+use geojson::{Feature, FeatureCollection};
+use n3gb_rs::HexCell;
+use std::fs::File;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::error::InfraHexError;
+
+use super::arrow::ColumnNames;
+use super::batch::{geometry_column_index, geometry_crs, is_geometry_field};
+use super::geometry::ToGeoJson;
+use super::parquet::{write_geoparquet, write_geoparquet_to_bytes};
+use super::reproject::{Crs, bng_to_wgs84, polygon_bng_to_wgs84, wgs84_to_bng};
+use super::wkt::ToWkt;
+
+// =============================================================================
+// Sink Trait
+// =============================================================================
+
+/// A destination a summary RecordBatch can be persisted to.
+///
+/// Each export format implements this, so callers can write a summary the
+/// same way whatever the target and new formats can be added without
+/// breaking existing code. Use [`format_from_extension`] to pick a sink from
+/// a file path.
+pub trait SummarySink {
+    /// Writes the batch to this sink's destination.
+    fn write(&self, batch: &RecordBatch) -> Result<(), InfraHexError>;
+}
+
+/// Writes summaries as GeoParquet via [`write_geoparquet`].
+#[derive(Debug, Clone)]
+pub struct ParquetSink {
+    path: PathBuf,
+}
+
+impl ParquetSink {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl SummarySink for ParquetSink {
+    fn write(&self, batch: &RecordBatch) -> Result<(), InfraHexError> {
+        write_geoparquet(batch, &self.path)
+    }
+}
+
+/// Writes hex summaries as a GeoJSON FeatureCollection.
+///
+/// Each row becomes a Feature whose geometry is taken from the batch's
+/// geometry column and reprojected to WGS84 lon/lat, as GeoJSON requires;
+/// raw eastings and northings would render in the wrong place. Batches
+/// without geometry have each polygon rebuilt from its hex id instead. All
+/// other columns become properties.
+#[derive(Debug, Clone)]
+pub struct GeoJsonSink {
+    path: PathBuf,
+    crs_member: bool,
+    column_names: ColumnNames,
+}
+
+impl GeoJsonSink {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            crs_member: false,
+            column_names: ColumnNames::default(),
+        }
+    }
+
+    /// Sets the column names to read summaries with, for summaries built
+    /// with custom [`ColumnNames`].
+    pub fn with_column_names(mut self, names: ColumnNames) -> Self {
+        self.column_names = names;
+        self
+    }
+
+    /// Adds a top-level `crs` member naming CRS84 (WGS84 lon/lat).
+    ///
+    /// RFC 7946 removed `crs` from GeoJSON, so it is omitted by default, but
+    /// some legacy consumers (older GDAL and QGIS versions) expect it. The
+    /// coordinates are WGS84 either way.
+    pub fn with_crs_member(mut self, include: bool) -> Self {
+        self.crs_member = include;
+        self
+    }
+}
+
+impl SummarySink for GeoJsonSink {
+    fn write(&self, batch: &RecordBatch) -> Result<(), InfraHexError> {
+        let mut collection = summary_to_feature_collection(batch, &self.column_names, true)?;
+        if self.crs_member {
+            collection.foreign_members = Some(crs84_member());
+        }
+        let file = File::create(&self.path).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        serde_json::to_writer(BufWriter::new(file), &collection)?;
+        Ok(())
+    }
+}
+
+/// Writes summaries as CSV for spreadsheets and tools without geospatial
+/// support.
+///
+/// Every non-geometry column is written with a header row; nulls become
+/// empty fields and list columns (such as `hex_ids` from
+/// `to_record_batch_no_geom`) have their items joined with `;`. The
+/// geometry column is skipped unless [`CsvSink::with_geometry_wkt`] is set.
+#[derive(Debug, Clone)]
+pub struct CsvSink {
+    path: PathBuf,
+    geometry_wkt: bool,
+    column_names: ColumnNames,
+}
+
+impl CsvSink {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            geometry_wkt: false,
+            column_names: ColumnNames::default(),
+        }
+    }
+
+    /// Sets the column names to read summaries with, for summaries built
+    /// with custom [`ColumnNames`]. Only the hex id column is read, to
+    /// rebuild polygons for [`CsvSink::with_geometry_wkt`].
+    pub fn with_column_names(mut self, names: ColumnNames) -> Self {
+        self.column_names = names;
+        self
+    }
+
+    /// Appends a `geometry_wkt` column holding each hex polygon as WKT.
+    ///
+    /// Polygons are rebuilt from `hex_id`, in the same CRS as the batch's
+    /// geometry column: WGS84 if it was reprojected, BNG otherwise. Writing
+    /// fails with `InfraHexError::Config` if the batch has no hex id column.
+    pub fn with_geometry_wkt(mut self, include: bool) -> Self {
+        self.geometry_wkt = include;
+        self
+    }
+}
+
+impl SummarySink for CsvSink {
+    fn write(&self, batch: &RecordBatch) -> Result<(), InfraHexError> {
+        let csv = summary_to_csv(batch, self.geometry_wkt.then_some(&self.column_names))?;
+        std::fs::write(&self.path, csv).map_err(|e| InfraHexError::Geometry(e.to_string()))
+    }
+}
+
+/// Writes the non-geometry columns of a summary to a CSV file at `path`.
+///
+/// Shorthand for [`CsvSink::new`]; use [`CsvSink::with_geometry_wkt`] to
+/// keep the hex polygons as WKT.
+pub fn write_hex_summary_csv(
+    batch: &RecordBatch,
+    path: impl AsRef<Path>,
+) -> Result<(), InfraHexError> {
+    CsvSink::new(path).write(batch)
+}
+
+/// Picks a sink for `path` from its file extension (case-insensitive).
+///
+/// - `.parquet`, `.geoparquet`: [`ParquetSink`]
+/// - `.geojson`, `.json`: [`GeoJsonSink`]
+/// - `.csv`: [`CsvSink`]
+///
+/// Returns `InfraHexError::Config` for any other extension.
+pub fn format_from_extension(
+    path: impl AsRef<Path>,
+) -> Result<Box<dyn SummarySink>, InfraHexError> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match extension.as_deref() {
+        Some("parquet") | Some("geoparquet") => Ok(Box::new(ParquetSink::new(path))),
+        Some("geojson") | Some("json") => Ok(Box::new(GeoJsonSink::new(path))),
+        Some("csv") => Ok(Box::new(CsvSink::new(path))),
+        _ => Err(InfraHexError::Config(format!(
+            "Unsupported output format: {}",
+            path.display()
+        ))),
+    }
+}
+
+// =============================================================================
+// Writer Output
+// =============================================================================
+
+/// Encodings a summary can be written in by [`write_summary_to_writer`] and
+/// [`write_summary_stdout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Comma-separated values with a header row; the geometry column is
+    /// dropped.
+    Csv,
+    /// A WGS84 GeoJSON FeatureCollection, as written by [`GeoJsonSink`].
+    GeoJson,
+    /// GeoParquet bytes, as written by [`ParquetSink`].
+    Parquet,
+}
+
+/// Writes a summary to any writer in the given format.
+pub fn write_summary_to_writer<W: Write>(
+    batch: &RecordBatch,
+    format: OutputFormat,
+    writer: W,
+) -> Result<(), InfraHexError> {
+    write_summary_to_writer_with_columns(batch, format, &ColumnNames::default(), writer)
+}
+
+/// Like [`write_summary_to_writer`], for a summary with the column names in
+/// `names`.
+pub fn write_summary_to_writer_with_columns<W: Write>(
+    batch: &RecordBatch,
+    format: OutputFormat,
+    names: &ColumnNames,
+    mut writer: W,
+) -> Result<(), InfraHexError> {
+    let bytes = encode_summary(batch, format, names)?;
+    writer
+        .write_all(&bytes)
+        .and_then(|_| writer.flush())
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Writes a summary to stdout in the given format, so it can be piped into
+/// other tools (e.g. `jq` for GeoJSON). Parquet is written as raw bytes.
+///
+/// If the reader closes the pipe early (as `head` does), the rest of the
+/// output is discarded and `Ok(())` is returned rather than an error.
+pub fn write_summary_stdout(
+    batch: &RecordBatch,
+    format: OutputFormat,
+) -> Result<(), InfraHexError> {
+    write_summary_stdout_with_columns(batch, format, &ColumnNames::default())
+}
+
+/// Like [`write_summary_stdout`], for a summary with the column names in
+/// `names`.
+pub fn write_summary_stdout_with_columns(
+    batch: &RecordBatch,
+    format: OutputFormat,
+    names: &ColumnNames,
+) -> Result<(), InfraHexError> {
+    let bytes = encode_summary(batch, format, names)?;
+    write_ignoring_broken_pipe(std::io::stdout().lock(), &bytes)
+}
+
+/// Writes `bytes`, treating a closed pipe as success.
+fn write_ignoring_broken_pipe(mut writer: impl Write, bytes: &[u8]) -> Result<(), InfraHexError> {
+    match writer.write_all(bytes).and_then(|_| writer.flush()) {
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(InfraHexError::Geometry(e.to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// Encodes a summary in `format`.
+fn encode_summary(
+    batch: &RecordBatch,
+    format: OutputFormat,
+    names: &ColumnNames,
+) -> Result<Vec<u8>, InfraHexError> {
+    match format {
+        OutputFormat::Csv => summary_to_csv(batch, None).map(String::into_bytes),
+        OutputFormat::GeoJson => Ok(serde_json::to_vec(&summary_to_feature_collection(
+            batch, names, true,
+        )?)?),
+        OutputFormat::Parquet => write_geoparquet_to_bytes(batch),
+    }
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+/// The legacy GeoJSON `crs` member naming CRS84.
+fn crs84_member() -> serde_json::Map<String, serde_json::Value> {
+    let mut members = serde_json::Map::new();
+    members.insert(
+        "crs".to_string(),
+        serde_json::json!({
+            "type": "name",
+            "properties": { "name": "urn:ogc:def:crs:OGC:1.3:CRS84" }
+        }),
+    );
+    members
+}
+
+/// Renders every non-geometry column as CSV with a header row, plus a
+/// `geometry_wkt` column rebuilt from the hex id column in `geometry_wkt`
+/// when it is set. Nulls become empty fields and list items are joined with
+/// `;`, as arrow's CSV writer can't write list columns itself.
+fn summary_to_csv(
+    batch: &RecordBatch,
+    geometry_wkt: Option<&ColumnNames>,
+) -> Result<String, InfraHexError> {
+    let geometry_index = geometry_column_index(batch);
+    let schema = batch.schema();
+
+    let mut fields = Vec::new();
+    let mut columns: Vec<ArrayRef> = Vec::new();
+    for i in (0..batch.num_columns()).filter(|i| Some(*i) != geometry_index) {
+        let field = schema.field(i);
+        match batch.column(i).as_any().downcast_ref::<ListArray>() {
+            Some(list) => {
+                fields.push(Field::new(field.name(), DataType::Utf8, true));
+                columns.push(Arc::new(join_list(list)?));
+            }
+            None => {
+                fields.push(field.clone());
+                columns.push(batch.column(i).clone());
+            }
+        }
+    }
+
+    if let Some(names) = geometry_wkt {
+        fields.push(Field::new("geometry_wkt", DataType::Utf8, false));
+        columns.push(Arc::new(StringArray::from(summary_wkt(
+            batch,
+            names,
+            geometry_index,
+        )?)));
+    }
+
+    let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
+    let csv_batch =
+        RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), columns, &options)
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+    let mut csv = Vec::new();
+    WriterBuilder::new()
+        .with_header(true)
+        .build(&mut csv)
+        .write(&csv_batch)
+        .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+    String::from_utf8(csv).map_err(|e| InfraHexError::Geometry(e.to_string()))
+}
+
+/// Renders each row of a list column as its items joined with `;`, keeping
+/// null rows null.
+fn join_list(list: &ListArray) -> Result<StringArray, InfraHexError> {
+    let options = FormatOptions::default();
+    (0..list.len())
+        .map(|row| {
+            if list.is_null(row) {
+                return Ok(None);
+            }
+            let items = list.value(row);
+            let formatter = ArrayFormatter::try_new(items.as_ref(), &options)
+                .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+            let joined: Vec<String> = (0..items.len())
+                .map(|i| formatter.value(i).to_string())
+                .collect();
+            Ok(Some(joined.join(";")))
+        })
+        .collect()
+}
+
+/// Rebuilds each row's hex polygon from its hex id as WKT, reprojected to
+/// WGS84 if the batch's geometry column is in WGS84.
+fn summary_wkt(
+    batch: &RecordBatch,
+    names: &ColumnNames,
+    geometry_index: Option<usize>,
+) -> Result<Vec<String>, InfraHexError> {
+    let hex_ids = names.hex_ids(batch)?;
+
+    let schema = batch.schema();
+    let wgs84 = geometry_index.is_some_and(|i| geometry_crs(schema.field(i)) == Some(Crs::Wgs84));
+
+    (0..batch.num_rows())
+        .map(|row| {
+            let polygon = HexCell::from_hex_id(hex_ids.value(row))?.to_polygon();
+            Ok(if wgs84 {
+                polygon_bng_to_wgs84(&polygon).to_wkt()
+            } else {
+                polygon.to_wkt()
+            })
+        })
+        .collect()
+}
+
+/// Converts a hex summary to a GeoJSON FeatureCollection, one feature per row.
+///
+/// Each feature's geometry is the row's polygon in British National Grid
+/// (EPSG:27700) metres, and every other column (`hex_id`, `pipe_count` and
+/// any extras) becomes a property. GeoJSON consumers generally assume WGS84,
+/// so for web maps use [`hex_summary_to_geojson_wgs84`] instead.
+///
+/// Geometry comes from the `geometry` column (or the first GeoArrow column)
+/// when its CRS is recognised as BNG or WGS84, so clipped or otherwise
+/// edited polygons are kept. Otherwise each polygon is rebuilt from
+/// `hex_id`, failing with `InfraHexError::Config` if there is no such
+/// column.
+pub fn hex_summary_to_geojson(batch: &RecordBatch) -> Result<FeatureCollection, InfraHexError> {
+    summary_to_feature_collection(batch, &ColumnNames::default(), false)
+}
+
+/// Like [`hex_summary_to_geojson`], but with polygons reprojected to WGS84
+/// (EPSG:4326) so they render correctly in Leaflet, Mapbox and browsers.
+pub fn hex_summary_to_geojson_wgs84(
+    batch: &RecordBatch,
+) -> Result<FeatureCollection, InfraHexError> {
+    summary_to_feature_collection(batch, &ColumnNames::default(), true)
+}
+
+/// Like [`hex_summary_to_geojson`], for a summary whose hex id and geometry
+/// columns are named by `names`.
+pub fn hex_summary_to_geojson_with_columns(
+    batch: &RecordBatch,
+    names: &ColumnNames,
+) -> Result<FeatureCollection, InfraHexError> {
+    summary_to_feature_collection(batch, names, false)
+}
+
+/// Like [`hex_summary_to_geojson_wgs84`], for a summary whose hex id and
+/// geometry columns are named by `names`.
+pub fn hex_summary_to_geojson_wgs84_with_columns(
+    batch: &RecordBatch,
+    names: &ColumnNames,
+) -> Result<FeatureCollection, InfraHexError> {
+    summary_to_feature_collection(batch, names, true)
+}
+
+/// Converts a hex summary to GeoJSON features, one per row, in WGS84 when
+/// `wgs84` is set and BNG otherwise.
+fn summary_to_feature_collection(
+    batch: &RecordBatch,
+    names: &ColumnNames,
+    wgs84: bool,
+) -> Result<FeatureCollection, InfraHexError> {
+    let schema = batch.schema();
+    let geometry_index = schema
+        .index_of(&names.geometry)
+        .ok()
+        .filter(|&i| is_geometry_field(schema.field(i)))
+        .or_else(|| geometry_column_index(batch));
+
+    // Use the stored geometry when its CRS is known, else rebuild from ids
+    let (geometries, crs) =
+        match geometry_index.and_then(|i| Some((i, geometry_crs(schema.field(i))?))) {
+            Some((i, crs)) => (
+                decode_polygons(batch.column(i).as_ref(), schema.field(i))?,
+                crs,
+            ),
+            None => (hex_polygons(names.hex_ids(batch)?)?, Crs::Bng),
+        };
+
+    let property_columns: Vec<(&str, &dyn Array)> = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != geometry_index)
+        .map(|(i, field)| (field.name().as_str(), batch.column(i).as_ref()))
+        .collect();
+
+    let mut features = Vec::with_capacity(batch.num_rows());
+    for (row, geometry) in geometries.into_iter().enumerate() {
+        let geometry = geometry.map(|g| {
+            match (crs, wgs84) {
+                (Crs::Bng, true) => g.map_coords(bng_to_wgs84),
+                (Crs::Wgs84, false) => g.map_coords(wgs84_to_bng),
+                _ => g,
+            }
+            .to_geojson()
+        });
+
+        let mut properties = serde_json::Map::new();
+        for (name, column) in &property_columns {
+            properties.insert(name.to_string(), json_value(*column, row)?);
+        }
+
+        features.push(Feature {
+            bbox: None,
+            geometry,
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        });
+    }
+
+    Ok(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+}
+
+/// Rebuilds each row's hex polygon, in BNG, from its id.
+fn hex_polygons(hex_ids: &StringArray) -> Result<Vec<Option<Geometry<f64>>>, InfraHexError> {
+    hex_ids
+        .iter()
+        .map(|id| {
+            id.map(|id| Ok(Geometry::Polygon(HexCell::from_hex_id(id)?.to_polygon())))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Decodes a polygon or multipolygon GeoArrow column to one geometry per
+/// row, `None` for null rows.
+pub(crate) fn decode_polygons(
+    column: &dyn Array,
+    field: &Field,
+) -> Result<Vec<Option<Geometry<f64>>>, InfraHexError> {
+    let array =
+        from_arrow_array(column, field).map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+
+    if let Some(polygons) = array.as_polygon_opt() {
+        (0..column.len())
+            .map(|i| {
+                let polygon = polygons
+                    .get(i)
+                    .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+                Ok(polygon.map(|p| Geometry::Polygon(p.to_polygon())))
+            })
+            .collect()
+    } else if let Some(multi_polygons) = array.as_multi_polygon_opt() {
+        (0..column.len())
+            .map(|i| {
+                let multi_polygon = multi_polygons
+                    .get(i)
+                    .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+                Ok(multi_polygon.map(|p| Geometry::MultiPolygon(p.to_multi_polygon())))
+            })
+            .collect()
+    } else {
+        Err(InfraHexError::Config(format!(
+            "Geometry column {} does not hold polygons",
+            field.name()
+        )))
+    }
+}
+
+/// Converts one value of an Arrow column to JSON. Common scalar types map to
+/// native JSON values; anything else falls back to Arrow's display string.
+fn json_value(array: &dyn Array, row: usize) -> Result<serde_json::Value, InfraHexError> {
+    if array.is_null(row) {
+        return Ok(serde_json::Value::Null);
+    }
+
+    let any = array.as_any();
+    let value = if let Some(a) = any.downcast_ref::<StringArray>() {
+        serde_json::json!(a.value(row))
+    } else if let Some(a) = any.downcast_ref::<UInt32Array>() {
+        serde_json::json!(a.value(row))
+    } else if let Some(a) = any.downcast_ref::<UInt64Array>() {
+        serde_json::json!(a.value(row))
+    } else if let Some(a) = any.downcast_ref::<Int64Array>() {
+        serde_json::json!(a.value(row))
+    } else if let Some(a) = any.downcast_ref::<Float64Array>() {
+        serde_json::json!(a.value(row))
+    } else if let Some(a) = any.downcast_ref::<BooleanArray>() {
+        serde_json::json!(a.value(row))
+    } else {
+        let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())
+            .map_err(|e| InfraHexError::Geometry(e.to_string()))?;
+        serde_json::json!(formatter.value(row).to_string())
+    };
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+    use crate::core::{
+        HexSummaryOptions, read_geoparquet, to_hex_summary, to_hex_summary_with_options,
+    };
+    use geojson::Value;
+
+    fn make_summary_record() -> CadentPipelineRecord {
+        CadentPipelineRecord {
+            pipe_type: Some("MP".to_string()),
+            asset_id: Some("TEST-001".to_string()),
+            ..line_record(vec![vec![-2.2484, 53.4804], vec![-2.2502, 53.4806]])
+        }
+    }
+
+    fn make_summary() -> RecordBatch {
+        to_hex_summary(&[make_summary_record()], 12).unwrap()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("infra-hex-rs-sink-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_format_from_extension_geojson_roundtrip() {
+        let summary = make_summary();
+        let path = temp_path("summary.geojson");
+
+        format_from_extension(&path)
+            .unwrap()
+            .write(&summary)
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let collection: FeatureCollection = serde_json::from_str(&contents).unwrap();
+        assert_eq!(collection.features.len(), summary.num_rows());
+
+        let props = collection.features[0].properties.as_ref().unwrap();
+        assert!(props.contains_key("hex_id"));
+        assert!(props["pipe_count"].is_u64());
+        assert!(!props.contains_key("geometry"));
+    }
+
+    #[test]
+    fn test_format_from_extension_parquet_roundtrip() {
+        let summary = make_summary();
+        let path = temp_path("summary.parquet");
+
+        format_from_extension(&path)
+            .unwrap()
+            .write(&summary)
+            .unwrap();
+        let batches = read_geoparquet(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        assert_eq!(rows, summary.num_rows());
+    }
+
+    #[test]
+    fn test_geojson_coordinates_are_wgs84() {
+        let collection = hex_summary_to_geojson_wgs84(&make_summary()).unwrap();
+        assert!(collection.foreign_members.is_none());
+
+        for feature in &collection.features {
+            let Some(Value::Polygon(rings)) = feature.geometry.as_ref().map(|g| &g.value) else {
+                panic!("expected a polygon geometry");
+            };
+            for position in rings.iter().flatten() {
+                // Great Britain in lon/lat, nowhere near BNG metres
+                assert!((-9.0..=3.0).contains(&position[0]), "lon {}", position[0]);
+                assert!((49.0..=61.0).contains(&position[1]), "lat {}", position[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_summary_to_geojson_bng_properties() {
+        let summary = make_summary();
+        let collection = hex_summary_to_geojson(&summary).unwrap();
+        assert_eq!(collection.features.len(), summary.num_rows());
+
+        for feature in &collection.features {
+            let properties = feature.properties.as_ref().unwrap();
+            assert!(properties["hex_id"].is_string());
+            assert!(properties["pipe_count"].is_u64());
+            assert!(!properties.contains_key("geometry"));
+
+            let Some(Value::Polygon(rings)) = feature.geometry.as_ref().map(|g| &g.value) else {
+                panic!("expected a polygon geometry");
+            };
+            // BNG metres, nowhere near lon/lat degrees
+            for position in rings.iter().flatten() {
+                assert!(position[0] > 1_000.0 && position[1] > 1_000.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_geojson_uses_stored_geometry() {
+        let summary = make_summary();
+        let hex_id = summary.schema().index_of("hex_id").unwrap();
+        let without_ids = summary
+            .project(
+                &(0..summary.num_columns())
+                    .filter(|&i| i != hex_id)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        assert!(hex_summary_to_geojson(&without_ids).is_ok());
+
+        let rebuilt = hex_summary_to_geojson_wgs84(&summary).unwrap();
+        let stored = hex_summary_to_geojson_wgs84(&without_ids).unwrap();
+        assert_eq!(stored.features.len(), rebuilt.features.len());
+        for (a, b) in stored.features.iter().zip(&rebuilt.features) {
+            assert_eq!(a.geometry, b.geometry);
+            assert!(!a.properties.as_ref().unwrap().contains_key("hex_id"));
+        }
+
+        // Geometry already in WGS84 is written as-is, not reprojected again
+        let options = HexSummaryOptions::new().with_reproject_to_wgs84(true);
+        let wgs84_summary =
+            to_hex_summary_with_options(&[make_summary_record()], 12, &(), &options).unwrap();
+        let collection = hex_summary_to_geojson_wgs84(&wgs84_summary).unwrap();
+        for feature in &collection.features {
+            let Some(Value::Polygon(rings)) = feature.geometry.as_ref().map(|g| &g.value) else {
+                panic!("expected a polygon geometry");
+            };
+            for position in rings.iter().flatten() {
+                assert!((-9.0..=3.0).contains(&position[0]), "lon {}", position[0]);
+                assert!((49.0..=61.0).contains(&position[1]), "lat {}", position[1]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_geojson_sink_crs_member() {
+        let path = temp_path("crs.geojson");
+        GeoJsonSink::new(&path)
+            .with_crs_member(true)
+            .write(&make_summary())
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(
+            json["crs"]["properties"]["name"],
+            "urn:ogc:def:crs:OGC:1.3:CRS84"
+        );
+    }
+
+    #[test]
+    fn test_sinks_with_renamed_columns() {
+        let names = ColumnNames::default()
+            .with_hex_id("cell")
+            .with_geometry("geom");
+        let options = HexSummaryOptions::new().with_column_names(names.clone());
+        let summary =
+            to_hex_summary_with_options(&[make_summary_record()], 12, &(), &options).unwrap();
+
+        let collection = hex_summary_to_geojson_with_columns(&summary, &names).unwrap();
+        assert_eq!(collection.features.len(), summary.num_rows());
+        let properties = collection.features[0].properties.as_ref().unwrap();
+        assert!(properties["cell"].is_string());
+        assert!(!properties.contains_key("geom"));
+
+        // Without geometry, polygons are rebuilt from the renamed id column
+        let without_geometry = summary.project(&[0, 1, 2]).unwrap();
+        assert!(hex_summary_to_geojson(&without_geometry).is_err());
+        assert!(hex_summary_to_geojson_with_columns(&without_geometry, &names).is_ok());
+
+        let path = temp_path("renamed.csv");
+        CsvSink::new(&path)
+            .with_geometry_wkt(true)
+            .with_column_names(names.clone())
+            .write(&summary)
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(contents.starts_with("cell,pipe_count,total_length_m,geometry_wkt\n"));
+
+        let mut buf = Vec::new();
+        write_summary_to_writer_with_columns(&summary, OutputFormat::GeoJson, &names, &mut buf)
+            .unwrap();
+        let collection: FeatureCollection = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(collection.features.len(), summary.num_rows());
+    }
+
+    #[test]
+    fn test_write_summary_to_writer_csv() {
+        let summary = make_summary();
+        let mut buf = Vec::new();
+        write_summary_to_writer(&summary, OutputFormat::Csv, &mut buf).unwrap();
+
+        let csv = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "hex_id,pipe_count,total_length_m");
+        assert_eq!(lines.len(), summary.num_rows() + 1);
+    }
+
+    #[test]
+    fn test_write_hex_summary_csv_skips_geometry() {
+        let summary = make_summary();
+        let path = temp_path("summary.csv");
+        write_hex_summary_csv(&summary, &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "hex_id,pipe_count,total_length_m");
+        assert_eq!(lines.len(), summary.num_rows() + 1);
+    }
+
+    #[test]
+    fn test_csv_sink_geometry_wkt() {
+        let summary = make_summary();
+        let path = temp_path("wkt.csv");
+        CsvSink::new(&path)
+            .with_geometry_wkt(true)
+            .write(&summary)
+            .unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines[0], "hex_id,pipe_count,total_length_m,geometry_wkt");
+        for line in &lines[1..] {
+            // The WKT contains commas, so it is quoted
+            let wkt = line.split_once(",\"").unwrap().1;
+            assert!(wkt.starts_with("POLYGON(("), "{}", wkt);
+            assert!(wkt.ends_with("))\""), "{}", wkt);
+        }
+    }
+
+    #[test]
+    fn test_csv_joins_list_columns() {
+        let batch = crate::core::to_record_batch_no_geom(&[make_summary_record()], 12).unwrap();
+
+        let csv = summary_to_csv(&batch, None).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].contains("hex_ids"));
+
+        let hex_ids = batch
+            .column_by_name("hex_ids")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<ListArray>()
+            .unwrap()
+            .value(0);
+        let hex_ids = hex_ids.as_any().downcast_ref::<StringArray>().unwrap();
+        let joined: Vec<&str> = hex_ids.iter().flatten().collect();
+        assert!(lines[1].contains(&joined.join(";")), "{}", lines[1]);
+    }
+
+    #[test]
+    fn test_write_summary_to_writer_geojson() {
+        let summary = make_summary();
+        let mut buf = Vec::new();
+        write_summary_to_writer(&summary, OutputFormat::GeoJson, &mut buf).unwrap();
+
+        let collection: FeatureCollection = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(collection.features.len(), summary.num_rows());
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(vec![Field::new("note", DataType::Utf8, true)])),
+            vec![Arc::new(StringArray::from(vec![
+                Some("plain"),
+                Some("a,b"),
+                Some("say \"hi\""),
+                None,
+            ]))],
+        )
+        .unwrap();
+
+        let csv = summary_to_csv(&batch, None).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec!["note", "plain", "\"a,b\"", "\"say \"\"hi\"\"\"", ""]
+        );
+    }
+
+    #[test]
+    fn test_broken_pipe_is_not_an_error() {
+        struct ClosedPipe(ErrorKind);
+        impl Write for ClosedPipe {
+            fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+                Err(self.0.into())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        assert!(write_ignoring_broken_pipe(ClosedPipe(ErrorKind::BrokenPipe), b"data").is_ok());
+        assert!(write_ignoring_broken_pipe(ClosedPipe(ErrorKind::Other), b"data").is_err());
+    }
+
+    #[test]
+    fn test_format_from_extension_unknown() {
+        assert!(format_from_extension("summary.xlsx").is_err());
+        assert!(format_from_extension("summary").is_err());
+    }
+}
@@ -0,0 +1,161 @@
+use geo_types::{LineString, MultiPolygon, Polygon};
+use n3gb_rs::HexCell;
+
+// =============================================================================
+// ToWkt Trait - Encode geo_types as Well-Known Text
+// =============================================================================
+
+/// Trait for formatting geometries as WKT (Well-Known Text).
+///
+/// Handy for debugging and for pasting cells into QGIS or a SQL console.
+/// Coordinates are written as-is, so hex cells come out in British National
+/// Grid eastings and northings; no SRID is included.
+pub trait ToWkt {
+    /// Formats this geometry as WKT, e.g. `POLYGON((0 0,1 0,1 1,0 0))`.
+    fn to_wkt(&self) -> String;
+}
+
+impl ToWkt for Polygon<f64> {
+    fn to_wkt(&self) -> String {
+        if self.exterior().0.is_empty() {
+            return "POLYGON EMPTY".to_string();
+        }
+        format!("POLYGON{}", polygon_body(self))
+    }
+}
+
+impl ToWkt for MultiPolygon<f64> {
+    fn to_wkt(&self) -> String {
+        if self.0.is_empty() {
+            return "MULTIPOLYGON EMPTY".to_string();
+        }
+        let polygons: Vec<String> = self.0.iter().map(polygon_body).collect();
+        format!("MULTIPOLYGON({})", polygons.join(","))
+    }
+}
+
+impl ToWkt for HexCell {
+    fn to_wkt(&self) -> String {
+        self.to_polygon().to_wkt()
+    }
+}
+
+/// A set of cells becomes one `GEOMETRYCOLLECTION` of their polygons.
+impl ToWkt for [HexCell] {
+    fn to_wkt(&self) -> String {
+        if self.is_empty() {
+            return "GEOMETRYCOLLECTION EMPTY".to_string();
+        }
+        let polygons: Vec<String> = self.iter().map(ToWkt::to_wkt).collect();
+        format!("GEOMETRYCOLLECTION({})", polygons.join(","))
+    }
+}
+
+/// Formats a hex cell's BNG polygon as a WKT `POLYGON`.
+pub fn hexcell_to_wkt(cell: &HexCell) -> String {
+    cell.to_wkt()
+}
+
+/// Formats cells as newline-delimited WKT, one `POLYGON` per line, in the
+/// order given. QGIS and most WKT loaders accept this directly.
+pub fn hexcells_to_wkt_lines(cells: &[HexCell]) -> String {
+    let lines: Vec<String> = cells.iter().map(ToWkt::to_wkt).collect();
+    lines.join("\n")
+}
+
+// =============================================================================
+// Helper Functions
+// =============================================================================
+
+/// Formats a polygon's rings, e.g. `((0 0,1 0,1 1,0 0))`, or `EMPTY` for a
+/// polygon with no exterior ring.
+fn polygon_body(polygon: &Polygon<f64>) -> String {
+    if polygon.exterior().0.is_empty() {
+        return "EMPTY".to_string();
+    }
+    let rings: Vec<String> = std::iter::once(polygon.exterior())
+        .chain(polygon.interiors())
+        .map(ring)
+        .collect();
+    format!("({})", rings.join(","))
+}
+
+fn ring(line: &LineString<f64>) -> String {
+    let coords: Vec<String> = line.coords().map(|c| format!("{} {}", c.x, c.y)).collect();
+    format!("({})", coords.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geo_types::Coord;
+
+    fn square() -> Polygon<f64> {
+        Polygon::new(
+            LineString::new(vec![
+                Coord { x: 0.0, y: 0.0 },
+                Coord { x: 1.5, y: 0.0 },
+                Coord { x: 1.5, y: 2.0 },
+                Coord { x: 0.0, y: 0.0 },
+            ]),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_polygon_and_multipolygon_wkt() {
+        assert_eq!(square().to_wkt(), "POLYGON((0 0,1.5 0,1.5 2,0 0))");
+        assert_eq!(
+            MultiPolygon::new(vec![square(), square()]).to_wkt(),
+            "MULTIPOLYGON(((0 0,1.5 0,1.5 2,0 0)),((0 0,1.5 0,1.5 2,0 0)))"
+        );
+        assert_eq!(MultiPolygon::new(vec![]).to_wkt(), "MULTIPOLYGON EMPTY");
+    }
+
+    #[test]
+    fn test_empty_polygon_wkt() {
+        let empty = Polygon::new(LineString::new(vec![]), vec![]);
+        assert_eq!(empty.to_wkt(), "POLYGON EMPTY");
+        assert_eq!(
+            MultiPolygon::new(vec![empty, square()]).to_wkt(),
+            "MULTIPOLYGON(EMPTY,((0 0,1.5 0,1.5 2,0 0)))"
+        );
+    }
+
+    #[test]
+    fn test_hexcell_to_wkt_ring_is_closed() {
+        let cell = HexCell::from_bng(&geo_types::Point::new(383_500.0, 398_200.0), 10).unwrap();
+        let wkt = hexcell_to_wkt(&cell);
+
+        let body = wkt
+            .strip_prefix("POLYGON((")
+            .and_then(|w| w.strip_suffix("))"))
+            .unwrap();
+        let coords: Vec<&str> = body.split(',').collect();
+        assert_eq!(coords.len(), cell.to_polygon().exterior().0.len());
+        assert_eq!(coords.first(), coords.last());
+        for coord in coords {
+            let xy: Vec<f64> = coord.split(' ').map(|v| v.parse().unwrap()).collect();
+            assert_eq!(xy.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_cells_to_collection_and_lines() {
+        let cells: Vec<HexCell> = [383_500.0, 385_000.0]
+            .iter()
+            .map(|&x| HexCell::from_bng(&geo_types::Point::new(x, 398_200.0), 10).unwrap())
+            .collect();
+
+        let collection = cells.to_wkt();
+        assert!(collection.starts_with("GEOMETRYCOLLECTION(POLYGON(("));
+        assert_eq!(collection.matches("POLYGON").count(), 2);
+
+        let lines = hexcells_to_wkt_lines(&cells);
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines, vec![cells[0].to_wkt(), cells[1].to_wkt()]);
+
+        let empty: &[HexCell] = &[];
+        assert_eq!(empty.to_wkt(), "GEOMETRYCOLLECTION EMPTY");
+    }
+}
@@ -0,0 +1,217 @@
+use geo::Area;
+use geo_types::Point;
+use n3gb_rs::HexCell;
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+use crate::client::PipelineData;
+use crate::error::InfraHexError;
+
+use super::hex::get_hex_cells;
+
+/// Highest zoom level supported by the n3gb grid.
+const MAX_ZOOM: u8 = 15;
+
+/// Number of records probed when estimating cell counts.
+const SAMPLE_SIZE: usize = 500;
+
+/// Approximate on-the-ground size of an n3gb cell at one zoom level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellDimensions {
+    pub zoom: u8,
+    /// Mean edge length of the cell's BNG polygon, in metres.
+    pub edge_length_m: f64,
+    /// Area of the cell's BNG polygon, in square metres.
+    pub area_m2: f64,
+}
+
+/// Reports the size of a cell at `zoom`, to help pick a zoom level (see also
+/// [`suggest_zoom`]).
+///
+/// Measured from the BNG polygon of one sample cell in central Great
+/// Britain. Cells at a given zoom share a size across the grid, so the
+/// figures apply everywhere. No data is involved.
+///
+/// # Errors
+///
+/// Returns [`InfraHexError::HexGrid`] if `zoom` is not a valid n3gb zoom.
+pub fn hex_cell_dimensions(zoom: u8) -> Result<CellDimensions, InfraHexError> {
+    let cell = HexCell::from_wgs84(&Point::new(-2.0, 54.0), zoom)?;
+    let polygon = cell.to_polygon();
+
+    let edges: Vec<f64> = polygon
+        .exterior()
+        .lines()
+        .map(|line| (line.end.x - line.start.x).hypot(line.end.y - line.start.y))
+        .collect();
+    let edge_length_m = edges.iter().sum::<f64>() / edges.len().max(1) as f64;
+
+    Ok(CellDimensions {
+        zoom,
+        edge_length_m,
+        area_m2: polygon.unsigned_area(),
+    })
+}
+
+/// Suggests a zoom level that yields roughly `target_cells` occupied hexes.
+///
+/// This is a heuristic: an evenly spaced sample of at most 500 records is
+/// hexed at a handful of zoom levels (binary search, since occupied cell
+/// counts grow with zoom) and the distinct cell count is scaled up by the
+/// sampling ratio. Overlapping pipes share cells, so dense inputs tend to be
+/// overestimated and the suggestion errs on the coarse side.
+///
+/// Of the two zoom levels either side of the target, the one whose estimate
+/// is closest in ratio terms is returned.
+///
+/// # Errors
+///
+/// Returns [`InfraHexError::Config`] if `records` is empty, or any error from
+/// hexing the sampled records.
+pub fn suggest_zoom<T: PipelineData>(
+    records: &[T],
+    target_cells: usize,
+) -> Result<u8, InfraHexError> {
+    if records.is_empty() {
+        return Err(InfraHexError::Config(
+            "Cannot suggest a zoom level without records".to_string(),
+        ));
+    }
+
+    let sample = sample_records(records, SAMPLE_SIZE);
+    let scale = records.len() as f64 / sample.len() as f64;
+    let estimate = |zoom: u8| -> Result<f64, InfraHexError> {
+        Ok(count_distinct_cells(&sample, zoom)? as f64 * scale)
+    };
+
+    // Find the first zoom whose estimate reaches the target
+    let target = target_cells.max(1) as f64;
+    let (mut lo, mut hi) = (0u8, MAX_ZOOM);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if estimate(mid)? >= target {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    if lo == 0 {
+        return Ok(0);
+    }
+
+    let distance = |count: f64| (count.max(1.0) / target).ln().abs();
+    if distance(estimate(lo)?) <= distance(estimate(lo - 1)?) {
+        Ok(lo)
+    } else {
+        Ok(lo - 1)
+    }
+}
+
+/// Counts the distinct hex cells the records occupy at `zoom`.
+///
+/// This is the row count `to_hex_summary_no_geom` would produce, computed
+/// without building any Arrow arrays or geometry, so callers can check a zoom
+/// level yields a manageable output before committing to it. Unlike
+/// [`suggest_zoom`], every record is hexed, so the count is exact.
+pub fn estimate_cell_count<T: PipelineData>(
+    records: &[T],
+    zoom: u8,
+) -> Result<usize, InfraHexError> {
+    let all: Vec<&T> = records.iter().collect();
+    count_distinct_cells(&all, zoom)
+}
+
+/// Picks at most `max` records spread evenly across the input.
+fn sample_records<T>(records: &[T], max: usize) -> Vec<&T> {
+    let step = records.len().div_ceil(max).max(1);
+    records.iter().step_by(step).collect()
+}
+
+/// Counts the distinct hex cells touched by the given records.
+fn count_distinct_cells<T: PipelineData>(records: &[&T], zoom: u8) -> Result<usize, InfraHexError> {
+    let cells_per_pipe: Vec<_> = records
+        .par_iter()
+        .map(|record| get_hex_cells(*record, zoom))
+        .collect::<Result<_, _>>()?;
+
+    let ids: HashSet<&str> = cells_per_pipe
+        .iter()
+        .flatten()
+        .map(|cell| cell.id.as_str())
+        .collect();
+    Ok(ids.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::CadentPipelineRecord;
+    use crate::client::cadent::fixtures::line_record;
+
+    fn make_records() -> Vec<CadentPipelineRecord> {
+        (0..20)
+            .map(|i| {
+                let lat = 53.47 + i as f64 * 0.001;
+                line_record(vec![vec![-2.26, lat], vec![-2.24, lat]])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hex_cell_dimensions_shrink_with_zoom() {
+        let dimensions: Vec<CellDimensions> = (0..=MAX_ZOOM)
+            .map(|zoom| hex_cell_dimensions(zoom).unwrap())
+            .collect();
+
+        for pair in dimensions.windows(2) {
+            assert!(pair[1].edge_length_m < pair[0].edge_length_m);
+            assert!(pair[1].area_m2 < pair[0].area_m2);
+        }
+        assert!(dimensions.iter().all(|d| d.area_m2 > 0.0));
+    }
+
+    #[test]
+    fn test_sample_records_caps_size() {
+        let values: Vec<u32> = (0..1234).collect();
+        let sample = sample_records(&values, 500);
+        assert!(sample.len() <= 500);
+        assert_eq!(*sample[0], 0);
+
+        let small = sample_records(&values[..10], 500);
+        assert_eq!(small.len(), 10);
+    }
+
+    #[test]
+    fn test_suggest_zoom_empty_records_errors() {
+        let records: Vec<CadentPipelineRecord> = Vec::new();
+        assert!(suggest_zoom(&records, 100).is_err());
+    }
+
+    #[test]
+    fn test_estimate_cell_count_matches_summary_rows() {
+        let records = make_records();
+
+        let count = estimate_cell_count(&records, 10).unwrap();
+        let summary = crate::core::to_hex_summary_no_geom(&records, 10).unwrap();
+
+        assert_eq!(count, summary.num_rows());
+    }
+
+    #[test]
+    fn test_estimate_cell_count_empty() {
+        let records: Vec<CadentPipelineRecord> = Vec::new();
+        assert_eq!(estimate_cell_count(&records, 10).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_suggest_zoom_increases_with_target() {
+        let records = make_records();
+
+        let coarse = suggest_zoom(&records, 10).unwrap();
+        let fine = suggest_zoom(&records, 10_000).unwrap();
+
+        assert!(coarse <= fine);
+        assert!(fine <= MAX_ZOOM);
+    }
+}
@@ -11,6 +11,9 @@ pub enum InfraHexError {
     #[error("API error: {0}")]
     Api(String),
 
+    #[error("Authentication failed: {0}")]
+    Auth(String),
+
     #[error("Config error: {0}")]
     Config(String),
 
@@ -3,16 +3,47 @@ pub mod core;
 pub mod error;
 
 pub use client::{
-    ApiResponse, BBox, BuiltUpArea, BuiltUpAreaClient, CadentClient, CadentPipelineRecord,
-    GeoPoint2d, InfraClient, InfraResult, PipelineData, polygon_to_geojson,
+    ApiResponse, AuditEntry, AuditLog, BBox, BuiltUpArea, BuiltUpAreaClient, CadentClient,
+    CadentPipelineRecord, FetchPlan, FetchProgress, GeoPoint2d, InfraClient, InfraResult, Material,
+    OpenDataSoftClient, PipeFilter, PipelineData, ProgressCallback, RateLimiter, RegionStats,
+    RetryConfig, polygon_to_geojson, records_to_geojson,
 };
+#[cfg(feature = "h3")]
+pub use core::h3_resolution_for_zoom;
 pub use core::{
-    get_hex_cells, to_hex_summary, to_hex_summary_for_multipolygon,
+    BNG_SRID, BoundaryFilter, CellDimensions, ColumnNames, ComputeProgress, CountNormalisation,
+    Crs, CsvSink, FromGeoJson, GeoJsonSink, GeoParquetBatchWriter, GeoParquetOptions, GroupBy,
+    HexAggregator, HexCellOptions, HexIdFilter, HexSummaryOptions, LineStringZ, MVT_LAYER_NAME,
+    OutputFormat, PROGRESS_CHUNK_SIZE, ParquetCompression, ParquetSink, PointZ, RingOrientation,
+    SCHEMA_VERSION, SCHEMA_VERSION_KEY, StreamFetchReport, SummarySink, ToEwkb, ToGeoJson, ToWkt,
+    annotate_hexes_with_areas, annotate_hexes_with_areas_with_columns, bng_to_wgs84, coverage_gaps,
+    coverage_gaps_with_columns, dedup_by_asset_id, dedup_by_geometry, encode_mvt,
+    encode_mvt_with_columns, estimate_batch_memory, estimate_cell_count, filter_by_ground,
+    filter_by_material, filter_by_min_diameter, filter_by_pipe_type, filter_records_by_min_length,
+    format_from_extension, geometry_column_index, get_hex_cells, get_hex_cells_buffered_m,
+    get_hex_cells_with_crs, get_hex_cells_with_options, hex_cell_dimensions, hex_summary_schema,
+    hex_summary_to_geojson, hex_summary_to_geojson_wgs84,
+    hex_summary_to_geojson_wgs84_with_columns, hex_summary_to_geojson_with_columns, hexcell_to_wkt,
+    hexcells_to_wkt_lines, merge_hex_summaries, merge_hex_summaries_with_columns,
+    multipolygon_bng_to_wgs84, multipolygon_wgs84_to_bng, normalise_winding, parse_hex_id,
+    pipe_length_m, polygon_bng_to_wgs84, polygon_wgs84_to_bng, read_and_merge_geoparquet,
+    read_geoparquet, read_geoparquet_batches, read_geoparquet_lenient, schema_to_json,
+    stream_fetch_to_parquet, suggest_zoom, to_hex_summary, to_hex_summary_buffered_m,
+    to_hex_summary_by_material, to_hex_summary_by_material_no_geom, to_hex_summary_by_type,
+    to_hex_summary_by_type_no_geom, to_hex_summary_for_multipolygon,
     to_hex_summary_for_multipolygon_no_geom, to_hex_summary_for_polygon,
-    to_hex_summary_for_polygon_no_geom, to_hex_summary_no_geom, to_record_batch,
-    to_record_batch_for_multipolygon, to_record_batch_for_multipolygon_no_geom,
+    to_hex_summary_for_polygon_no_geom, to_hex_summary_grouped, to_hex_summary_grouped_no_geom,
+    to_hex_summary_no_geom, to_hex_summary_with_options, to_hex_summary_with_progress,
+    to_record_batch, to_record_batch_for_multipolygon, to_record_batch_for_multipolygon_no_geom,
     to_record_batch_for_polygon, to_record_batch_for_polygon_no_geom, to_record_batch_no_geom,
-    write_geoparquet, FromGeoJson, ToGeoJson,
+    to_record_batch_with_options, to_record_batches, to_record_hex_pairs,
+    to_record_hex_pairs_no_geom, to_record_hex_pairs_with_options, wgs84_to_bng, would_fit,
+    write_geoparquet, write_geoparquet_batches, write_geoparquet_parallel,
+    write_geoparquet_parallel_to_writer, write_geoparquet_parallel_to_writer_with_options,
+    write_geoparquet_parallel_with_options, write_geoparquet_to_bytes, write_geoparquet_to_writer,
+    write_geoparquet_with_options, write_hex_summary_csv, write_mvt, write_mvt_with_columns,
+    write_summary_stdout, write_summary_stdout_with_columns, write_summary_to_writer,
+    write_summary_to_writer_with_columns,
 };
 pub use error::InfraHexError;
 
@@ -24,7 +55,7 @@ mod tests {
     use geo::BoundingRect;
 
     use crate::{
-        BBox, BuiltUpAreaClient, CadentClient, InfraClient, InfraHexError,
+        BBox, BuiltUpAreaClient, CadentClient, InfraClient, InfraHexError, estimate_batch_memory,
         to_hex_summary_for_multipolygon, write_geoparquet,
     };
 
@@ -87,11 +118,7 @@ mod tests {
             );
         }
 
-        let mem_size: usize = summary
-            .columns()
-            .iter()
-            .map(|c| c.get_array_memory_size())
-            .sum();
+        let mem_size = estimate_batch_memory(&summary);
         println!("Memory size: {:.2} KB", mem_size as f64 / 1024.0);
 
         if summary.num_rows() > 0 {